@@ -0,0 +1,156 @@
+//! Frame cost on a 400x120 `TestBackend` terminal with a long snake.
+//!
+//! Run with `cargo bench --bench render`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use terminal_snake::background::BackgroundChoice;
+use terminal_snake::config::{GridSize, Theme, fallback_theme, glyphs};
+use terminal_snake::game::GameState;
+use terminal_snake::input::Direction;
+use terminal_snake::keymap::{KeyBindings, KeyPreset};
+use terminal_snake::renderer::{self, FrameLayout, MenuUiState, RenderCache};
+use terminal_snake::snake::{Position, Snake};
+use terminal_snake::theme::AppearancePreference;
+use terminal_snake::ui::hud::{HudInfo, HudValueFlash};
+
+const WIDTH: u16 = 400;
+const HEIGHT: u16 = 120;
+
+struct Scene {
+    terminal: Terminal<TestBackend>,
+    theme: Theme,
+    bindings: KeyBindings,
+}
+
+impl Scene {
+    fn new() -> Self {
+        Self {
+            terminal: Terminal::new(TestBackend::new(WIDTH, HEIGHT)).expect("test backend"),
+            theme: fallback_theme(),
+            bindings: KeyBindings::default(),
+        }
+    }
+
+    fn draw(&mut self, state: &GameState, cache: &mut RenderCache) -> FrameLayout {
+        let mut layout = FrameLayout::default();
+        let (theme, bindings) = (&self.theme, &self.bindings);
+        self.terminal
+            .draw(|frame| {
+                layout = renderer::render(
+                    frame,
+                    state,
+                    HudInfo {
+                        high_score: 0,
+                        game_over_reference_high_score: 0,
+                        theme,
+                        debug: false,
+                        debug_line: String::new(),
+                        now: Instant::now(),
+                        value_flash: HudValueFlash::default(),
+                        toast: None,
+                    },
+                    MenuUiState {
+                        start_selected_idx: 0,
+                        start_settings_open: false,
+                        start_settings_selected_idx: 0,
+                        start_speed_level: 1,
+                        start_speed_adjust_mode: false,
+                        appearance: AppearancePreference::Auto,
+                        background: BackgroundChoice::Theme,
+                        game_border_enabled: true,
+                        shape_coding: false,
+                        play_area_too_small: false,
+                        pause_selected_idx: 0,
+                        game_over_selected_idx: 0,
+                        start_theme_select: None,
+                        pause_theme_select: None,
+                        theme_editor: None,
+                        key_editor: None,
+                        key_preset: KeyPreset::Default,
+                        bindings,
+                        mouse_steering: false,
+                    },
+                    cache,
+                );
+            })
+            .expect("test backend draw");
+        layout
+    }
+}
+
+/// Builds a game sized to the gameplay viewport whose snake fills the top
+/// half of the board back and forth, heading down into free space.
+fn half_covered_state(scene: &mut Scene) -> GameState {
+    let probe = GameState::new_with_seed(GridSize::default(), 0);
+    let area = scene
+        .draw(&probe, &mut RenderCache::default())
+        .gameplay_area;
+    let bounds = glyphs()
+        .mode
+        .grid_size_for_viewport(area.width, area.height);
+
+    let width = i32::from(bounds.width);
+    let mut segments: Vec<Position> = (0..i32::from(bounds.height) / 2)
+        .flat_map(|y| {
+            let xs: Box<dyn Iterator<Item = i32>> = if y % 2 == 0 {
+                Box::new(0..width)
+            } else {
+                Box::new((0..width).rev())
+            };
+            xs.map(move |x| Position { x, y })
+        })
+        .collect();
+    segments.reverse();
+
+    let mut state = GameState::new_with_seed(bounds, 7);
+    state.snake = Snake::from_segments(segments, Direction::Down).expect("snake has segments");
+    state
+        .foods
+        .retain(|food| !state.snake.occupies(food.position));
+    state
+}
+
+fn bench_frames(c: &mut Criterion) {
+    let mut group = c.benchmark_group("render_400x120");
+    let mut scene = Scene::new();
+    let state = half_covered_state(&mut scene);
+    let mut cache = RenderCache::default();
+    scene.draw(&state, &mut cache);
+
+    group.bench_function("idle_frame", |b| {
+        b.iter(|| black_box(scene.draw(&state, &mut cache)));
+    });
+
+    let mut warmup = Scene::new();
+    group.bench_function("tick_frame", |b| {
+        b.iter_batched(
+            // A clone's snake has no spare capacity, so the first tick after
+            // it reallocates; keep that out of the timing.
+            || {
+                let (mut state, mut cache) = (state.clone(), cache.clone());
+                state.tick();
+                warmup.draw(&state, &mut cache);
+                (state, cache)
+            },
+            |(mut state, mut cache)| {
+                state.tick();
+                scene.draw(&state, &mut cache);
+                (state, cache)
+            },
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.bench_function("uncached_frame", |b| {
+        b.iter(|| black_box(scene.draw(&state, &mut RenderCache::default())));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_frames);
+criterion_main!(benches);
@@ -0,0 +1,87 @@
+//! Tick cost on a 500x200 board with the snake covering most of it.
+//!
+//! Run with `cargo bench --bench tick`.
+
+use std::hint::black_box;
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use terminal_snake::config::GridSize;
+use terminal_snake::food::Food;
+use terminal_snake::game::GameState;
+use terminal_snake::input::Direction;
+use terminal_snake::snake::{Position, Snake};
+
+const BOUNDS: GridSize = GridSize {
+    width: 500,
+    height: 200,
+};
+
+/// Builds a state whose snake fills the top `coverage` of the board in a
+/// back-and-forth pattern, with its head at the end of the last full row
+/// facing down into free space. With `food_ahead`, food sits two cells
+/// below the head so it is eaten on the second tick.
+fn covered_state(coverage: f64, food_ahead: bool) -> GameState {
+    let rows = (f64::from(BOUNDS.height) * coverage) as i32;
+    let width = i32::from(BOUNDS.width);
+    let mut segments: Vec<Position> = (0..rows)
+        .flat_map(|y| {
+            let xs: Box<dyn Iterator<Item = i32>> = if y % 2 == 0 {
+                Box::new(0..width)
+            } else {
+                Box::new((0..width).rev())
+            };
+            xs.map(move |x| Position { x, y })
+        })
+        .collect();
+    segments.reverse();
+
+    let mut state = GameState::new_with_seed(BOUNDS, 7);
+    state.snake = Snake::from_segments(segments, Direction::Down).expect("snake has segments");
+    state
+        .foods
+        .retain(|food| !state.snake.occupies(food.position));
+    // One tick fits the occupancy grid to the board before measuring.
+    state.tick();
+
+    let head = state.snake.head();
+    let below: Vec<Position> = (1..=2)
+        .map(|dy| Position {
+            x: head.x,
+            y: head.y + dy,
+        })
+        .collect();
+    state.foods.retain(|food| !below.contains(&food.position));
+    if food_ahead {
+        state.foods.push(Food::new(below[1]));
+    }
+    state
+}
+
+fn bench_ticks(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tick_500x200");
+    for (label, coverage) in [("50pct", 0.5), ("90pct", 0.9)] {
+        for (case, food_ahead) in [("move", false), ("eat", true)] {
+            let state = covered_state(coverage, food_ahead);
+            group.bench_function(format!("{label}_{case}"), |b| {
+                b.iter_batched(
+                    // A clone's body has no spare capacity, so the first
+                    // tick after it reallocates; keep that out of the timing.
+                    || {
+                        let mut state = state.clone();
+                        state.tick();
+                        state
+                    },
+                    |mut state| {
+                        state.tick();
+                        black_box(state)
+                    },
+                    BatchSize::LargeInput,
+                );
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_ticks);
+criterion_main!(benches);
@@ -0,0 +1,307 @@
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use crate::config::GridSize;
+
+/// Side length, in logical cells, of one checkerboard tile.
+const CHECKER_TILE: usize = 6;
+/// Thickness, in logical cells, of each stripe.
+const STRIPE_WIDTH: usize = 3;
+/// Distance, in logical cells, between dots of the dot grid.
+const DOT_SPACING: usize = 4;
+/// Characters of an ASCII-art background from no ink to full ink.
+const ART_RAMP: &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
+
+/// Pattern painted across empty play-field cells.
+///
+/// Every pattern shades each cell somewhere between the theme's `field_bg`
+/// (0.0) and `field_bg_alt` (1.0), so it follows the theme's colors.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum BackgroundPattern {
+    /// `field_bg` everywhere.
+    Plain,
+    /// Alternating square tiles.
+    #[default]
+    Checkerboard,
+    HorizontalStripes,
+    VerticalStripes,
+    /// Single `field_bg_alt` cells on a regular grid.
+    DotGrid,
+    /// `field_bg` in the middle fading to `field_bg_alt` at the corners.
+    Vignette,
+    /// `field_bg` at the top fading to `field_bg_alt` at the bottom.
+    Gradient,
+    /// An ASCII-art picture stretched over the field.
+    Image {
+        path: PathBuf,
+        art: AsciiArt,
+    },
+}
+
+impl BackgroundPattern {
+    /// Returns how far the cell at `col`, `row` is shaded toward
+    /// `field_bg_alt`, from 0.0 to 1.0.
+    #[must_use]
+    pub fn shade(&self, col: usize, row: usize, bounds: GridSize) -> f32 {
+        let on = |hit: bool| if hit { 1.0 } else { 0.0 };
+        match self {
+            Self::Plain => 0.0,
+            Self::Checkerboard => on(!(col / CHECKER_TILE + row / CHECKER_TILE).is_multiple_of(2)),
+            Self::HorizontalStripes => on(!(row / STRIPE_WIDTH).is_multiple_of(2)),
+            Self::VerticalStripes => on(!(col / STRIPE_WIDTH).is_multiple_of(2)),
+            Self::DotGrid => {
+                let middle = DOT_SPACING / 2;
+                on(col % DOT_SPACING == middle && row % DOT_SPACING == middle)
+            }
+            Self::Vignette => {
+                let offset =
+                    |pos: usize, len: u16| (pos as f32 + 0.5) / f32::from(len.max(1)) * 2.0 - 1.0;
+                let dx = offset(col, bounds.width);
+                let dy = offset(row, bounds.height);
+                ((dx * dx + dy * dy) / 2.0).min(1.0)
+            }
+            Self::Gradient => {
+                let last = bounds.height.saturating_sub(1).max(1);
+                (row as f32 / f32::from(last)).min(1.0)
+            }
+            Self::Image { art, .. } => art.sample(col, row, bounds),
+        }
+    }
+}
+
+/// A text picture whose characters are read as ink density.
+///
+/// Characters of ` .:-=+*#%@` map from no ink to full ink; any other
+/// non-blank character counts as full ink.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AsciiArt {
+    /// Ink level per character, as an index into the density ramp.
+    rows: Vec<Vec<u8>>,
+    width: usize,
+}
+
+impl AsciiArt {
+    /// Reads an ASCII-art file.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        fs::read_to_string(path).map(|text| Self::parse(&text))
+    }
+
+    /// Parses ASCII art, dropping trailing blank lines. Tabs count as blanks.
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let mut rows = text
+            .lines()
+            .map(|line| line.chars().map(ink_level).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        while rows
+            .last()
+            .is_some_and(|row| row.iter().all(|&level| level == 0))
+        {
+            rows.pop();
+        }
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        Self { rows, width }
+    }
+
+    /// Width and height of the picture in characters.
+    #[must_use]
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.rows.len())
+    }
+
+    /// Returns the ink at the picture position nearest to `col`, `row` when
+    /// the picture is stretched over `bounds`, from 0.0 to 1.0.
+    #[must_use]
+    pub fn sample(&self, col: usize, row: usize, bounds: GridSize) -> f32 {
+        let (width, height) = self.size();
+        if width == 0 || height == 0 || bounds.width == 0 || bounds.height == 0 {
+            return 0.0;
+        }
+        let art_row = row * height / usize::from(bounds.height);
+        let art_col = col * width / usize::from(bounds.width);
+        let level = self
+            .rows
+            .get(art_row)
+            .and_then(|cells| cells.get(art_col))
+            .copied()
+            .unwrap_or(0);
+        f32::from(level) / (ART_RAMP.len() - 1) as f32
+    }
+}
+
+fn ink_level(ch: char) -> u8 {
+    if ch == '\t' {
+        return 0;
+    }
+    ART_RAMP
+        .iter()
+        .position(|&ramp| ramp == ch)
+        .unwrap_or(ART_RAMP.len() - 1) as u8
+}
+
+/// Background setting chosen by the user; `Theme` uses the theme's own
+/// pattern.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum BackgroundChoice {
+    #[default]
+    Theme,
+    Off,
+    Checkerboard,
+    HorizontalStripes,
+    VerticalStripes,
+    DotGrid,
+    Vignette,
+    Gradient,
+}
+
+impl BackgroundChoice {
+    /// Returns the pattern to draw, given the current theme's pattern.
+    #[must_use]
+    pub fn resolve(self, theme_pattern: &BackgroundPattern) -> &BackgroundPattern {
+        match self {
+            Self::Theme => theme_pattern,
+            Self::Off => &BackgroundPattern::Plain,
+            Self::Checkerboard => &BackgroundPattern::Checkerboard,
+            Self::HorizontalStripes => &BackgroundPattern::HorizontalStripes,
+            Self::VerticalStripes => &BackgroundPattern::VerticalStripes,
+            Self::DotGrid => &BackgroundPattern::DotGrid,
+            Self::Vignette => &BackgroundPattern::Vignette,
+            Self::Gradient => &BackgroundPattern::Gradient,
+        }
+    }
+
+    /// Returns the next choice in settings-menu order.
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Theme => Self::Off,
+            Self::Off => Self::Checkerboard,
+            Self::Checkerboard => Self::HorizontalStripes,
+            Self::HorizontalStripes => Self::VerticalStripes,
+            Self::VerticalStripes => Self::DotGrid,
+            Self::DotGrid => Self::Vignette,
+            Self::Vignette => Self::Gradient,
+            Self::Gradient => Self::Theme,
+        }
+    }
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Theme => "Theme",
+            Self::Off => "Off",
+            Self::Checkerboard => "Checker",
+            Self::HorizontalStripes => "H-Stripes",
+            Self::VerticalStripes => "V-Stripes",
+            Self::DotGrid => "Dots",
+            Self::Vignette => "Vignette",
+            Self::Gradient => "Gradient",
+        }
+    }
+}
+
+impl std::str::FromStr for BackgroundChoice {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            value if value.eq_ignore_ascii_case("theme") => Ok(Self::Theme),
+            value if value.eq_ignore_ascii_case("off") => Ok(Self::Off),
+            value if value.eq_ignore_ascii_case("checkerboard") => Ok(Self::Checkerboard),
+            value if value.eq_ignore_ascii_case("hstripes") => Ok(Self::HorizontalStripes),
+            value if value.eq_ignore_ascii_case("vstripes") => Ok(Self::VerticalStripes),
+            value if value.eq_ignore_ascii_case("dots") => Ok(Self::DotGrid),
+            value if value.eq_ignore_ascii_case("vignette") => Ok(Self::Vignette),
+            value if value.eq_ignore_ascii_case("gradient") => Ok(Self::Gradient),
+            other => Err(format!(
+                "unknown background '{other}' (expected theme, off, checkerboard, hstripes, \
+                 vstripes, dots, vignette or gradient)"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AsciiArt, BackgroundChoice, BackgroundPattern};
+    use crate::config::GridSize;
+
+    const BOUNDS: GridSize = GridSize {
+        width: 12,
+        height: 12,
+    };
+
+    #[test]
+    fn tiled_patterns_alternate_between_field_colors() {
+        let shade = |pattern: &BackgroundPattern, col, row| pattern.shade(col, row, BOUNDS);
+
+        assert_eq!(shade(&BackgroundPattern::Checkerboard, 0, 0), 0.0);
+        assert_eq!(shade(&BackgroundPattern::Checkerboard, 6, 0), 1.0);
+        assert_eq!(shade(&BackgroundPattern::Checkerboard, 6, 6), 0.0);
+        assert_eq!(shade(&BackgroundPattern::HorizontalStripes, 9, 2), 0.0);
+        assert_eq!(shade(&BackgroundPattern::HorizontalStripes, 0, 3), 1.0);
+        assert_eq!(shade(&BackgroundPattern::VerticalStripes, 3, 0), 1.0);
+        assert_eq!(shade(&BackgroundPattern::DotGrid, 2, 6), 1.0);
+        assert_eq!(shade(&BackgroundPattern::DotGrid, 2, 5), 0.0);
+        assert_eq!(shade(&BackgroundPattern::Plain, 6, 0), 0.0);
+    }
+
+    #[test]
+    fn vignette_and_gradient_shade_smoothly() {
+        let vignette = BackgroundPattern::Vignette;
+        let center = vignette.shade(6, 6, BOUNDS);
+        let edge = vignette.shade(0, 6, BOUNDS);
+        let corner = vignette.shade(0, 0, BOUNDS);
+        assert!(center < 0.05);
+        assert!(center < edge && edge < corner);
+        assert!(corner <= 1.0);
+
+        let gradient = BackgroundPattern::Gradient;
+        assert_eq!(gradient.shade(5, 0, BOUNDS), 0.0);
+        assert_eq!(gradient.shade(5, 11, BOUNDS), 1.0);
+        assert!(gradient.shade(5, 4, BOUNDS) < gradient.shade(5, 8, BOUNDS));
+    }
+
+    #[test]
+    fn ascii_art_maps_ink_density_and_stretches_to_the_field() {
+        let art = AsciiArt::parse("@ \n.X\n\n");
+        assert_eq!(art.size(), (2, 2));
+
+        assert_eq!(art.sample(0, 0, BOUNDS), 1.0);
+        assert_eq!(art.sample(5, 5, BOUNDS), 1.0);
+        assert_eq!(art.sample(6, 0, BOUNDS), 0.0);
+        assert!((art.sample(0, 6, BOUNDS) - 1.0 / 9.0).abs() < 1e-6);
+        assert_eq!(art.sample(11, 11, BOUNDS), 1.0);
+
+        assert_eq!(AsciiArt::parse("").sample(0, 0, BOUNDS), 0.0);
+    }
+
+    #[test]
+    fn choice_cycles_and_resolves_against_theme_pattern() {
+        let mut choice = BackgroundChoice::Theme;
+        let mut seen = Vec::new();
+        loop {
+            seen.push(choice);
+            choice = choice.next();
+            if choice == BackgroundChoice::Theme {
+                break;
+            }
+        }
+        assert_eq!(seen.len(), 8);
+
+        let theme_pattern = BackgroundPattern::Vignette;
+        assert_eq!(
+            BackgroundChoice::Theme.resolve(&theme_pattern),
+            &BackgroundPattern::Vignette
+        );
+        assert_eq!(
+            BackgroundChoice::Off.resolve(&theme_pattern),
+            &BackgroundPattern::Plain
+        );
+        assert_eq!(
+            "DOTS".parse::<BackgroundChoice>(),
+            Ok(BackgroundChoice::DotGrid)
+        );
+        assert!("plaid".parse::<BackgroundChoice>().is_err());
+    }
+}
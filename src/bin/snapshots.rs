@@ -0,0 +1,95 @@
+use std::fs;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use terminal_snake::snapshot::{self, SNAPSHOT_DIR, SnapshotStatus};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "snapshots",
+    about = "Check or regenerate the renderer's golden snapshots"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Report snapshots that no longer match; exits non-zero if any differ.
+    Check,
+    /// Rewrite snapshots from the current renderer and delete stale ones.
+    Update,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let snapshots = snapshot::render_all();
+    let stale = match snapshot::stale_files(&snapshots) {
+        Ok(stale) => stale,
+        Err(error) => {
+            eprintln!("{SNAPSHOT_DIR}: error: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut failed = false;
+    match cli.command {
+        Command::Check => {
+            for snapshot in &snapshots {
+                match snapshot.compare() {
+                    Ok(SnapshotStatus::Matches) => {}
+                    Ok(SnapshotStatus::Missing) => {
+                        println!("missing  {}", snapshot.name);
+                        failed = true;
+                    }
+                    Ok(SnapshotStatus::Differs { line }) => {
+                        println!("differs  {} (line {line})", snapshot.name);
+                        failed = true;
+                    }
+                    Err(error) => {
+                        eprintln!("{}: error: {error}", snapshot.path().display());
+                        failed = true;
+                    }
+                }
+            }
+            for path in &stale {
+                println!("stale    {}", path.display());
+                failed = true;
+            }
+        }
+        Command::Update => {
+            if let Err(error) = fs::create_dir_all(SNAPSHOT_DIR) {
+                eprintln!("{SNAPSHOT_DIR}: error: {error}");
+                return ExitCode::FAILURE;
+            }
+            for snapshot in &snapshots {
+                if snapshot.compare().ok() == Some(SnapshotStatus::Matches) {
+                    continue;
+                }
+                match fs::write(snapshot.path(), &snapshot.contents) {
+                    Ok(()) => println!("updated  {}", snapshot.name),
+                    Err(error) => {
+                        eprintln!("{}: error: {error}", snapshot.path().display());
+                        failed = true;
+                    }
+                }
+            }
+            for path in &stale {
+                match fs::remove_file(path) {
+                    Ok(()) => println!("removed  {}", path.display()),
+                    Err(error) => {
+                        eprintln!("{}: error: {error}", path.display());
+                        failed = true;
+                    }
+                }
+            }
+        }
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
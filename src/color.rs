@@ -0,0 +1,287 @@
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+
+use crate::platform::ColorDepth;
+
+/// Channel levels of the xterm 6x6x6 color cube (indices 16–231).
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Named ANSI colors in xterm index order, paired with the RGB values used
+/// to approximate them.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 49, 49)),
+    (Color::Green, (13, 188, 121)),
+    (Color::Yellow, (229, 229, 16)),
+    (Color::Blue, (36, 114, 200)),
+    (Color::Magenta, (188, 63, 188)),
+    (Color::Cyan, (17, 168, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (102, 102, 102)),
+    (Color::LightRed, (241, 76, 76)),
+    (Color::LightGreen, (35, 209, 139)),
+    (Color::LightYellow, (245, 245, 67)),
+    (Color::LightBlue, (59, 142, 234)),
+    (Color::LightMagenta, (214, 112, 214)),
+    (Color::LightCyan, (41, 184, 219)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Maps `color` onto what a terminal with `depth` can display.
+///
+/// True color passes through unchanged, 256-color terminals get the nearest
+/// xterm palette index, 16-color terminals the nearest named ANSI color, and
+/// monochrome output drops every color in favor of the terminal default.
+#[must_use]
+pub fn quantize(color: Color, depth: ColorDepth) -> Color {
+    match (depth, color) {
+        (ColorDepth::TrueColor, color) | (_, color @ Color::Reset) => color,
+        (ColorDepth::Monochrome, _) => Color::Reset,
+        (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(rgb_to_xterm256(r, g, b)),
+        (ColorDepth::Ansi256, color) => color,
+        (ColorDepth::Ansi16, Color::Indexed(index)) if index < 16 => ANSI16[index as usize].0,
+        (ColorDepth::Ansi16, color) => match color_to_rgb(color) {
+            Some((r, g, b)) => nearest_ansi16(r, g, b),
+            None => color,
+        },
+    }
+}
+
+/// Quantizes every cell color in `buffer` in place. A no-op for true color.
+pub fn quantize_buffer(buffer: &mut Buffer, depth: ColorDepth) {
+    if depth == ColorDepth::TrueColor {
+        return;
+    }
+
+    for cell in &mut buffer.content {
+        cell.fg = quantize(cell.fg, depth);
+        cell.bg = quantize(cell.bg, depth);
+        cell.underline_color = quantize(cell.underline_color, depth);
+    }
+}
+
+/// Returns the RGB value a color is expected to render as, or `None` for
+/// the terminal default.
+#[must_use]
+pub fn color_to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    let rgb = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(index) => xterm_index_to_rgb(index),
+        Color::Reset => return None,
+        named => ANSI16
+            .iter()
+            .find(|(candidate, _)| *candidate == named)
+            .map(|(_, rgb)| *rgb)?,
+    };
+
+    Some(rgb)
+}
+
+/// Returns the standard xterm RGB value for a 256-color palette index.
+#[must_use]
+pub fn xterm_index_to_rgb(index: u8) -> (u8, u8, u8) {
+    if index < 16 {
+        const XTERM16: [(u8, u8, u8); 16] = [
+            (0, 0, 0),
+            (128, 0, 0),
+            (0, 128, 0),
+            (128, 128, 0),
+            (0, 0, 128),
+            (128, 0, 128),
+            (0, 128, 128),
+            (192, 192, 192),
+            (128, 128, 128),
+            (255, 0, 0),
+            (0, 255, 0),
+            (255, 255, 0),
+            (0, 0, 255),
+            (255, 0, 255),
+            (0, 255, 255),
+            (255, 255, 255),
+        ];
+        return XTERM16[index as usize];
+    }
+
+    if index <= 231 {
+        let i = index - 16;
+        let level = |v: u8| CUBE_LEVELS[v as usize];
+        return (level(i / 36), level((i % 36) / 6), level(i % 6));
+    }
+
+    let gray = 8 + (index - 232) * 10;
+    (gray, gray, gray)
+}
+
+/// Converts an RGB color to hue (degrees, `0..360`), saturation and
+/// lightness (both `0.0..=1.0`).
+#[must_use]
+pub fn rgb_to_hsl((r, g, b): (u8, u8, u8)) -> (f32, f32, f32) {
+    let (r, g, b) = (
+        f32::from(r) / 255.0,
+        f32::from(g) / 255.0,
+        f32::from(b) / 255.0,
+    );
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+    if delta == 0.0 {
+        return (0.0, 0.0, lightness);
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    (hue, saturation.min(1.0), lightness)
+}
+
+/// Converts hue (degrees), saturation and lightness back to RGB. Hue wraps
+/// around; saturation and lightness are clamped to `0.0..=1.0`.
+#[must_use]
+pub fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> (u8, u8, u8) {
+    let hue = hue.rem_euclid(360.0);
+    let saturation = saturation.clamp(0.0, 1.0);
+    let lightness = lightness.clamp(0.0, 1.0);
+
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match hue {
+        h if h < 60.0 => (chroma, x, 0.0),
+        h if h < 120.0 => (x, chroma, 0.0),
+        h if h < 180.0 => (0.0, chroma, x),
+        h if h < 240.0 => (0.0, x, chroma),
+        h if h < 300.0 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |value: f32| ((value + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+
+    (channel(r), channel(g), channel(b))
+}
+
+/// Finds the nearest xterm palette entry among the color cube and the
+/// grayscale ramp; the first 16 entries are skipped because terminals
+/// commonly remap them.
+fn rgb_to_xterm256(r: u8, g: u8, b: u8) -> u8 {
+    let cube_slot = |channel: u8| {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, level)| level.abs_diff(channel))
+            .map_or(0, |(slot, _)| slot as u8)
+    };
+    let (cr, cg, cb) = (cube_slot(r), cube_slot(g), cube_slot(b));
+    let cube_index = 16 + 36 * cr + 6 * cg + cb;
+
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let gray_step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray_index = 232 + gray_step;
+
+    if distance((r, g, b), xterm_index_to_rgb(gray_index))
+        < distance((r, g, b), xterm_index_to_rgb(cube_index))
+    {
+        gray_index
+    } else {
+        cube_index
+    }
+}
+
+fn nearest_ansi16(r: u8, g: u8, b: u8) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .map_or(Color::Reset, |(color, _)| *color)
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| u32::from(x.abs_diff(y)).pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::buffer::Buffer;
+    use ratatui::layout::Rect;
+    use ratatui::style::{Color, Style};
+
+    use super::{
+        hsl_to_rgb, quantize, quantize_buffer, rgb_to_hsl, rgb_to_xterm256, xterm_index_to_rgb,
+    };
+    use crate::platform::ColorDepth;
+
+    #[test]
+    fn xterm256_quantization_inverts_palette_lookup() {
+        for index in 16..=255u8 {
+            let (r, g, b) = xterm_index_to_rgb(index);
+            assert_eq!(
+                xterm_index_to_rgb(rgb_to_xterm256(r, g, b)),
+                (r, g, b),
+                "index {index} should round-trip"
+            );
+        }
+    }
+
+    #[test]
+    fn hsl_conversion_round_trips() {
+        for rgb in [
+            (255, 0, 0),
+            (0, 128, 64),
+            (224, 108, 117),
+            (40, 44, 52),
+            (200, 200, 200),
+        ] {
+            let (h, s, l) = rgb_to_hsl(rgb);
+            assert_eq!(hsl_to_rgb(h, s, l), rgb, "{rgb:?} should round-trip");
+        }
+        assert_eq!(rgb_to_hsl((0, 0, 255)), (240.0, 1.0, 0.5));
+        assert_eq!(hsl_to_rgb(480.0, 1.0, 0.5), (0, 255, 0));
+    }
+
+    #[test]
+    fn near_gray_prefers_grayscale_ramp() {
+        assert_eq!(rgb_to_xterm256(0x30, 0x30, 0x31), 236);
+    }
+
+    #[test]
+    fn ansi16_picks_nearest_named_color() {
+        assert_eq!(
+            quantize(Color::Rgb(200, 40, 40), ColorDepth::Ansi16),
+            Color::Red
+        );
+        assert_eq!(
+            quantize(Color::Indexed(231), ColorDepth::Ansi16),
+            Color::White
+        );
+        assert_eq!(quantize(Color::Indexed(1), ColorDepth::Ansi16), Color::Red);
+    }
+
+    #[test]
+    fn monochrome_drops_every_color() {
+        assert_eq!(
+            quantize(Color::Rgb(1, 2, 3), ColorDepth::Monochrome),
+            Color::Reset
+        );
+        assert_eq!(quantize(Color::Red, ColorDepth::Monochrome), Color::Reset);
+    }
+
+    #[test]
+    fn buffer_quantization_rewrites_cell_styles() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 2, 1));
+        buffer.set_string(
+            0,
+            0,
+            "x",
+            Style::new().fg(Color::Rgb(255, 0, 0)).bg(Color::Reset),
+        );
+
+        quantize_buffer(&mut buffer, ColorDepth::Ansi256);
+        assert_eq!(buffer[(0, 0)].fg, Color::Indexed(196));
+        assert_eq!(buffer[(0, 0)].bg, Color::Reset);
+    }
+}
@@ -1,11 +1,14 @@
 use ratatui::style::Color;
 use std::sync::OnceLock;
 
+use crate::background::BackgroundPattern;
+use crate::platform::ColorDepth;
+
 /// Logical grid dimensions passed through the game as a named type.
 ///
 /// Replaces the anonymous `(u16, u16)` tuple that was used for bounds,
 /// making width vs. height unambiguous at every call site.
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
 pub struct GridSize {
     pub width: u16,
     pub height: u16,
@@ -27,7 +30,7 @@ impl GridSize {
 ///
 /// UI fields (`ui_bg`, `ui_text`, `ui_accent`, `ui_muted`, `ui_bright`) style
 /// the HUD and menu panels. JSON theme keys match these field names 1:1.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Theme {
     pub name: String,
     /// Solid block color for the snake head segment.
@@ -55,8 +58,31 @@ pub struct Theme {
     pub ui_muted: Color,
     /// Brighter UI accent for standout secondary text and highlights.
     pub ui_bright: Color,
-    /// Alternate background for checkerboard tiles on the play field.
+    /// Alternate play-field background that background patterns shade toward.
     pub field_bg_alt: Color,
+    /// How body segments between the head and tail are colored.
+    pub snake_skin: SnakeSkin,
+    /// Pattern drawn across empty play-field cells.
+    pub background: BackgroundPattern,
+}
+
+/// Coloring style for the snake body, set by a theme's `snake_skin` section.
+///
+/// The head and tail keep `snake_head` and `snake_tail`; glow effects are
+/// blended on top of whatever color the skin produces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum SnakeSkin {
+    /// `snake_body`, with every other band of three segments slightly
+    /// reddened.
+    #[default]
+    Banded,
+    /// Colors blended across evenly spaced stops from head to tail.
+    Gradient(Vec<Color>),
+    /// Colors repeated down the body, each covering `width` segments.
+    Stripes { colors: Vec<Color>, width: usize },
+    /// One body color that moves through the stops as the snake grows,
+    /// reaching the last stop at `length` segments.
+    Length { colors: Vec<Color>, length: usize },
 }
 
 /// Emergency fallback theme used when no external/bundled themes load.
@@ -77,6 +103,8 @@ pub fn fallback_theme() -> Theme {
         ui_muted: Color::DarkGray,
         ui_bright: Color::Gray,
         field_bg_alt: Color::DarkGray,
+        snake_skin: SnakeSkin::Banded,
+        background: BackgroundPattern::Checkerboard,
     }
 }
 
@@ -104,40 +132,141 @@ pub const GLYPH_INDICATOR_DOWN: &str = "▼";
 /// Filled square marker used in HUD counters.
 pub const GLYPH_MARKER_SQUARE: &str = "■";
 
+/// First code point of the Unicode Braille Patterns block (blank pattern).
+pub const GLYPH_BRAILLE_BASE: u32 = 0x2800;
+
 /// Runtime-selected glyph mode.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum GlyphMode {
+    /// Half-block compositing: 1x2 logical cells per terminal cell.
     Unicode,
+    /// ASCII-safe fallback using the half-block layout, with one character
+    /// per entity.
     Ascii,
+    /// Braille sub-cell rendering: 2x4 logical cells per terminal cell.
+    Braille,
+    /// Square cells two terminal columns wide with per-entity Unicode glyphs.
+    Square,
+    /// ASCII-safe square cells with a distinct character pair per entity.
+    AsciiSquare,
+}
+
+/// How logical grid cells map onto terminal cells for a glyph mode.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CellGeometry {
+    /// Logical columns packed into one terminal column.
+    pub cols_per_term_cell: u16,
+    /// Logical rows packed into one terminal row.
+    pub rows_per_term_cell: u16,
+    /// Terminal columns spanned by one logical cell.
+    pub term_cols_per_cell: u16,
+}
+
+/// Glyphs drawn for each entity in modes that give entities their own shape.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CellGlyphs {
+    /// Head glyphs indexed as up, down, left, right.
+    pub head: [&'static str; 4],
+    pub body: &'static str,
+    /// Body glyph used when shape coding is on.
+    pub hatched_body: &'static str,
+    pub tail: &'static str,
+    pub food: &'static str,
+    pub super_food: &'static str,
+    pub empty: &'static str,
 }
 
 /// Glyph palette used by rendering paths.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct GlyphPalette {
+    pub mode: GlyphMode,
     pub half_upper: &'static str,
     pub half_lower: &'static str,
     pub solid: &'static str,
+    /// Full-cell glyph marking the snake head in half-block mode when shape
+    /// coding is on.
+    pub head_mark: &'static str,
     pub table_separator: &'static str,
+    /// Two-column glyphs for square-cell modes.
+    pub cell: CellGlyphs,
+    /// One-column glyphs for ASCII mode, which keeps the half-block layout.
+    pub compact: CellGlyphs,
 }
 
 impl GlyphMode {
-    /// Resolves glyph mode from CLI and optional environment override.
+    /// Resolves glyph mode from the layered settings value, defaulting to
+    /// ASCII when the terminal is unlikely to draw block glyphs.
     #[must_use]
-    pub fn resolve(force_ascii: bool) -> Self {
-        let env = std::env::var("TERMINAL_SNAKE_GLYPHS").ok();
-        glyph_mode_from_inputs(force_ascii, env.as_deref())
+    pub fn resolve(setting: Option<GlyphMode>, unicode_blocks: bool) -> Self {
+        setting.unwrap_or(if unicode_blocks {
+            GlyphMode::Unicode
+        } else {
+            GlyphMode::Ascii
+        })
     }
-}
 
-fn glyph_mode_from_inputs(force_ascii: bool, env_value: Option<&str>) -> GlyphMode {
-    if force_ascii {
-        return GlyphMode::Ascii;
+    /// Adjusts the mode for the terminal's color depth.
+    ///
+    /// Half-block and Braille compositing tell entities apart by color
+    /// alone, so monochrome output switches them to square cells, whose
+    /// per-entity glyphs stay distinguishable without color.
+    #[must_use]
+    pub fn for_color_depth(self, depth: ColorDepth) -> Self {
+        match (self, depth) {
+            (Self::Unicode | Self::Braille, ColorDepth::Monochrome) => Self::Square,
+            (mode, _) => mode,
+        }
     }
 
-    if env_value.is_some_and(|value| value.eq_ignore_ascii_case("ascii")) {
-        GlyphMode::Ascii
-    } else {
-        GlyphMode::Unicode
+    /// Returns how logical cells are packed into terminal cells.
+    #[must_use]
+    pub fn cell_geometry(self) -> CellGeometry {
+        match self {
+            Self::Unicode | Self::Ascii => CellGeometry {
+                cols_per_term_cell: 1,
+                rows_per_term_cell: 2,
+                term_cols_per_cell: 1,
+            },
+            Self::Braille => CellGeometry {
+                cols_per_term_cell: 2,
+                rows_per_term_cell: 4,
+                term_cols_per_cell: 1,
+            },
+            Self::Square | Self::AsciiSquare => CellGeometry {
+                cols_per_term_cell: 1,
+                rows_per_term_cell: 1,
+                term_cols_per_cell: 2,
+            },
+        }
+    }
+
+    /// Converts a gameplay viewport measured in terminal cells into logical
+    /// grid dimensions.
+    #[must_use]
+    pub fn grid_size_for_viewport(self, viewport_width: u16, viewport_height: u16) -> GridSize {
+        let geometry = self.cell_geometry();
+        GridSize {
+            width: viewport_width.saturating_mul(geometry.cols_per_term_cell)
+                / geometry.term_cols_per_cell,
+            height: viewport_height.saturating_mul(geometry.rows_per_term_cell),
+        }
+    }
+}
+
+impl std::str::FromStr for GlyphMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            value if value.eq_ignore_ascii_case("unicode") => Ok(Self::Unicode),
+            value if value.eq_ignore_ascii_case("ascii") => Ok(Self::Ascii),
+            value if value.eq_ignore_ascii_case("braille") => Ok(Self::Braille),
+            value if value.eq_ignore_ascii_case("square") => Ok(Self::Square),
+            value if value.eq_ignore_ascii_case("ascii-square") => Ok(Self::AsciiSquare),
+            other => Err(format!(
+                "unknown glyph mode '{other}' (expected unicode, ascii, braille, square or ascii-square)"
+            )),
+        }
     }
 }
 
@@ -146,17 +275,27 @@ static GLYPH_PALETTE: OnceLock<GlyphPalette> = OnceLock::new();
 /// Configures the global glyph palette. First call wins.
 pub fn configure_glyphs(mode: GlyphMode) {
     let _ = GLYPH_PALETTE.set(match mode {
-        GlyphMode::Unicode => GlyphPalette {
-            half_upper: GLYPH_HALF_UPPER,
-            half_lower: GLYPH_HALF_LOWER,
-            solid: "█",
-            table_separator: "│",
+        GlyphMode::Unicode | GlyphMode::Braille | GlyphMode::Square => GlyphPalette {
+            mode,
+            ..unicode_palette()
         },
-        GlyphMode::Ascii => GlyphPalette {
+        GlyphMode::Ascii | GlyphMode::AsciiSquare => GlyphPalette {
+            mode,
             half_upper: "#",
             half_lower: "#",
             solid: "#",
+            head_mark: "@",
             table_separator: "|",
+            cell: CellGlyphs {
+                head: ["/\\", "\\/", "<:", ":>"],
+                body: "[]",
+                hatched_body: "##",
+                tail: "::",
+                food: "()",
+                super_food: "<>",
+                empty: "  ",
+            },
+            compact: ASCII_COMPACT_GLYPHS,
         },
     });
 }
@@ -164,20 +303,51 @@ pub fn configure_glyphs(mode: GlyphMode) {
 /// Returns the active glyph palette.
 #[must_use]
 pub fn glyphs() -> &'static GlyphPalette {
-    GLYPH_PALETTE.get_or_init(|| GlyphPalette {
+    GLYPH_PALETTE.get_or_init(unicode_palette)
+}
+
+fn unicode_palette() -> GlyphPalette {
+    GlyphPalette {
+        mode: GlyphMode::Unicode,
         half_upper: GLYPH_HALF_UPPER,
         half_lower: GLYPH_HALF_LOWER,
         solid: "█",
+        head_mark: "◉",
         table_separator: "│",
-    })
+        cell: CellGlyphs {
+            head: ["◢◣", "◥◤", "◀█", "█▶"],
+            body: "██",
+            hatched_body: "▒▒",
+            tail: "▓▓",
+            food: "◖◗",
+            super_food: "◆◆",
+            empty: "  ",
+        },
+        compact: ASCII_COMPACT_GLYPHS,
+    }
 }
 
+/// One-column entity glyphs; every palette shares them, only ASCII mode
+/// draws them.
+const ASCII_COMPACT_GLYPHS: CellGlyphs = CellGlyphs {
+    head: ["^", "v", "<", ">"],
+    body: "o",
+    hatched_body: "x",
+    tail: "+",
+    food: "*",
+    super_food: "$",
+    empty: " ",
+};
+
 /// Base tick interval in milliseconds.
 pub const DEFAULT_TICK_INTERVAL_MS: u64 = 200;
 
 /// Minimum tick interval in milliseconds.
 pub const MIN_TICK_INTERVAL_MS: u64 = 60;
 
+/// Tick interval divisor while the player holds the current direction key.
+pub const SPRINT_SPEED_MULTIPLIER: u32 = 2;
+
 /// Food items eaten per speed level increase.
 pub const FOOD_PER_SPEED_LEVEL: u32 = 5;
 
@@ -189,29 +359,92 @@ pub const MAX_START_SPEED_LEVEL: u32 = 15;
 
 #[cfg(test)]
 mod tests {
-    use super::{GlyphMode, glyph_mode_from_inputs};
+    use super::{GlyphMode, GridSize};
+    use crate::platform::ColorDepth;
 
     #[test]
-    fn glyph_mode_resolve_prefers_cli_flag() {
+    fn glyph_mode_defaults_to_unicode() {
+        assert_eq!(GlyphMode::resolve(None, true), GlyphMode::Unicode);
+    }
+
+    #[test]
+    fn glyph_mode_defaults_to_ascii_without_unicode_support() {
+        assert_eq!(GlyphMode::resolve(None, false), GlyphMode::Ascii);
+    }
+
+    #[test]
+    fn glyph_mode_setting_overrides_detection() {
+        assert_eq!(
+            GlyphMode::resolve(Some(GlyphMode::Square), false),
+            GlyphMode::Square
+        );
         assert_eq!(
-            glyph_mode_from_inputs(true, Some("unicode")),
+            GlyphMode::resolve(Some(GlyphMode::Ascii), true),
             GlyphMode::Ascii
         );
     }
 
     #[test]
-    fn glyph_mode_uses_ascii_when_env_requests_it() {
+    fn square_modes_use_two_columns_per_cell() {
+        for mode in [GlyphMode::Square, GlyphMode::AsciiSquare] {
+            assert_eq!(
+                mode.grid_size_for_viewport(41, 10),
+                GridSize {
+                    width: 20,
+                    height: 10,
+                }
+            );
+        }
+        assert_eq!("square".parse::<GlyphMode>(), Ok(GlyphMode::Square));
         assert_eq!(
-            glyph_mode_from_inputs(false, Some("ascii")),
-            GlyphMode::Ascii
+            "ascii-square".parse::<GlyphMode>(),
+            Ok(GlyphMode::AsciiSquare)
         );
     }
 
     #[test]
-    fn glyph_mode_defaults_to_unicode() {
+    fn ascii_mode_keeps_the_half_block_layout() {
         assert_eq!(
-            glyph_mode_from_inputs(false, Some("unicode")),
-            GlyphMode::Unicode
+            GlyphMode::Ascii.cell_geometry(),
+            GlyphMode::Unicode.cell_geometry()
+        );
+    }
+
+    #[test]
+    fn braille_mode_packs_two_by_four_cells() {
+        assert_eq!(
+            GlyphMode::Braille.grid_size_for_viewport(40, 10),
+            GridSize {
+                width: 80,
+                height: 40,
+            }
+        );
+        assert_eq!(
+            GlyphMode::Unicode.grid_size_for_viewport(40, 10),
+            GridSize {
+                width: 40,
+                height: 20,
+            }
+        );
+    }
+
+    #[test]
+    fn monochrome_switches_color_composited_modes_to_square_cells() {
+        assert_eq!(
+            GlyphMode::Unicode.for_color_depth(ColorDepth::Monochrome),
+            GlyphMode::Square
+        );
+        assert_eq!(
+            GlyphMode::Braille.for_color_depth(ColorDepth::Monochrome),
+            GlyphMode::Square
+        );
+        assert_eq!(
+            GlyphMode::Ascii.for_color_depth(ColorDepth::Monochrome),
+            GlyphMode::Ascii
+        );
+        assert_eq!(
+            GlyphMode::Braille.for_color_depth(ColorDepth::Ansi16),
+            GlyphMode::Braille
         );
     }
 }
@@ -0,0 +1,513 @@
+//! Game loop logic for hosts that run their own event loop.
+//!
+//! A [`GameController`] takes raw crossterm events and timestamps instead of
+//! reading the terminal itself, and is drawn with [`crate::widget::GameView`].
+
+use std::time::Instant;
+
+use crossterm::event::Event;
+use ratatui::layout::Rect;
+
+use crate::config::{GridSize, glyphs};
+use crate::game::{GameState, GameStatus, GlowEffect};
+use crate::input::{Direction, GameInput, InputHandler};
+use crate::keymap::KeyBindings;
+use crate::renderer::{RenderCache, grid_position_at};
+use crate::scheduler::{TickScheduler, loop_wake_deadline};
+use crate::ui::menu::{
+    GAME_OVER_MENU_ITEM_COUNT, GAME_OVER_MENU_PLAY_AGAIN_IDX, GAME_OVER_MENU_QUIT_IDX, MenuTarget,
+    PAUSE_MENU_ITEM_COUNT, PAUSE_MENU_QUIT_IDX, PAUSE_MENU_RESUME_IDX, PAUSE_MENU_THEME_IDX,
+    START_MENU_ITEM_COUNT, START_MENU_QUIT_IDX, START_MENU_SETTINGS_IDX, START_MENU_START_IDX,
+};
+use crate::widget::MenuState;
+
+/// What the host should do after the controller handled an event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControllerEvent {
+    /// Nothing on screen changed.
+    None,
+    /// The game or a menu changed; draw the view again.
+    Redraw,
+    /// The player asked for the next theme; themes belong to the host.
+    CycleTheme,
+    /// The player picked Theme in the pause menu; the host shows its own
+    /// theme picker.
+    ChooseTheme,
+    /// The player picked Settings in the start menu; the host shows its own
+    /// settings.
+    OpenSettings,
+    /// The player chose Quit or pressed the quit key.
+    Quit,
+}
+
+/// One embedded game: its state, input mapping, tick clock and menus.
+///
+/// Feed it every terminal event with [`Self::handle_event`], call
+/// [`Self::update`] whenever the loop wakes, and sleep no later than
+/// [`Self::next_wake`]. The game opens on the start menu.
+pub struct GameController {
+    state: GameState,
+    input: InputHandler,
+    /// Bindings named in menu hints; the input handler keeps its own copy.
+    bindings: KeyBindings,
+    scheduler: TickScheduler,
+    last_status: GameStatus,
+    high_score: u32,
+    /// High score before the current game, to tell a new record apart.
+    reference_high_score: u32,
+    mouse_steering: bool,
+    /// Gameplay viewport of the last frame, for mouse steering.
+    gameplay_area: Rect,
+    menu: MenuState,
+    cache: RenderCache,
+}
+
+/// Borrowed parts of a controller a [`crate::widget::GameView`] draws from.
+pub(crate) struct ControllerView<'a> {
+    pub(crate) state: &'a GameState,
+    pub(crate) bindings: &'a KeyBindings,
+    pub(crate) high_score: u32,
+    pub(crate) reference_high_score: u32,
+    pub(crate) menu: &'a mut MenuState,
+    pub(crate) cache: &'a mut RenderCache,
+}
+
+impl GameController {
+    /// Starts a paused game on a `bounds`-sized board.
+    #[must_use]
+    pub fn new(bounds: GridSize, now: Instant) -> Self {
+        let mut state = GameState::new(bounds);
+        state.status = GameStatus::Paused;
+        Self {
+            last_status: state.status,
+            state,
+            input: InputHandler::new(),
+            bindings: KeyBindings::default(),
+            scheduler: TickScheduler::new(now),
+            high_score: 0,
+            reference_high_score: 0,
+            mouse_steering: false,
+            gameplay_area: Rect::default(),
+            menu: MenuState::default(),
+            cache: RenderCache::default(),
+        }
+    }
+
+    /// Replaces the default key bindings.
+    #[must_use]
+    pub fn with_bindings(mut self, bindings: KeyBindings) -> Self {
+        self.input.set_bindings(bindings.clone());
+        self.bindings = bindings;
+        self
+    }
+
+    /// Sets the high score to beat, e.g. one the host saved earlier.
+    #[must_use]
+    pub fn with_high_score(mut self, high_score: u32) -> Self {
+        self.high_score = high_score;
+        self.reference_high_score = high_score;
+        self
+    }
+
+    /// Lets clicks on the play field turn the snake toward the clicked cell.
+    #[must_use]
+    pub fn with_mouse_steering(mut self, enabled: bool) -> Self {
+        self.mouse_steering = enabled;
+        self
+    }
+
+    #[must_use]
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Returns the best score so far, including the game in progress.
+    #[must_use]
+    pub fn high_score(&self) -> u32 {
+        self.high_score.max(self.state.score)
+    }
+
+    /// Handles one terminal event that arrived at `now`.
+    pub fn handle_event(&mut self, event: Event, now: Instant) -> ControllerEvent {
+        let Some(input) = self.input.map_event(event) else {
+            return ControllerEvent::None;
+        };
+        let outcome = self.apply_input(input);
+        self.settle(now);
+        outcome
+    }
+
+    /// Runs the ticks due by `now`. Returns whether the game advanced and
+    /// needs a redraw.
+    pub fn update(&mut self, now: Instant) -> bool {
+        let mut advanced = false;
+        if self.state.status == GameStatus::Playing {
+            let tick_interval = self.state.tick_interval();
+            for _ in 0..self.scheduler.due_ticks(now, tick_interval) {
+                self.state.record_tick_duration(tick_interval);
+                self.state.tick();
+                advanced = true;
+                if self.state.status != GameStatus::Playing {
+                    break;
+                }
+            }
+        }
+        self.settle(now);
+        advanced
+    }
+
+    /// Returns when [`Self::update`] next has work to do: the next tick, the
+    /// next frame of a glow animation, or an idle wake-up.
+    #[must_use]
+    pub fn next_wake(&self, now: Instant) -> Instant {
+        let tick_interval =
+            (self.state.status == GameStatus::Playing).then(|| self.state.tick_interval());
+        let last_frame = self
+            .state
+            .active_glow()
+            .is_some_and(GlowEffect::is_active)
+            .then_some(now);
+        loop_wake_deadline(now, &self.scheduler, tick_interval, last_frame)
+    }
+
+    /// Resizes the board to fill `gameplay_area` in the active glyph mode.
+    ///
+    /// [`crate::widget::GameView`] calls this on every render; hosts that
+    /// lay out the widgets themselves call it with the play field's area.
+    pub fn fit(&mut self, gameplay_area: Rect) {
+        self.gameplay_area = gameplay_area;
+        let bounds = glyphs()
+            .mode
+            .grid_size_for_viewport(gameplay_area.width, gameplay_area.height);
+        if bounds.total_cells() > 0 && bounds != self.state.bounds() {
+            self.state.resize_bounds(bounds);
+        }
+    }
+
+    pub(crate) fn view(&mut self) -> ControllerView<'_> {
+        ControllerView {
+            state: &self.state,
+            bindings: &self.bindings,
+            high_score: self.high_score(),
+            reference_high_score: self.reference_high_score,
+            menu: &mut self.menu,
+            cache: &mut self.cache,
+        }
+    }
+
+    fn apply_input(&mut self, input: GameInput) -> ControllerEvent {
+        // Clicks on a menu entry select it and then act like Enter.
+        let input = match input {
+            GameInput::Click { column, row } => match self.menu.target_at(column, row) {
+                Some(
+                    MenuTarget::StartItem(idx)
+                    | MenuTarget::PauseItem(idx)
+                    | MenuTarget::GameOverItem(idx),
+                ) => {
+                    self.menu.selected_idx = idx;
+                    GameInput::Confirm
+                }
+                Some(_) => return ControllerEvent::None,
+                None => {
+                    if self.mouse_steering
+                        && self.state.status == GameStatus::Playing
+                        && let Some(cell) =
+                            grid_position_at(self.gameplay_area, self.state.bounds(), column, row)
+                    {
+                        self.state.steer_toward(cell);
+                    }
+                    return ControllerEvent::None;
+                }
+            },
+            GameInput::Quit => return ControllerEvent::Quit,
+            GameInput::Resize => return ControllerEvent::Redraw,
+            other => other,
+        };
+
+        match apply_menu_input(&mut self.state, &mut self.menu.selected_idx, input, true) {
+            MenuOutcome::Ignored => ControllerEvent::None,
+            MenuOutcome::Changed => ControllerEvent::Redraw,
+            MenuOutcome::ChooseTheme => ControllerEvent::ChooseTheme,
+            MenuOutcome::CycleTheme => ControllerEvent::CycleTheme,
+            MenuOutcome::OpenSettings => ControllerEvent::OpenSettings,
+            MenuOutcome::Quit => ControllerEvent::Quit,
+        }
+    }
+
+    /// Follows status changes: records the high score when a game ends,
+    /// resets the menu selection and holds the tick clock while stopped.
+    fn settle(&mut self, now: Instant) {
+        let status = self.state.status;
+        self.input
+            .set_repeat_directions(status != GameStatus::Playing);
+        self.state.set_held_direction(self.input.held_direction());
+        if status != GameStatus::Playing || status != self.last_status {
+            self.scheduler.reset(now);
+        }
+        if status == self.last_status {
+            return;
+        }
+
+        if matches!(status, GameStatus::GameOver | GameStatus::Victory) {
+            self.reference_high_score = self.high_score;
+            self.high_score = self.high_score.max(self.state.score);
+        }
+        self.menu.selected_idx = 0;
+        self.last_status = status;
+    }
+}
+
+/// What [`apply_menu_input`] leaves for the host to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuOutcome {
+    /// The input did nothing.
+    Ignored,
+    /// The game or the menu selection changed.
+    Changed,
+    /// The player picked Theme in the pause menu.
+    ChooseTheme,
+    /// The player pressed the theme key while playing.
+    CycleTheme,
+    /// The player picked Settings in the start menu.
+    OpenSettings,
+    /// The player picked Quit.
+    Quit,
+}
+
+/// Applies one input to a game: it drives the start, pause and game-over
+/// menus while stopped and the snake while playing.
+///
+/// `selected_idx` is the highlighted entry of the menu `state` shows; hosts
+/// reset it when the status changes. Starting and resuming are refused
+/// unless `can_resume`, e.g. while the terminal is too small to play in.
+/// Play Again restarts onto the start menu.
+pub fn apply_menu_input(
+    state: &mut GameState,
+    selected_idx: &mut usize,
+    input: GameInput,
+    can_resume: bool,
+) -> MenuOutcome {
+    if state.is_start_screen() {
+        match input {
+            GameInput::Direction(Direction::Up) => {
+                *selected_idx = wrap_prev(*selected_idx, START_MENU_ITEM_COUNT);
+            }
+            GameInput::Direction(Direction::Down) => {
+                *selected_idx = wrap_next(*selected_idx, START_MENU_ITEM_COUNT);
+            }
+            GameInput::Confirm | GameInput::Direction(Direction::Right) => match *selected_idx {
+                START_MENU_START_IDX if can_resume => *state = state.restart(),
+                START_MENU_SETTINGS_IDX => return MenuOutcome::OpenSettings,
+                START_MENU_QUIT_IDX => return MenuOutcome::Quit,
+                _ => return MenuOutcome::Ignored,
+            },
+            _ => return MenuOutcome::Ignored,
+        }
+        return MenuOutcome::Changed;
+    }
+
+    match state.status {
+        GameStatus::Paused => match input {
+            GameInput::Direction(Direction::Up) => {
+                *selected_idx = wrap_prev(*selected_idx, PAUSE_MENU_ITEM_COUNT);
+            }
+            GameInput::Direction(Direction::Down) => {
+                *selected_idx = wrap_next(*selected_idx, PAUSE_MENU_ITEM_COUNT);
+            }
+            GameInput::Confirm | GameInput::Direction(Direction::Right) => match *selected_idx {
+                PAUSE_MENU_RESUME_IDX if can_resume => state.status = GameStatus::Playing,
+                PAUSE_MENU_THEME_IDX => return MenuOutcome::ChooseTheme,
+                PAUSE_MENU_QUIT_IDX => return MenuOutcome::Quit,
+                _ => return MenuOutcome::Ignored,
+            },
+            GameInput::Pause | GameInput::Direction(Direction::Left) if can_resume => {
+                state.status = GameStatus::Playing;
+            }
+            _ => return MenuOutcome::Ignored,
+        },
+        GameStatus::GameOver | GameStatus::Victory => match input {
+            GameInput::Direction(Direction::Up) => {
+                *selected_idx = wrap_prev(*selected_idx, GAME_OVER_MENU_ITEM_COUNT);
+            }
+            GameInput::Direction(Direction::Down) => {
+                *selected_idx = wrap_next(*selected_idx, GAME_OVER_MENU_ITEM_COUNT);
+            }
+            GameInput::Confirm | GameInput::Direction(Direction::Right) => match *selected_idx {
+                GAME_OVER_MENU_PLAY_AGAIN_IDX => {
+                    *state = state.restart();
+                    state.status = GameStatus::Paused;
+                }
+                GAME_OVER_MENU_QUIT_IDX => return MenuOutcome::Quit,
+                _ => return MenuOutcome::Ignored,
+            },
+            _ => return MenuOutcome::Ignored,
+        },
+        GameStatus::Playing => match input {
+            GameInput::CycleTheme => return MenuOutcome::CycleTheme,
+            other => state.apply_input(other),
+        },
+    }
+    MenuOutcome::Changed
+}
+
+/// Returns the menu entry below `current` in a `len`-entry menu, wrapping
+/// from the last entry to the first.
+#[must_use]
+pub fn wrap_next(current: usize, len: usize) -> usize {
+    (current + 1) % len
+}
+
+/// Returns the menu entry above `current`, wrapping from the first entry to
+/// the last.
+#[must_use]
+pub fn wrap_prev(current: usize, len: usize) -> usize {
+    if current == 0 { len - 1 } else { current - 1 }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{ControllerEvent, GameController, MenuOutcome, apply_menu_input};
+    use crate::config::GridSize;
+    use crate::game::{GameState, GameStatus};
+    use crate::input::{Direction, GameInput};
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    fn bounds() -> GridSize {
+        GridSize {
+            width: 40,
+            height: 30,
+        }
+    }
+
+    #[test]
+    fn pause_menu_starts_the_game_and_ticks_follow_the_clock() {
+        let start = Instant::now();
+        let mut controller = GameController::new(bounds(), start);
+        assert_eq!(controller.state().status, GameStatus::Paused);
+        assert!(!controller.update(start + Duration::from_secs(5)));
+
+        let resumed = start + Duration::from_secs(10);
+        assert_eq!(
+            controller.handle_event(key(KeyCode::Enter), resumed),
+            ControllerEvent::Redraw
+        );
+        assert_eq!(controller.state().status, GameStatus::Playing);
+
+        let interval = controller.state().tick_interval();
+        assert_eq!(controller.next_wake(resumed), resumed + interval);
+        assert!(!controller.update(resumed + interval / 2));
+        assert!(controller.update(resumed + interval));
+        assert_eq!(controller.state().tick_count, 1);
+    }
+
+    #[test]
+    fn idle_screens_wake_at_most_four_times_a_second() {
+        let start = Instant::now();
+        let mut controller = GameController::new(bounds(), start);
+
+        for status in [GameStatus::Paused, GameStatus::GameOver] {
+            controller.state.status = status;
+            controller.update(start);
+            let mut now = start;
+            let mut wakes = 0;
+            loop {
+                now = controller.next_wake(now);
+                if now > start + Duration::from_secs(1) {
+                    break;
+                }
+                wakes += 1;
+                assert!(
+                    !controller.update(now),
+                    "{status:?} screen redrew while idle"
+                );
+            }
+            assert!(
+                wakes <= 4,
+                "{status:?} screen woke {wakes} times in one second"
+            );
+        }
+    }
+
+    #[test]
+    fn menu_entries_map_to_settings_theme_and_quit_events() {
+        let now = Instant::now();
+        let mut controller = GameController::new(bounds(), now);
+        assert!(controller.state().is_start_screen());
+
+        controller.handle_event(key(KeyCode::Down), now);
+        assert_eq!(
+            controller.handle_event(key(KeyCode::Enter), now),
+            ControllerEvent::OpenSettings
+        );
+        controller.handle_event(key(KeyCode::Down), now);
+        assert_eq!(
+            controller.handle_event(key(KeyCode::Enter), now),
+            ControllerEvent::Quit
+        );
+        assert_eq!(
+            controller.handle_event(Event::FocusGained, now),
+            ControllerEvent::None
+        );
+
+        // A game that has ticked shows the pause menu instead.
+        controller.state.tick_count = 1;
+        controller.menu.selected_idx = 0;
+        controller.handle_event(key(KeyCode::Down), now);
+        assert_eq!(
+            controller.handle_event(key(KeyCode::Enter), now),
+            ControllerEvent::ChooseTheme
+        );
+    }
+
+    #[test]
+    fn a_finished_game_keeps_its_score_as_the_high_score() {
+        let now = Instant::now();
+        let mut controller = GameController::new(bounds(), now).with_high_score(5);
+        controller.handle_event(key(KeyCode::Enter), now);
+        controller.state.score = 12;
+        controller.state.status = GameStatus::GameOver;
+        controller.update(now);
+
+        assert_eq!(controller.high_score(), 12);
+        assert_eq!(controller.reference_high_score, 5);
+        controller.handle_event(key(KeyCode::Enter), now);
+        assert_eq!(controller.state().status, GameStatus::Paused);
+        assert_eq!(controller.state().score, 0);
+    }
+
+    #[test]
+    fn menus_refuse_to_start_or_resume_when_play_is_blocked() {
+        let mut state = GameState::new(bounds());
+        state.status = GameStatus::Paused;
+        let mut selected_idx = 0;
+        assert_eq!(
+            apply_menu_input(&mut state, &mut selected_idx, GameInput::Confirm, false),
+            MenuOutcome::Ignored
+        );
+        assert!(state.is_start_screen());
+
+        state.tick_count = 1;
+        let outcome = apply_menu_input(&mut state, &mut selected_idx, GameInput::Confirm, false);
+        assert_eq!(outcome, MenuOutcome::Ignored);
+        assert_eq!(state.status, GameStatus::Paused);
+
+        let down = GameInput::Direction(Direction::Down);
+        apply_menu_input(&mut state, &mut selected_idx, down, false);
+        assert_eq!(
+            apply_menu_input(&mut state, &mut selected_idx, GameInput::Confirm, false),
+            MenuOutcome::ChooseTheme
+        );
+        assert_eq!(
+            apply_menu_input(&mut state, &mut selected_idx, GameInput::Pause, true),
+            MenuOutcome::Changed
+        );
+        assert_eq!(state.status, GameStatus::Playing);
+    }
+}
@@ -86,12 +86,19 @@ impl Food {
 }
 
 /// Returns a random position not currently occupied by the snake.
+///
+/// Takes a free cell straight from the snake's occupancy grid when it
+/// covers `bounds`, and otherwise scans the board.
 #[must_use]
 pub fn spawn_position<R: Rng + ?Sized>(
     rng: &mut R,
     bounds: GridSize,
     snake: &Snake,
 ) -> Option<Position> {
+    if snake.occupancy().bounds() == bounds {
+        return snake.occupancy().random_free(rng);
+    }
+
     let mut candidates = Vec::new();
 
     for y in 0..i32::from(bounds.height) {
@@ -1,14 +1,15 @@
 use rand::Rng;
 use rand::SeedableRng;
 use rand::rngs::StdRng;
+use std::collections::HashSet;
 use std::time::{Duration, Instant};
 
 use crate::config::{
     DEFAULT_TICK_INTERVAL_MS, FOOD_PER_SPEED_LEVEL, GridSize, MAX_START_SPEED_LEVEL,
-    MIN_TICK_INTERVAL_MS,
+    MIN_TICK_INTERVAL_MS, SPRINT_SPEED_MULTIPLIER,
 };
 use crate::food::Food;
-use crate::input::GameInput;
+use crate::input::{Direction, GameInput};
 use crate::snake::{Position, Snake};
 
 /// Current high-level gameplay state.
@@ -144,6 +145,9 @@ pub struct GameState {
     pub death_reason: Option<DeathReason>,
     glow: Option<GlowEffect>,
     elapsed_millis: u64,
+    /// Whether the current direction key is held down to sprint.
+    sprinting: bool,
+    sprint_millis: u64,
     bounds: GridSize,
     base_speed_level: u32,
     food_density: FoodDensity,
@@ -230,6 +234,8 @@ impl GameState {
             death_reason: None,
             glow: None,
             elapsed_millis: 0,
+            sprinting: false,
+            sprint_millis: 0,
             bounds,
             base_speed_level,
             food_density: normalized_density,
@@ -352,7 +358,51 @@ impl GameState {
                     other => other,
                 };
             }
-            GameInput::Quit | GameInput::Confirm | GameInput::CycleTheme | GameInput::Resize => {}
+            GameInput::Quit
+            | GameInput::FocusLost
+            | GameInput::Confirm
+            | GameInput::CycleTheme
+            | GameInput::EditTheme
+            | GameInput::Char(_)
+            | GameInput::Backspace
+            | GameInput::Bind(_)
+            | GameInput::Click { .. }
+            | GameInput::Scroll(_)
+            | GameInput::Resize => {}
+        }
+    }
+
+    /// Turns the snake toward `target`, e.g. a clicked cell.
+    ///
+    /// Takes the axis with the larger distance first, and the other axis
+    /// when the first would reverse the snake. A target straight behind the
+    /// head is ignored.
+    pub fn steer_toward(&mut self, target: Position) {
+        let head = self.snake.head();
+        let dx = target.x - head.x;
+        let dy = target.y - head.y;
+        let horizontal = match dx.signum() {
+            1 => Some(Direction::Right),
+            -1 => Some(Direction::Left),
+            _ => None,
+        };
+        let vertical = match dy.signum() {
+            1 => Some(Direction::Down),
+            -1 => Some(Direction::Up),
+            _ => None,
+        };
+        let (first, second) = if dx.abs() >= dy.abs() {
+            (horizontal, vertical)
+        } else {
+            (vertical, horizontal)
+        };
+        let reverse = self.snake.direction().opposite();
+        if let Some(direction) = [first, second]
+            .into_iter()
+            .flatten()
+            .find(|direction| *direction != reverse)
+        {
+            self.apply_input(GameInput::Direction(direction));
         }
     }
 
@@ -432,9 +482,43 @@ impl GameState {
 
     /// Adds gameplay time for one simulation step.
     pub fn record_tick_duration(&mut self, duration: Duration) {
-        self.elapsed_millis = self
-            .elapsed_millis
-            .saturating_add(duration.as_millis().min(u128::from(u64::MAX)) as u64);
+        let millis = duration.as_millis().min(u128::from(u64::MAX)) as u64;
+        self.elapsed_millis = self.elapsed_millis.saturating_add(millis);
+        if self.sprinting {
+            self.sprint_millis = self.sprint_millis.saturating_add(millis);
+        }
+    }
+
+    /// Updates the sprint from the direction key being held, if any.
+    ///
+    /// Holding the key of the direction the snake already moves in sprints;
+    /// any other key, or none, stops the sprint.
+    pub fn set_held_direction(&mut self, held: Option<Direction>) {
+        self.sprinting = self.status == GameStatus::Playing && held == Some(self.snake.direction());
+    }
+
+    /// Returns whether the snake is sprinting.
+    #[must_use]
+    pub fn is_sprinting(&self) -> bool {
+        self.sprinting
+    }
+
+    /// Returns the time between ticks at the current speed, shortened while
+    /// sprinting.
+    #[must_use]
+    pub fn tick_interval(&self) -> Duration {
+        let interval = tick_interval_for_speed(self.speed_level);
+        if self.sprinting {
+            interval / SPRINT_SPEED_MULTIPLIER
+        } else {
+            interval
+        }
+    }
+
+    /// Returns the part of [`Self::elapsed_duration`] spent sprinting.
+    #[must_use]
+    pub fn sprint_duration(&self) -> Duration {
+        Duration::from_millis(self.sprint_millis)
     }
 
     /// Returns total gameplay duration accumulated from simulation ticks.
@@ -503,13 +587,18 @@ impl GameState {
         if self.foods.len() > target_count {
             self.foods.truncate(target_count);
         }
+        if self.foods.len() == target_count {
+            return;
+        }
 
+        self.snake.fit_to_bounds(self.bounds);
+        let mut food_cells: HashSet<Position> = self.foods.iter().map(|f| f.position).collect();
         while self.foods.len() < target_count {
-            let Some(mut food) =
-                spawn_food_avoiding(&mut self.rng, self.bounds, &self.snake, &self.foods)
+            let Some(mut food) = spawn_food_avoiding(&mut self.rng, &self.snake, &food_cells)
             else {
                 break;
             };
+            food_cells.insert(food.position);
 
             // 30% chance to upgrade newly spawned food to super food
             // (only after the game has started — initial food is always normal).
@@ -566,29 +655,30 @@ fn dedupe_food_positions(foods: &mut Vec<Food>) {
     *foods = unique;
 }
 
+/// Random draws from the snake's free cells before falling back to listing
+/// the cells that hold no food either. Food covers about one free cell in
+/// 200, so the fallback only runs when the board is nearly full.
+const FOOD_SPAWN_ATTEMPTS: usize = 8;
+
+/// Spawns food on a cell free of both the snake and `food_cells`, in
+/// constant expected time. The snake's occupancy grid must cover the board.
 fn spawn_food_avoiding<R: Rng + ?Sized>(
     rng: &mut R,
-    bounds: GridSize,
     snake: &Snake,
-    existing_foods: &[Food],
+    food_cells: &HashSet<Position>,
 ) -> Option<Food> {
-    let mut candidates = Vec::new();
-
-    for y in 0..i32::from(bounds.height) {
-        for x in 0..i32::from(bounds.width) {
-            let position = Position { x, y };
-            if snake.occupies(position) {
-                continue;
-            }
-
-            if existing_foods.iter().any(|food| food.position == position) {
-                continue;
-            }
-
-            candidates.push(position);
+    let occupancy = snake.occupancy();
+    for _ in 0..FOOD_SPAWN_ATTEMPTS {
+        let position = occupancy.random_free(rng)?;
+        if !food_cells.contains(&position) {
+            return Some(Food::new(position));
         }
     }
 
+    let candidates: Vec<Position> = (0..occupancy.free_len())
+        .filter_map(|n| occupancy.free_cell(n))
+        .filter(|position| !food_cells.contains(position))
+        .collect();
     if candidates.is_empty() {
         return None;
     }
@@ -607,6 +697,35 @@ mod tests {
     use crate::input::GameInput;
     use crate::snake::{Position, Snake};
 
+    #[test]
+    fn holding_the_current_direction_sprints_and_counts_sprint_time() {
+        let mut state = GameState::new_with_seed(
+            GridSize {
+                width: 10,
+                height: 10,
+            },
+            1,
+        );
+        state.snake = Snake::new(Position { x: 1, y: 1 }, Direction::Right);
+        let normal = state.tick_interval();
+
+        state.set_held_direction(Some(Direction::Down));
+        assert!(!state.is_sprinting());
+        state.set_held_direction(Some(Direction::Right));
+        assert!(state.is_sprinting());
+        assert_eq!(state.tick_interval(), normal / 2);
+
+        state.record_tick_duration(state.tick_interval());
+        state.set_held_direction(None);
+        state.record_tick_duration(state.tick_interval());
+        assert_eq!(state.sprint_duration(), normal / 2);
+        assert_eq!(state.elapsed_duration(), normal / 2 + normal);
+
+        state.status = GameStatus::Paused;
+        state.set_held_direction(Some(Direction::Right));
+        assert!(!state.is_sprinting());
+    }
+
     #[test]
     fn snake_grows_after_eating_food() {
         let mut state = GameState::new_with_seed(
@@ -624,6 +743,32 @@ mod tests {
         assert_eq!(state.status, GameStatus::Playing);
     }
 
+    #[test]
+    fn steering_turns_toward_the_target_but_never_reverses() {
+        let mut state = GameState::new_with_seed(
+            GridSize {
+                width: 12,
+                height: 12,
+            },
+            1,
+        );
+        state.status = GameStatus::Playing;
+        state.foods.clear();
+        state.snake = Snake::new(Position { x: 5, y: 5 }, Direction::Right);
+
+        state.steer_toward(Position { x: 1, y: 5 });
+        state.tick();
+        assert_eq!(state.snake.head(), Position { x: 6, y: 5 });
+
+        state.steer_toward(Position { x: 2, y: 1 });
+        state.tick();
+        assert_eq!(state.snake.head(), Position { x: 6, y: 4 });
+
+        state.steer_toward(Position { x: 10, y: 3 });
+        state.tick();
+        assert_eq!(state.snake.head(), Position { x: 7, y: 4 });
+    }
+
     #[test]
     fn snake_collision_with_wall_sets_game_over() {
         let mut state = GameState::new_with_seed(
@@ -860,7 +1005,7 @@ mod tests {
         ];
 
         for (food_eaten, expected_level) in cases {
-            let len = (food_eaten + 2) as i32;
+            let len = food_eaten + 2;
             let segments = (0..len)
                 .map(|i| Position { x: 300 - i, y: 10 })
                 .collect::<Vec<_>>();
@@ -0,0 +1,619 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+
+use crate::platform::Platform;
+
+/// Image id used for every kitty transmission so each frame replaces the last.
+const KITTY_IMAGE_ID: u32 = 31;
+
+/// Maximum base64 payload bytes per kitty escape chunk.
+const KITTY_CHUNK_LEN: usize = 4096;
+
+/// Largest palette a sixel image may declare before colors are quantized.
+const SIXEL_MAX_COLORS: usize = 256;
+
+/// Cell pixel size assumed when the terminal does not report one.
+const FALLBACK_CELL_PIXELS: CellPixels = CellPixels {
+    width: 8,
+    height: 16,
+};
+
+/// Requested pixel renderer for the play field.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GraphicsMode {
+    /// Always draw the play field with text glyphs.
+    Off,
+    /// Use a pixel protocol when the terminal advertises one.
+    Auto,
+    /// Force the kitty graphics protocol.
+    Kitty,
+    /// Force sixel output.
+    Sixel,
+}
+
+/// Escape-sequence protocol used to transmit play-field images.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Sixel,
+}
+
+impl GraphicsMode {
+    /// Resolves the protocol to use from the layered settings value, probed
+    /// terminal capabilities, and terminal identification variables.
+    /// `None` means text rendering.
+    #[must_use]
+    pub fn resolve(setting: Option<GraphicsMode>, platform: Platform) -> Option<GraphicsProtocol> {
+        let probed = if platform.kitty_graphics() {
+            Some(GraphicsProtocol::Kitty)
+        } else if platform.sixel() {
+            Some(GraphicsProtocol::Sixel)
+        } else {
+            None
+        };
+        graphics_protocol_from_inputs(setting, probed, |key| std::env::var(key).ok())
+    }
+}
+
+impl std::str::FromStr for GraphicsMode {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            value if value.eq_ignore_ascii_case("off") => Ok(Self::Off),
+            value if value.eq_ignore_ascii_case("auto") => Ok(Self::Auto),
+            value if value.eq_ignore_ascii_case("kitty") => Ok(Self::Kitty),
+            value if value.eq_ignore_ascii_case("sixel") => Ok(Self::Sixel),
+            other => Err(format!(
+                "unknown graphics mode '{other}' (expected off, auto, kitty or sixel)"
+            )),
+        }
+    }
+}
+
+fn graphics_protocol_from_inputs(
+    setting: Option<GraphicsMode>,
+    probed: Option<GraphicsProtocol>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Option<GraphicsProtocol> {
+    match setting.unwrap_or(GraphicsMode::Off) {
+        GraphicsMode::Off => None,
+        GraphicsMode::Kitty => Some(GraphicsProtocol::Kitty),
+        GraphicsMode::Sixel => Some(GraphicsProtocol::Sixel),
+        GraphicsMode::Auto => probed.or_else(|| detect_protocol(&env)),
+    }
+}
+
+/// Identifies pixel-capable terminals from the variables they export.
+fn detect_protocol(env: &impl Fn(&str) -> Option<String>) -> Option<GraphicsProtocol> {
+    let term = env("TERM").unwrap_or_default().to_ascii_lowercase();
+    let term_program = env("TERM_PROGRAM").unwrap_or_default().to_ascii_lowercase();
+
+    if env("KITTY_WINDOW_ID").is_some()
+        || term.contains("kitty")
+        || term.contains("ghostty")
+        || matches!(term_program.as_str(), "wezterm" | "ghostty")
+    {
+        return Some(GraphicsProtocol::Kitty);
+    }
+
+    if term.contains("sixel")
+        || term.starts_with("foot")
+        || term.starts_with("mlterm")
+        || term_program == "iterm.app"
+    {
+        return Some(GraphicsProtocol::Sixel);
+    }
+
+    None
+}
+
+/// Size of one terminal cell in pixels.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CellPixels {
+    pub width: u16,
+    pub height: u16,
+}
+
+impl CellPixels {
+    /// Reads the cell size from the terminal window, when it reports one.
+    #[must_use]
+    pub fn query() -> Option<Self> {
+        let size = crossterm::terminal::window_size().ok()?;
+        if size.columns == 0 || size.rows == 0 || size.width == 0 || size.height == 0 {
+            return None;
+        }
+
+        Some(Self {
+            width: size.width / size.columns,
+            height: size.height / size.rows,
+        })
+    }
+}
+
+/// Packed 24-bit RGB image the play field is rasterized into.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PixelImage {
+    width: u32,
+    height: u32,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl PixelImage {
+    /// Creates an image filled with a single color.
+    #[must_use]
+    pub fn new(width: u32, height: u32, fill: [u8; 3]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![fill; width as usize * height as usize],
+        }
+    }
+
+    #[must_use]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Returns the pixel at (`x`, `y`). Panics when out of bounds.
+    #[must_use]
+    pub fn pixel(&self, x: u32, y: u32) -> [u8; 3] {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Fills the half-open pixel rectangle `[x0, x1) x [y0, y1)`, clipped to the image.
+    pub fn fill_rect(&mut self, x0: f32, y0: f32, x1: f32, y1: f32, color: [u8; 3]) {
+        let (px0, px1) = self.clip_span(x0, x1, self.width);
+        let (py0, py1) = self.clip_span(y0, y1, self.height);
+        for y in py0..py1 {
+            let row = (y * self.width) as usize;
+            self.pixels[row + px0 as usize..row + px1 as usize].fill(color);
+        }
+    }
+
+    /// Draws an anti-aliased filled circle blended at `alpha` opacity.
+    pub fn fill_circle(&mut self, cx: f32, cy: f32, radius: f32, color: [u8; 3], alpha: f32) {
+        self.blend_disc(cx, cy, radius, color, |distance| {
+            (radius + 0.5 - distance).clamp(0.0, 1.0) * alpha
+        });
+    }
+
+    /// Draws a soft radial halo whose opacity falls off linearly to zero at `radius`.
+    pub fn fill_glow(&mut self, cx: f32, cy: f32, radius: f32, color: [u8; 3], alpha: f32) {
+        self.blend_disc(cx, cy, radius, color, |distance| {
+            (1.0 - distance / radius).clamp(0.0, 1.0) * alpha
+        });
+    }
+
+    fn blend_disc(
+        &mut self,
+        cx: f32,
+        cy: f32,
+        radius: f32,
+        color: [u8; 3],
+        coverage: impl Fn(f32) -> f32,
+    ) {
+        let (px0, px1) = self.clip_span(cx - radius - 1.0, cx + radius + 1.0, self.width);
+        let (py0, py1) = self.clip_span(cy - radius - 1.0, cy + radius + 1.0, self.height);
+        for y in py0..py1 {
+            for x in px0..px1 {
+                let dx = x as f32 + 0.5 - cx;
+                let dy = y as f32 + 0.5 - cy;
+                let amount = coverage((dx * dx + dy * dy).sqrt());
+                if amount > 0.0 {
+                    let idx = (y * self.width + x) as usize;
+                    self.pixels[idx] = blend(self.pixels[idx], color, amount);
+                }
+            }
+        }
+    }
+
+    fn clip_span(&self, start: f32, end: f32, limit: u32) -> (u32, u32) {
+        let start = start.round().clamp(0.0, limit as f32) as u32;
+        let end = end.round().clamp(0.0, limit as f32) as u32;
+        (start, end.max(start))
+    }
+}
+
+fn blend(from: [u8; 3], to: [u8; 3], amount: f32) -> [u8; 3] {
+    let amount = amount.clamp(0.0, 1.0);
+    let mix = |a: u8, b: u8| (f32::from(a) + (f32::from(b) - f32::from(a)) * amount).round() as u8;
+    [
+        mix(from[0], to[0]),
+        mix(from[1], to[1]),
+        mix(from[2], to[2]),
+    ]
+}
+
+/// Encodes `image` as a kitty graphics transmission scaled to `cols` x `rows`
+/// terminal cells at the current cursor position.
+///
+/// The cursor is left in place (`C=1`) and terminal responses are suppressed
+/// (`q=2`) so the output can be interleaved with ratatui's own writes.
+#[must_use]
+pub fn encode_kitty(image: &PixelImage, cols: u16, rows: u16) -> String {
+    let mut raw = Vec::with_capacity(image.pixels.len() * 3);
+    for pixel in &image.pixels {
+        raw.extend_from_slice(pixel);
+    }
+    let payload = base64_encode(&raw);
+
+    let mut out = String::with_capacity(payload.len() + 128);
+    let chunks: Vec<&str> = if payload.is_empty() {
+        vec![""]
+    } else {
+        payload
+            .as_bytes()
+            .chunks(KITTY_CHUNK_LEN)
+            .map(|chunk| std::str::from_utf8(chunk).expect("base64 output is ASCII"))
+            .collect()
+    };
+
+    for (idx, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(idx + 1 < chunks.len());
+        if idx == 0 {
+            out.push_str(&format!(
+                "\x1b_Ga=T,f=24,s={},v={},i={KITTY_IMAGE_ID},p=1,c={cols},r={rows},C=1,q=2,m={more};{chunk}\x1b\\",
+                image.width, image.height
+            ));
+        } else {
+            out.push_str(&format!("\x1b_Gm={more};{chunk}\x1b\\"));
+        }
+    }
+
+    out
+}
+
+/// Returns the kitty sequence that removes the play-field image and its data.
+#[must_use]
+pub fn kitty_delete() -> String {
+    format!("\x1b_Ga=d,d=I,i={KITTY_IMAGE_ID},q=2\x1b\\")
+}
+
+/// Encodes `image` as a sixel DCS sequence drawn at the current cursor position.
+///
+/// Images with more than 256 distinct colors are quantized to a 6x6x6 cube.
+#[must_use]
+pub fn encode_sixel(image: &PixelImage) -> String {
+    let mut palette: Vec<[u8; 3]> = Vec::new();
+    let mut palette_index: HashMap<[u8; 3], usize> = HashMap::new();
+    let mut indices = Vec::with_capacity(image.pixels.len());
+    let quantize = count_distinct_exceeds(&image.pixels, SIXEL_MAX_COLORS);
+
+    for &pixel in &image.pixels {
+        let pixel = if quantize {
+            quantize_to_cube(pixel)
+        } else {
+            pixel
+        };
+        let idx = *palette_index.entry(pixel).or_insert_with(|| {
+            palette.push(pixel);
+            palette.len() - 1
+        });
+        indices.push(idx);
+    }
+
+    let mut out = format!("\x1bPq\"1;1;{};{}", image.width, image.height);
+    for (idx, [r, g, b]) in palette.iter().enumerate() {
+        let pct = |channel: u8| (u32::from(channel) * 100 + 127) / 255;
+        out.push_str(&format!("#{idx};2;{};{};{}", pct(*r), pct(*g), pct(*b)));
+    }
+
+    let width = image.width as usize;
+    let height = image.height as usize;
+    let bands = height.div_ceil(6);
+    for band in 0..bands {
+        let top = band * 6;
+        let band_rows = (height - top).min(6);
+        let mut used: Vec<usize> = Vec::new();
+        let mut in_band = vec![false; palette.len()];
+        for &idx in &indices[top * width..(top + band_rows) * width] {
+            if !in_band[idx] {
+                in_band[idx] = true;
+                used.push(idx);
+            }
+        }
+
+        for (pass, &color) in used.iter().enumerate() {
+            if pass > 0 {
+                out.push('$');
+            }
+            out.push_str(&format!("#{color}"));
+
+            let mut line: Vec<u8> = (0..width)
+                .map(|x| {
+                    (0..band_rows).fold(0u8, |bits, dy| {
+                        if indices[(top + dy) * width + x] == color {
+                            bits | (1 << dy)
+                        } else {
+                            bits
+                        }
+                    })
+                })
+                .collect();
+            while line.last() == Some(&0) {
+                line.pop();
+            }
+            push_sixel_runs(&mut out, &line);
+        }
+
+        if band + 1 < bands {
+            out.push('-');
+        }
+    }
+
+    out.push_str("\x1b\\");
+    out
+}
+
+fn count_distinct_exceeds(pixels: &[[u8; 3]], limit: usize) -> bool {
+    let mut seen: HashSet<[u8; 3]> = HashSet::new();
+    pixels
+        .iter()
+        .any(|pixel| seen.insert(*pixel) && seen.len() > limit)
+}
+
+fn quantize_to_cube(pixel: [u8; 3]) -> [u8; 3] {
+    pixel.map(|channel| ((u16::from(channel) + 25) / 51 * 51) as u8)
+}
+
+/// Appends sixel data characters, using `!` repeat introducers for runs of four or more.
+fn push_sixel_runs(out: &mut String, line: &[u8]) {
+    let mut idx = 0;
+    while idx < line.len() {
+        let bits = line[idx];
+        let run = line[idx..].iter().take_while(|&&b| b == bits).count();
+        let ch = char::from(63 + bits);
+        if run >= 4 {
+            out.push_str(&format!("!{run}{ch}"));
+        } else {
+            for _ in 0..run {
+                out.push(ch);
+            }
+        }
+        idx += run;
+    }
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        out.push(char::from(ALPHABET[(n >> 18) as usize & 63]));
+        out.push(char::from(ALPHABET[(n >> 12) as usize & 63]));
+        out.push(if chunk.len() > 1 {
+            char::from(ALPHABET[(n >> 6) as usize & 63])
+        } else {
+            '='
+        });
+        out.push(if chunk.len() > 2 {
+            char::from(ALPHABET[n as usize & 63])
+        } else {
+            '='
+        });
+    }
+    out
+}
+
+/// Marks every buffer cell in `area` as skipped so ratatui never paints text
+/// over the pixel image occupying it.
+pub fn reserve_cells(buffer: &mut Buffer, area: Rect) {
+    let area = area.intersection(buffer.area);
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            buffer[(x, y)].set_skip(true);
+        }
+    }
+}
+
+/// Tracks what pixel image is currently on screen and emits only changes.
+#[derive(Debug)]
+pub struct PixelPlayField {
+    protocol: GraphicsProtocol,
+    /// Cell size as of the last query; see [`Self::resize`].
+    cell_pixels: Option<CellPixels>,
+    shown: Option<(Rect, PixelImage)>,
+}
+
+impl PixelPlayField {
+    #[must_use]
+    pub fn new(protocol: GraphicsProtocol) -> Self {
+        Self {
+            protocol,
+            cell_pixels: Self::query_cell_pixels(protocol),
+            shown: None,
+        }
+    }
+
+    #[must_use]
+    pub fn protocol(&self) -> GraphicsProtocol {
+        self.protocol
+    }
+
+    /// Returns the pixel size to rasterize one terminal cell at, or `None`
+    /// when sixel output cannot know the real cell size.
+    #[must_use]
+    pub fn cell_pixels(&self) -> Option<CellPixels> {
+        self.cell_pixels
+    }
+
+    /// Queries the cell size again and forgets the on-screen image, after
+    /// the terminal was resized or its font changed.
+    pub fn resize(&mut self) {
+        self.cell_pixels = Self::query_cell_pixels(self.protocol);
+        self.shown = None;
+    }
+
+    /// Returns whether nothing is shown over `area` yet, so a frame must be
+    /// rasterized even when the play field itself did not change.
+    #[must_use]
+    pub fn needs_image(&self, area: Rect) -> bool {
+        self.shown
+            .as_ref()
+            .is_none_or(|(shown_area, _)| *shown_area != area)
+    }
+
+    fn query_cell_pixels(protocol: GraphicsProtocol) -> Option<CellPixels> {
+        match (protocol, CellPixels::query()) {
+            (_, Some(cell)) => Some(cell),
+            (GraphicsProtocol::Kitty, None) => Some(FALLBACK_CELL_PIXELS),
+            (GraphicsProtocol::Sixel, None) => None,
+        }
+    }
+
+    /// Draws `image` over `area` unless the same image is already shown there.
+    pub fn show(&mut self, out: &mut impl Write, area: Rect, image: PixelImage) -> io::Result<()> {
+        if self
+            .shown
+            .as_ref()
+            .is_some_and(|(shown_area, shown)| *shown_area == area && *shown == image)
+        {
+            return Ok(());
+        }
+
+        let payload = match self.protocol {
+            GraphicsProtocol::Kitty => encode_kitty(&image, area.width, area.height),
+            GraphicsProtocol::Sixel => encode_sixel(&image),
+        };
+        write!(
+            out,
+            "\x1b7\x1b[{};{}H{payload}\x1b8",
+            area.y + 1,
+            area.x + 1
+        )?;
+        out.flush()?;
+        self.shown = Some((area, image));
+        Ok(())
+    }
+
+    /// Removes the image. Reserved cells are repainted by ratatui on the next
+    /// frame because their skip flag no longer matches the previous buffer.
+    pub fn hide(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.shown.take().is_some() && self.protocol == GraphicsProtocol::Kitty {
+            out.write_all(kitty_delete().as_bytes())?;
+            out.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        GraphicsMode, GraphicsProtocol, PixelImage, base64_encode, encode_sixel,
+        graphics_protocol_from_inputs, quantize_to_cube,
+    };
+
+    fn env_from(pairs: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |key| {
+            pairs
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| (*value).to_owned())
+        }
+    }
+
+    #[test]
+    fn base64_matches_reference_vectors() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn graphics_default_off_without_request() {
+        let env = env_from(&[("TERM", "xterm-kitty")]);
+        assert_eq!(graphics_protocol_from_inputs(None, None, env), None);
+    }
+
+    #[test]
+    fn auto_detects_kitty_and_sixel_terminals() {
+        assert_eq!(
+            graphics_protocol_from_inputs(
+                Some(GraphicsMode::Auto),
+                None,
+                env_from(&[("KITTY_WINDOW_ID", "1")])
+            ),
+            Some(GraphicsProtocol::Kitty)
+        );
+        assert_eq!(
+            graphics_protocol_from_inputs(
+                Some(GraphicsMode::Auto),
+                None,
+                env_from(&[("TERM", "foot")])
+            ),
+            Some(GraphicsProtocol::Sixel)
+        );
+    }
+
+    #[test]
+    fn auto_prefers_probed_protocol() {
+        let env = env_from(&[("TERM", "xterm-256color")]);
+        assert_eq!(
+            graphics_protocol_from_inputs(
+                Some(GraphicsMode::Auto),
+                Some(GraphicsProtocol::Sixel),
+                env
+            ),
+            Some(GraphicsProtocol::Sixel)
+        );
+    }
+
+    #[test]
+    fn auto_falls_back_when_detection_fails() {
+        let env = env_from(&[("TERM", "xterm-256color")]);
+        assert_eq!(
+            graphics_protocol_from_inputs(Some(GraphicsMode::Auto), None, env),
+            None
+        );
+    }
+
+    #[test]
+    fn graphics_setting_picks_the_protocol_without_detection() {
+        let env = env_from(&[("TERM", "xterm-kitty")]);
+        assert_eq!(
+            graphics_protocol_from_inputs(Some(GraphicsMode::Sixel), None, env),
+            Some(GraphicsProtocol::Sixel)
+        );
+    }
+
+    #[test]
+    fn circle_fill_is_clipped_to_image() {
+        let mut image = PixelImage::new(4, 4, [0, 0, 0]);
+        image.fill_circle(0.0, 0.0, 3.0, [255, 255, 255], 1.0);
+        assert_eq!(image.pixel(0, 0), [255, 255, 255]);
+        assert_eq!(image.pixel(3, 3), [0, 0, 0]);
+    }
+
+    #[test]
+    fn sixel_declares_each_color_once_and_paints_it_per_band() {
+        let mut image = PixelImage::new(2, 7, [0, 0, 0]);
+        image.fill_rect(1.0, 0.0, 2.0, 7.0, [255, 255, 255]);
+        assert_eq!(
+            encode_sixel(&image),
+            "\x1bPq\"1;1;2;7#0;2;0;0;0#1;2;100;100;100#0~$#1?~-#0@$#1?@\x1b\\"
+        );
+    }
+
+    #[test]
+    fn cube_quantization_snaps_to_nearest_level() {
+        assert_eq!(quantize_to_cube([0, 26, 255]), [0, 51, 255]);
+    }
+}
@@ -1,7 +1,12 @@
 use std::io;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
+
+use crate::keymap::{Key, KeyBindings};
 
 /// Canonical movement directions for snake input.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
@@ -33,11 +38,42 @@ pub enum GameInput {
     Quit,
     Confirm,
     CycleTheme,
+    /// Open the editor for the highlighted theme (only used in the theme picker).
+    EditTheme,
+    /// A printable character typed while text entry is active.
+    Char(char),
+    /// Backspace pressed while text entry is active.
+    Backspace,
+    /// A key pressed while a key binding is being captured.
+    Bind(Key),
+    /// Left mouse button pressed at a terminal cell.
+    Click {
+        column: u16,
+        row: u16,
+    },
+    /// Mouse wheel turned up or down.
+    Scroll(Direction),
     Resize,
+    /// The terminal window lost focus (only reported when focus events are enabled).
+    FocusLost,
 }
 
 /// Non-blocking keyboard input poller.
-pub struct InputHandler;
+pub struct InputHandler {
+    bindings: KeyBindings,
+    /// When set, printable keys are reported as [`GameInput::Char`] instead
+    /// of being mapped to game actions.
+    text_entry: bool,
+    /// When set, keys are reported as [`GameInput::Bind`] instead of being
+    /// mapped to game actions.
+    key_capture: bool,
+    /// When set, key repeat of a direction key is reported as another
+    /// direction press, e.g. to scroll through menus.
+    repeat_directions: bool,
+    /// Direction key currently held down, known only from key repeat and
+    /// release events (kitty keyboard protocol).
+    held_direction: Option<Direction>,
+}
 
 impl Default for InputHandler {
     fn default() -> Self {
@@ -46,10 +82,78 @@ impl Default for InputHandler {
 }
 
 impl InputHandler {
-    /// Builds a new input handler.
+    /// Builds a new input handler with the default key bindings.
     #[must_use]
     pub fn new() -> Self {
-        Self
+        Self::with_bindings(KeyBindings::default())
+    }
+
+    /// Builds a new input handler with the given key bindings.
+    #[must_use]
+    pub fn with_bindings(bindings: KeyBindings) -> Self {
+        Self {
+            bindings,
+            text_entry: false,
+            key_capture: false,
+            repeat_directions: true,
+            held_direction: None,
+        }
+    }
+
+    /// Replaces the key bindings, e.g. after they were edited in settings.
+    pub fn set_bindings(&mut self, bindings: KeyBindings) {
+        self.bindings = bindings;
+    }
+
+    /// Switches text entry on or off, e.g. while a hex value is being typed.
+    ///
+    /// Key releases are not tracked while text is entered, so turning it on
+    /// forgets the held direction.
+    pub fn set_text_entry(&mut self, enabled: bool) {
+        self.text_entry = enabled;
+        if enabled {
+            self.held_direction = None;
+        }
+    }
+
+    /// Switches key capture on or off while a key binding is being edited.
+    /// Like text entry, turning it on forgets the held direction.
+    pub fn set_key_capture(&mut self, enabled: bool) {
+        self.key_capture = enabled;
+        if enabled {
+            self.held_direction = None;
+        }
+    }
+
+    /// Switches whether held direction keys repeat; gameplay turns this off
+    /// so holding a key only sprints. Turning repeat on outside gameplay also
+    /// forgets the held direction, so a game never resumes sprinting on a
+    /// key that was let go in a menu.
+    pub fn set_repeat_directions(&mut self, enabled: bool) {
+        self.repeat_directions = enabled;
+        if enabled {
+            self.held_direction = None;
+        }
+    }
+
+    /// Returns the direction key being held down.
+    ///
+    /// Always `None` unless the terminal reports key repeat and release
+    /// events, since a plain key press says nothing about how long the key
+    /// stays down.
+    #[must_use]
+    pub fn held_direction(&self) -> Option<Direction> {
+        self.held_direction
+    }
+
+    /// Blocks until input is waiting or `deadline` passes, without reading
+    /// any events.
+    pub fn wait_until(&self, deadline: Instant) -> io::Result<()> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        if !timeout.is_zero() {
+            event::poll(timeout)?;
+        }
+        Ok(())
     }
 
     /// Polls for one input event without blocking the game loop.
@@ -57,12 +161,15 @@ impl InputHandler {
     /// Keyboard events are drained in a single batch so the latest direction
     /// intent wins while quit/confirm actions still get through immediately.
     pub fn poll_input(&mut self) -> io::Result<Option<GameInput>> {
+        if self.text_entry || self.key_capture {
+            return self.poll_text_input();
+        }
+
         let mut queued_direction: Option<GameInput> = None;
         let mut queued_action: Option<GameInput> = None;
 
         while event::poll(Duration::from_millis(0))? {
-            let terminal_event = event::read()?;
-            let Some(mapped) = map_terminal_event(terminal_event) else {
+            let Some(mapped) = self.map_event(event::read()?) else {
                 continue;
             };
 
@@ -72,8 +179,8 @@ impl InputHandler {
 
             if let GameInput::Direction(direction) = mapped {
                 // Keep the last direction in the batch (most recent intent).
-                // OS key-repeat of the same direction is harmless — the snake's
-                // buffer_direction handles dedup at the game-logic level.
+                // Without the kitty keyboard protocol, OS key-repeat arrives as
+                // more presses; the snake's buffer_direction dedups those.
                 queued_direction = Some(GameInput::Direction(direction));
                 continue;
             }
@@ -87,6 +194,45 @@ impl InputHandler {
 
         Ok(None)
     }
+
+    /// Maps one terminal event to a game input, tracking the held direction
+    /// key along the way.
+    ///
+    /// This is the mapping [`Self::poll_input`] applies to each event it
+    /// reads, for hosts that read crossterm events themselves. Key repeat
+    /// maps to a direction press only while direction repeat is on.
+    pub fn map_event(&mut self, event: Event) -> Option<GameInput> {
+        if self.text_entry || self.key_capture {
+            return map_text_event(event, self.key_capture);
+        }
+
+        match event {
+            Event::Key(key_event) => {
+                self.held_direction =
+                    next_held_direction(self.held_direction, key_event, &self.bindings);
+                if key_event.kind == KeyEventKind::Repeat {
+                    return key_direction(key_event, &self.bindings)
+                        .filter(|_| self.repeat_directions)
+                        .map(GameInput::Direction);
+                }
+            }
+            Event::FocusLost => self.held_direction = None,
+            _ => {}
+        }
+        map_terminal_event(event, &self.bindings)
+    }
+
+    /// Returns the next text-entry or key-capture event without draining the
+    /// queue, so every typed character reaches the caller in order.
+    fn poll_text_input(&mut self) -> io::Result<Option<GameInput>> {
+        while event::poll(Duration::from_millis(0))? {
+            if let Some(mapped) = self.map_event(event::read()?) {
+                return Ok(Some(mapped));
+            }
+        }
+
+        Ok(None)
+    }
 }
 
 fn select_buffered_input(
@@ -100,15 +246,17 @@ fn select_buffered_input(
     queued_action.or(queued_direction)
 }
 
-fn map_terminal_event(event: Event) -> Option<GameInput> {
+fn map_terminal_event(event: Event, bindings: &KeyBindings) -> Option<GameInput> {
     match event {
-        Event::Key(key_event) => map_key_event(key_event),
+        Event::Key(key_event) => map_key_event(key_event, bindings),
+        Event::Mouse(mouse_event) => map_mouse_event(mouse_event),
         Event::Resize(_, _) => Some(GameInput::Resize),
+        Event::FocusLost => Some(GameInput::FocusLost),
         _ => None,
     }
 }
 
-fn map_key_event(key_event: KeyEvent) -> Option<GameInput> {
+fn map_key_event(key_event: KeyEvent, bindings: &KeyBindings) -> Option<GameInput> {
     if !matches!(key_event.kind, KeyEventKind::Press) {
         return None;
     }
@@ -120,32 +268,116 @@ fn map_key_event(key_event: KeyEvent) -> Option<GameInput> {
         return Some(GameInput::Quit);
     }
 
-    match key_code {
-        KeyCode::Up | KeyCode::Char('w') | KeyCode::Char('W') => {
-            Some(GameInput::Direction(Direction::Up))
-        }
-        KeyCode::Down | KeyCode::Char('s') | KeyCode::Char('S') => {
-            Some(GameInput::Direction(Direction::Down))
-        }
-        KeyCode::Left | KeyCode::Char('a') | KeyCode::Char('A') => {
-            Some(GameInput::Direction(Direction::Left))
+    Key::from_code(key_code)
+        .and_then(|key| bindings.action_for(key))
+        .map(|action| action.input())
+}
+
+/// Returns the direction a movement key is bound to, for any event kind.
+fn key_direction(key_event: KeyEvent, bindings: &KeyBindings) -> Option<Direction> {
+    Key::from_code(key_event.code)
+        .and_then(|key| bindings.action_for(key))
+        .and_then(|action| match action.input() {
+            GameInput::Direction(direction) => Some(direction),
+            _ => None,
+        })
+}
+
+/// Tracks the held direction key: a repeat marks it held, and its release or
+/// a press of another direction lets go.
+fn next_held_direction(
+    held: Option<Direction>,
+    key_event: KeyEvent,
+    bindings: &KeyBindings,
+) -> Option<Direction> {
+    let Some(direction) = key_direction(key_event, bindings) else {
+        return held;
+    };
+    match key_event.kind {
+        KeyEventKind::Repeat => Some(direction),
+        KeyEventKind::Press => held.filter(|&held| held == direction),
+        KeyEventKind::Release => held.filter(|&held| held != direction),
+    }
+}
+
+fn map_mouse_event(mouse_event: MouseEvent) -> Option<GameInput> {
+    match mouse_event.kind {
+        MouseEventKind::Down(MouseButton::Left) => Some(GameInput::Click {
+            column: mouse_event.column,
+            row: mouse_event.row,
+        }),
+        MouseEventKind::ScrollUp => Some(GameInput::Scroll(Direction::Up)),
+        MouseEventKind::ScrollDown => Some(GameInput::Scroll(Direction::Down)),
+        _ => None,
+    }
+}
+
+fn map_text_event(event: Event, key_capture: bool) -> Option<GameInput> {
+    match event {
+        Event::Key(key_event) if key_capture => map_capture_key_event(key_event),
+        Event::Key(key_event) => map_text_key_event(key_event),
+        Event::Resize(_, _) => Some(GameInput::Resize),
+        _ => None,
+    }
+}
+
+/// Maps keys while text entry is active: printable characters are passed
+/// through, while Enter, Esc, arrows and Ctrl-C keep their usual meaning.
+fn map_text_key_event(key_event: KeyEvent) -> Option<GameInput> {
+    if !matches!(key_event.kind, KeyEventKind::Press) {
+        return None;
+    }
+
+    match key_event.code {
+        KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some(GameInput::Quit)
         }
-        KeyCode::Right | KeyCode::Char('d') | KeyCode::Char('D') => {
-            Some(GameInput::Direction(Direction::Right))
+        KeyCode::Char(ch) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some(GameInput::Char(ch))
         }
-        KeyCode::Char('p') | KeyCode::Char('P') | KeyCode::Esc => Some(GameInput::Pause),
-        KeyCode::Char('q') | KeyCode::Char('Q') => Some(GameInput::Quit),
-        KeyCode::Enter | KeyCode::Char(' ') => Some(GameInput::Confirm),
-        KeyCode::Char('t') | KeyCode::Char('T') => Some(GameInput::CycleTheme),
+        KeyCode::Backspace => Some(GameInput::Backspace),
+        KeyCode::Enter => Some(GameInput::Confirm),
+        KeyCode::Esc => Some(GameInput::Pause),
+        KeyCode::Up => Some(GameInput::Direction(Direction::Up)),
+        KeyCode::Down => Some(GameInput::Direction(Direction::Down)),
+        KeyCode::Left => Some(GameInput::Direction(Direction::Left)),
+        KeyCode::Right => Some(GameInput::Direction(Direction::Right)),
         _ => None,
     }
 }
 
+/// Maps keys while a binding is being captured: Esc cancels and Ctrl-C
+/// still quits; any other bindable key is reported as itself.
+fn map_capture_key_event(key_event: KeyEvent) -> Option<GameInput> {
+    if !matches!(key_event.kind, KeyEventKind::Press) {
+        return None;
+    }
+
+    match key_event.code {
+        KeyCode::Char('c') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some(GameInput::Quit)
+        }
+        KeyCode::Esc => Some(GameInput::Pause),
+        code => Key::from_code(code).map(GameInput::Bind),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
-
-    use super::{Direction, GameInput, map_key_event, map_terminal_event, select_buffered_input};
+    use crossterm::event::{
+        Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MouseButton,
+        MouseEvent, MouseEventKind,
+    };
+
+    use super::{
+        Direction, GameInput, InputHandler, map_capture_key_event, map_key_event,
+        map_terminal_event, map_text_key_event, next_held_direction, select_buffered_input,
+    };
+    use crate::keymap::{Key, KeyBindings, KeyPreset};
+
+    fn map_default(key_event: KeyEvent) -> Option<GameInput> {
+        map_key_event(key_event, &KeyBindings::default())
+    }
 
     #[test]
     fn opposite_direction_is_correct() {
@@ -160,9 +392,9 @@ mod tests {
         let up = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::NONE);
         let right = KeyEvent::new(KeyCode::Right, KeyModifiers::NONE);
 
-        assert_eq!(map_key_event(up), Some(GameInput::Direction(Direction::Up)));
+        assert_eq!(map_default(up), Some(GameInput::Direction(Direction::Up)));
         assert_eq!(
-            map_key_event(right),
+            map_default(right),
             Some(GameInput::Direction(Direction::Right))
         );
     }
@@ -174,10 +406,10 @@ mod tests {
         let confirm = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
         let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
 
-        assert_eq!(map_key_event(quit), Some(GameInput::Quit));
-        assert_eq!(map_key_event(pause), Some(GameInput::Pause));
-        assert_eq!(map_key_event(confirm), Some(GameInput::Confirm));
-        assert_eq!(map_key_event(ctrl_c), Some(GameInput::Quit));
+        assert_eq!(map_default(quit), Some(GameInput::Quit));
+        assert_eq!(map_default(pause), Some(GameInput::Pause));
+        assert_eq!(map_default(confirm), Some(GameInput::Confirm));
+        assert_eq!(map_default(ctrl_c), Some(GameInput::Quit));
     }
 
     #[test]
@@ -189,17 +421,147 @@ mod tests {
             state: KeyEventState::NONE,
         };
 
-        assert_eq!(map_key_event(release), None);
+        assert_eq!(map_default(release), None);
+    }
+
+    #[test]
+    fn held_direction_follows_repeat_and_release_events() {
+        let bindings = KeyBindings::default();
+        let event = |code, kind| KeyEvent {
+            code,
+            modifiers: KeyModifiers::NONE,
+            kind,
+            state: KeyEventState::NONE,
+        };
+        let step = |held, code, kind| next_held_direction(held, event(code, kind), &bindings);
+
+        assert_eq!(step(None, KeyCode::Right, KeyEventKind::Press), None);
+        let held = step(None, KeyCode::Char('d'), KeyEventKind::Repeat);
+        assert_eq!(held, Some(Direction::Right));
+        assert_eq!(
+            step(held, KeyCode::Char('p'), KeyEventKind::Release),
+            Some(Direction::Right)
+        );
+        assert_eq!(
+            step(held, KeyCode::Up, KeyEventKind::Release),
+            Some(Direction::Right)
+        );
+        assert_eq!(step(held, KeyCode::Right, KeyEventKind::Release), None);
+        assert_eq!(step(held, KeyCode::Up, KeyEventKind::Press), None);
+
+        let repeat = event(KeyCode::Right, KeyEventKind::Repeat);
+        assert_eq!(map_key_event(repeat, &bindings), None);
+    }
+
+    #[test]
+    fn mapped_events_repeat_directions_only_while_enabled() {
+        let mut input = InputHandler::new();
+        let repeat = Event::Key(KeyEvent::new_with_kind(
+            KeyCode::Up,
+            KeyModifiers::NONE,
+            KeyEventKind::Repeat,
+        ));
+
+        assert_eq!(
+            input.map_event(repeat.clone()),
+            Some(GameInput::Direction(Direction::Up))
+        );
+        input.set_repeat_directions(false);
+        assert_eq!(input.map_event(repeat), None);
+        assert_eq!(input.held_direction(), Some(Direction::Up));
+        assert_eq!(
+            input.map_event(Event::FocusLost),
+            Some(GameInput::FocusLost)
+        );
+        assert_eq!(input.held_direction(), None);
+
+        input.set_text_entry(true);
+        let letter = Event::Key(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::NONE));
+        assert_eq!(input.map_event(letter), Some(GameInput::Char('w')));
+    }
+
+    #[test]
+    fn text_entry_and_menus_forget_the_held_direction() {
+        let mut input = InputHandler::new();
+        input.set_repeat_directions(false);
+        let held_up = Event::Key(KeyEvent::new_with_kind(
+            KeyCode::Up,
+            KeyModifiers::NONE,
+            KeyEventKind::Repeat,
+        ));
+        let release_up = Event::Key(KeyEvent::new_with_kind(
+            KeyCode::Up,
+            KeyModifiers::NONE,
+            KeyEventKind::Release,
+        ));
+
+        input.map_event(held_up.clone());
+        input.set_text_entry(true);
+        input.map_event(release_up.clone());
+        input.set_text_entry(false);
+        assert_eq!(input.held_direction(), None);
+
+        input.map_event(held_up.clone());
+        input.set_key_capture(true);
+        assert_eq!(input.held_direction(), None);
+        input.set_key_capture(false);
+
+        input.map_event(held_up);
+        assert_eq!(input.held_direction(), Some(Direction::Up));
+        input.set_repeat_directions(true);
+        assert_eq!(input.held_direction(), None);
     }
 
     #[test]
     fn terminal_resize_event_maps_to_resize_input() {
         assert_eq!(
-            map_terminal_event(Event::Resize(120, 40)),
+            map_terminal_event(Event::Resize(120, 40), &KeyBindings::default()),
             Some(GameInput::Resize)
         );
     }
 
+    #[test]
+    fn focus_lost_event_maps_to_focus_lost_input() {
+        assert_eq!(
+            map_terminal_event(Event::FocusLost, &KeyBindings::default()),
+            Some(GameInput::FocusLost)
+        );
+        assert_eq!(
+            map_terminal_event(Event::FocusGained, &KeyBindings::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn mouse_clicks_and_wheel_map_to_inputs() {
+        let mouse = |kind| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column: 12,
+                row: 7,
+                modifiers: KeyModifiers::NONE,
+            })
+        };
+        let bindings = KeyBindings::default();
+
+        assert_eq!(
+            map_terminal_event(mouse(MouseEventKind::Down(MouseButton::Left)), &bindings),
+            Some(GameInput::Click { column: 12, row: 7 })
+        );
+        assert_eq!(
+            map_terminal_event(mouse(MouseEventKind::ScrollDown), &bindings),
+            Some(GameInput::Scroll(Direction::Down))
+        );
+        assert_eq!(
+            map_terminal_event(mouse(MouseEventKind::Down(MouseButton::Right)), &bindings),
+            None
+        );
+        assert_eq!(
+            map_terminal_event(mouse(MouseEventKind::Moved), &bindings),
+            None
+        );
+    }
+
     #[test]
     fn buffered_input_prioritizes_action_over_direction() {
         let selected = select_buffered_input(
@@ -214,4 +576,47 @@ mod tests {
         let selected = select_buffered_input(Some(GameInput::Direction(Direction::Right)), None);
         assert_eq!(selected, Some(GameInput::Direction(Direction::Right)));
     }
+
+    #[test]
+    fn text_entry_passes_printable_keys_through() {
+        let letter = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+        let shifted = KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT);
+        let backspace = KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE);
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+
+        assert_eq!(map_text_key_event(letter), Some(GameInput::Char('q')));
+        assert_eq!(map_text_key_event(shifted), Some(GameInput::Char('A')));
+        assert_eq!(map_text_key_event(backspace), Some(GameInput::Backspace));
+        assert_eq!(map_text_key_event(ctrl_c), Some(GameInput::Quit));
+    }
+
+    #[test]
+    fn keyboard_mapping_follows_the_bindings() {
+        let vim = KeyBindings::preset(KeyPreset::Vim);
+        let k = KeyEvent::new(KeyCode::Char('K'), KeyModifiers::SHIFT);
+        let w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::NONE);
+
+        assert_eq!(
+            map_key_event(k, &vim),
+            Some(GameInput::Direction(Direction::Up))
+        );
+        assert_eq!(map_key_event(w, &vim), None);
+    }
+
+    #[test]
+    fn key_capture_reports_raw_keys() {
+        let letter = KeyEvent::new(KeyCode::Char('Q'), KeyModifiers::SHIFT);
+        let arrow = KeyEvent::new(KeyCode::Left, KeyModifiers::NONE);
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+
+        assert_eq!(
+            map_capture_key_event(letter),
+            Some(GameInput::Bind(Key::Char('q')))
+        );
+        assert_eq!(
+            map_capture_key_event(arrow),
+            Some(GameInput::Bind(Key::Left))
+        );
+        assert_eq!(map_capture_key_event(esc), Some(GameInput::Pause));
+    }
 }
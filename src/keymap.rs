@@ -0,0 +1,564 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crossterm::event::KeyCode;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::input::{Direction, GameInput};
+
+/// A game action that keys can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Pause,
+    Quit,
+    Confirm,
+    CycleTheme,
+    /// Open the editor for the highlighted theme (only used in the theme picker).
+    EditTheme,
+}
+
+impl Action {
+    /// Every action, in settings-menu order.
+    pub const ALL: [Self; 9] = [
+        Self::Up,
+        Self::Down,
+        Self::Left,
+        Self::Right,
+        Self::Pause,
+        Self::Quit,
+        Self::Confirm,
+        Self::CycleTheme,
+        Self::EditTheme,
+    ];
+
+    /// Returns the input this action produces.
+    #[must_use]
+    pub fn input(self) -> GameInput {
+        match self {
+            Self::Up => GameInput::Direction(Direction::Up),
+            Self::Down => GameInput::Direction(Direction::Down),
+            Self::Left => GameInput::Direction(Direction::Left),
+            Self::Right => GameInput::Direction(Direction::Right),
+            Self::Pause => GameInput::Pause,
+            Self::Quit => GameInput::Quit,
+            Self::Confirm => GameInput::Confirm,
+            Self::CycleTheme => GameInput::CycleTheme,
+            Self::EditTheme => GameInput::EditTheme,
+        }
+    }
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Up => "Up",
+            Self::Down => "Down",
+            Self::Left => "Left",
+            Self::Right => "Right",
+            Self::Pause => "Pause",
+            Self::Quit => "Quit",
+            Self::Confirm => "Confirm",
+            Self::CycleTheme => "Next theme",
+            Self::EditTheme => "Edit theme",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// A key that can be bound, ignoring modifiers. Letters are stored in lower
+/// case so Shift does not change the binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Key {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Esc,
+    Space,
+    Tab,
+    Backspace,
+    F(u8),
+}
+
+impl Key {
+    /// Returns the bindable key for a terminal key code, if there is one.
+    #[must_use]
+    pub fn from_code(code: KeyCode) -> Option<Self> {
+        Some(match code {
+            KeyCode::Char(ch) => Self::from_char(ch),
+            KeyCode::Up => Self::Up,
+            KeyCode::Down => Self::Down,
+            KeyCode::Left => Self::Left,
+            KeyCode::Right => Self::Right,
+            KeyCode::Enter => Self::Enter,
+            KeyCode::Esc => Self::Esc,
+            KeyCode::Tab => Self::Tab,
+            KeyCode::Backspace => Self::Backspace,
+            KeyCode::F(n) => Self::F(n),
+            _ => return None,
+        })
+    }
+
+    fn from_char(ch: char) -> Self {
+        if ch == ' ' {
+            Self::Space
+        } else {
+            Self::Char(ch.to_lowercase().next().unwrap_or(ch))
+        }
+    }
+
+    /// Short name shown in menu footers, e.g. `↑`, `W` or `Enter`.
+    #[must_use]
+    pub fn label(self) -> String {
+        match self {
+            Self::Char(ch) => ch.to_uppercase().collect(),
+            Self::Up => "↑".to_owned(),
+            Self::Down => "↓".to_owned(),
+            Self::Left => "←".to_owned(),
+            Self::Right => "→".to_owned(),
+            Self::Enter => "Enter".to_owned(),
+            Self::Esc => "Esc".to_owned(),
+            Self::Space => "Space".to_owned(),
+            Self::Tab => "Tab".to_owned(),
+            Self::Backspace => "Bksp".to_owned(),
+            Self::F(n) => format!("F{n}"),
+        }
+    }
+
+    fn is_arrow(self) -> bool {
+        matches!(self, Self::Up | Self::Down | Self::Left | Self::Right)
+    }
+}
+
+/// Config file name of the key; parses back to the same key.
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Char(ch) => write!(f, "{ch}"),
+            Self::Up => f.write_str("up"),
+            Self::Down => f.write_str("down"),
+            Self::Left => f.write_str("left"),
+            Self::Right => f.write_str("right"),
+            Self::Enter => f.write_str("enter"),
+            Self::Esc => f.write_str("esc"),
+            Self::Space => f.write_str("space"),
+            Self::Tab => f.write_str("tab"),
+            Self::Backspace => f.write_str("backspace"),
+            Self::F(n) => write!(f, "f{n}"),
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut chars = value.chars();
+        if let (Some(ch), None) = (chars.next(), chars.next()) {
+            return Ok(Self::from_char(ch));
+        }
+        if let Some(n) = value
+            .strip_prefix(['f', 'F'])
+            .and_then(|n| n.parse::<u8>().ok())
+            .filter(|n| (1..=12).contains(n))
+        {
+            return Ok(Self::F(n));
+        }
+        match value {
+            value if value.eq_ignore_ascii_case("up") => Ok(Self::Up),
+            value if value.eq_ignore_ascii_case("down") => Ok(Self::Down),
+            value if value.eq_ignore_ascii_case("left") => Ok(Self::Left),
+            value if value.eq_ignore_ascii_case("right") => Ok(Self::Right),
+            value if value.eq_ignore_ascii_case("enter") => Ok(Self::Enter),
+            value if value.eq_ignore_ascii_case("esc") => Ok(Self::Esc),
+            value if value.eq_ignore_ascii_case("space") => Ok(Self::Space),
+            value if value.eq_ignore_ascii_case("tab") => Ok(Self::Tab),
+            value if value.eq_ignore_ascii_case("backspace") => Ok(Self::Backspace),
+            other => Err(format!(
+                "unknown key '{other}' (expected a single character, up, down, left, right, \
+                 enter, esc, space, tab, backspace or f1-f12)"
+            )),
+        }
+    }
+}
+
+impl Serialize for Key {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Built-in key layouts. Every preset also binds the arrow keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyPreset {
+    /// WASD.
+    #[default]
+    Default,
+    /// HJKL.
+    Vim,
+    /// ZQSD, with Quit moved to X.
+    Azerty,
+    /// IJKL, for the right hand when the left one is on the mouse.
+    LeftHanded,
+}
+
+impl KeyPreset {
+    /// Returns the next preset in settings-menu order.
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Default => Self::Vim,
+            Self::Vim => Self::Azerty,
+            Self::Azerty => Self::LeftHanded,
+            Self::LeftHanded => Self::Default,
+        }
+    }
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Default => "Default",
+            Self::Vim => "Vim",
+            Self::Azerty => "AZERTY",
+            Self::LeftHanded => "Left-hand",
+        }
+    }
+
+    /// Letter keys for up, left, down and right.
+    fn movement_letters(self) -> [char; 4] {
+        match self {
+            Self::Default => ['w', 'a', 's', 'd'],
+            Self::Vim => ['k', 'h', 'j', 'l'],
+            Self::Azerty => ['z', 'q', 's', 'd'],
+            Self::LeftHanded => ['i', 'j', 'k', 'l'],
+        }
+    }
+
+    fn quit_key(self) -> char {
+        match self {
+            Self::Azerty => 'x',
+            _ => 'q',
+        }
+    }
+}
+
+impl FromStr for KeyPreset {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            value if value.eq_ignore_ascii_case("default") => Ok(Self::Default),
+            value if value.eq_ignore_ascii_case("vim") => Ok(Self::Vim),
+            value if value.eq_ignore_ascii_case("azerty") => Ok(Self::Azerty),
+            value if value.eq_ignore_ascii_case("left-handed") => Ok(Self::LeftHanded),
+            other => Err(format!(
+                "unknown key preset '{other}' (expected default, vim, azerty or left-handed)"
+            )),
+        }
+    }
+}
+
+/// A key bound to more than one action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyConflict {
+    pub key: Key,
+    /// The action the key triggers.
+    pub action: Action,
+    /// The action that loses the key.
+    pub shadowed: Action,
+}
+
+impl fmt::Display for KeyConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "key '{}' is bound to both {} and {}; it will {}",
+            self.key,
+            self.action.label(),
+            self.shadowed.label(),
+            self.action.label().to_lowercase()
+        )
+    }
+}
+
+/// Keys bound to each action. The first key of an action is the one shown
+/// in menu footers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    keys: [Vec<Key>; Action::ALL.len()],
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::preset(KeyPreset::Default)
+    }
+}
+
+impl KeyBindings {
+    /// Returns the bindings of a built-in layout.
+    #[must_use]
+    pub fn preset(preset: KeyPreset) -> Self {
+        let [up, left, down, right] = preset.movement_letters().map(Key::Char);
+        let mut keys: [Vec<Key>; Action::ALL.len()] = Default::default();
+        keys[Action::Up.index()] = vec![Key::Up, up];
+        keys[Action::Down.index()] = vec![Key::Down, down];
+        keys[Action::Left.index()] = vec![Key::Left, left];
+        keys[Action::Right.index()] = vec![Key::Right, right];
+        keys[Action::Pause.index()] = vec![Key::Esc, Key::Char('p')];
+        keys[Action::Quit.index()] = vec![Key::Char(preset.quit_key())];
+        keys[Action::Confirm.index()] = vec![Key::Enter, Key::Space];
+        keys[Action::CycleTheme.index()] = vec![Key::Char('t')];
+        keys[Action::EditTheme.index()] = vec![Key::Char('e')];
+        Self { keys }
+    }
+
+    /// Returns these bindings with the listed actions rebound.
+    #[must_use]
+    pub fn with_overrides(mut self, overrides: &BTreeMap<Action, Vec<Key>>) -> Self {
+        for (action, keys) in overrides {
+            self.keys[action.index()] = keys.clone();
+        }
+        self
+    }
+
+    /// Returns the actions whose keys differ from `preset`, as stored in the
+    /// config file.
+    #[must_use]
+    pub fn overrides(&self, preset: KeyPreset) -> BTreeMap<Action, Vec<Key>> {
+        let base = Self::preset(preset);
+        Action::ALL
+            .into_iter()
+            .filter(|action| self.keys(*action) != base.keys(*action))
+            .map(|action| (action, self.keys(action).to_vec()))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn keys(&self, action: Action) -> &[Key] {
+        &self.keys[action.index()]
+    }
+
+    /// Returns the action `key` triggers. When a key is bound twice, the
+    /// action listed first in [`Action::ALL`] wins.
+    #[must_use]
+    pub fn action_for(&self, key: Key) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|action| self.keys(*action).contains(&key))
+    }
+
+    /// Binds `key` as the only key of `action`.
+    ///
+    /// Returns the action that already uses `key`, leaving the bindings
+    /// unchanged, when that is a different action.
+    pub fn rebind(&mut self, action: Action, key: Key) -> Result<(), Action> {
+        match self.action_for(key) {
+            Some(other) if other != action => Err(other),
+            _ => {
+                self.keys[action.index()] = vec![key];
+                Ok(())
+            }
+        }
+    }
+
+    /// Lists keys bound to more than one action.
+    #[must_use]
+    pub fn conflicts(&self) -> Vec<KeyConflict> {
+        let mut conflicts = Vec::new();
+        for (idx, action) in Action::ALL.into_iter().enumerate() {
+            for shadowed in Action::ALL.into_iter().skip(idx + 1) {
+                for &key in self.keys(action) {
+                    if self.keys(shadowed).contains(&key) {
+                        conflicts.push(KeyConflict {
+                            key,
+                            action,
+                            shadowed,
+                        });
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Label of the first key bound to `action`, or `-` when it has none.
+    #[must_use]
+    pub fn label(&self, action: Action) -> String {
+        self.keys(action)
+            .first()
+            .map_or_else(|| "-".to_owned(), |key| key.label())
+    }
+
+    /// Labels of every key bound to `action`, e.g. `↑/W`.
+    #[must_use]
+    pub fn labels(&self, action: Action) -> String {
+        let labels = self
+            .keys(action)
+            .iter()
+            .map(|key| key.label())
+            .collect::<Vec<_>>();
+        if labels.is_empty() {
+            "-".to_owned()
+        } else {
+            labels.join("/")
+        }
+    }
+
+    /// First-key labels of two actions, run together when both are arrows
+    /// (`↑↓`) and slash-separated otherwise (`K/J`).
+    #[must_use]
+    pub fn pair_label(&self, first: Action, second: Action) -> String {
+        let is_arrow = |action| self.keys(action).first().is_some_and(|key| key.is_arrow());
+        if is_arrow(first) && is_arrow(second) {
+            format!("{}{}", self.label(first), self.label(second))
+        } else {
+            format!("{}/{}", self.label(first), self.label(second))
+        }
+    }
+
+    /// Describes the movement keys, e.g. `arrows/WASD`.
+    #[must_use]
+    pub fn movement_label(&self) -> String {
+        let movement = [Action::Up, Action::Left, Action::Down, Action::Right];
+        let sets = movement
+            .map(|action| self.keys(action).len())
+            .into_iter()
+            .max();
+        (0..sets.unwrap_or(0))
+            .filter_map(|slot| {
+                let keys = movement.map(|action| self.keys(action).get(slot).copied());
+                if keys.iter().all(|key| key.is_some_and(Key::is_arrow)) {
+                    return Some("arrows".to_owned());
+                }
+                let labels = keys
+                    .iter()
+                    .flatten()
+                    .map(|key| key.label())
+                    .collect::<Vec<_>>();
+                if labels.is_empty() {
+                    None
+                } else if labels.iter().all(|label| label.chars().count() == 1) {
+                    Some(labels.concat())
+                } else {
+                    Some(labels.join(" "))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use crossterm::event::KeyCode;
+
+    use super::{Action, Key, KeyBindings, KeyPreset};
+
+    #[test]
+    fn presets_bind_their_letters_and_the_arrows() {
+        for (preset, up, left) in [
+            (KeyPreset::Default, 'w', 'a'),
+            (KeyPreset::Vim, 'k', 'h'),
+            (KeyPreset::Azerty, 'z', 'q'),
+            (KeyPreset::LeftHanded, 'i', 'j'),
+        ] {
+            let bindings = KeyBindings::preset(preset);
+            assert_eq!(bindings.action_for(Key::Char(up)), Some(Action::Up));
+            assert_eq!(bindings.action_for(Key::Char(left)), Some(Action::Left));
+            assert_eq!(bindings.action_for(Key::Up), Some(Action::Up));
+            assert!(bindings.conflicts().is_empty(), "{preset:?} has conflicts");
+        }
+        assert_eq!(
+            KeyBindings::preset(KeyPreset::Azerty).action_for(Key::Char('x')),
+            Some(Action::Quit)
+        );
+    }
+
+    #[test]
+    fn keys_parse_and_print_config_names() {
+        for name in ["w", "up", "esc", "space", "f5", "é"] {
+            let key = name.parse::<Key>().expect("key should parse");
+            assert_eq!(key.to_string(), name);
+        }
+        assert_eq!("W".parse::<Key>(), Ok(Key::Char('w')));
+        assert_eq!(" ".parse::<Key>(), Ok(Key::Space));
+        assert_eq!("ENTER".parse::<Key>(), Ok(Key::Enter));
+        assert!("f13".parse::<Key>().is_err());
+        assert!("ctrl".parse::<Key>().is_err());
+        assert_eq!(Key::from_code(KeyCode::Char('Q')), Some(Key::Char('q')));
+    }
+
+    #[test]
+    fn rebinding_refuses_keys_used_by_other_actions() {
+        let mut bindings = KeyBindings::default();
+        assert_eq!(
+            bindings.rebind(Action::Up, Key::Char('q')),
+            Err(Action::Quit)
+        );
+        assert_eq!(bindings.keys(Action::Up), [Key::Up, Key::Char('w')]);
+
+        assert_eq!(bindings.rebind(Action::Up, Key::Char('w')), Ok(()));
+        assert_eq!(bindings.rebind(Action::Quit, Key::Char('x')), Ok(()));
+        assert_eq!(bindings.action_for(Key::Char('q')), None);
+        assert_eq!(
+            bindings.overrides(KeyPreset::Default),
+            BTreeMap::from([
+                (Action::Up, vec![Key::Char('w')]),
+                (Action::Quit, vec![Key::Char('x')]),
+            ])
+        );
+    }
+
+    #[test]
+    fn overrides_that_share_a_key_are_reported_as_conflicts() {
+        let bindings = KeyBindings::default().with_overrides(&BTreeMap::from([(
+            Action::CycleTheme,
+            vec![Key::Char('w')],
+        )]));
+
+        let conflicts = bindings.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].action, Action::Up);
+        assert_eq!(conflicts[0].shadowed, Action::CycleTheme);
+        assert_eq!(bindings.action_for(Key::Char('w')), Some(Action::Up));
+    }
+
+    #[test]
+    fn footer_labels_follow_the_bindings() {
+        let default = KeyBindings::default();
+        assert_eq!(default.pair_label(Action::Up, Action::Down), "↑↓");
+        assert_eq!(default.label(Action::Pause), "Esc");
+        assert_eq!(default.labels(Action::Confirm), "Enter/Space");
+        assert_eq!(default.movement_label(), "arrows/WASD");
+        assert_eq!(
+            KeyBindings::preset(KeyPreset::LeftHanded).movement_label(),
+            "arrows/IJKL"
+        );
+
+        let mut custom = KeyBindings::default();
+        custom
+            .rebind(Action::Up, Key::Char('i'))
+            .expect("i should be free");
+        assert_eq!(custom.pair_label(Action::Up, Action::Down), "I/↓");
+    }
+}
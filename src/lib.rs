@@ -1,12 +1,23 @@
+pub mod background;
 pub mod block_font;
+pub mod color;
 pub mod config;
+pub mod controller;
 pub mod food;
 pub mod game;
+pub mod graphics;
 pub mod input;
+pub mod keymap;
+pub mod occupancy;
 pub mod platform;
 pub mod renderer;
+pub mod scheduler;
 pub mod score;
+pub mod settings;
 pub mod snake;
+#[cfg(feature = "snapshots")]
+pub mod snapshot;
 pub mod terminal_runtime;
 pub mod theme;
 pub mod ui;
+pub mod widget;
@@ -1,25 +1,35 @@
 use std::io;
-use std::thread;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use std::time::{Duration, Instant};
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use crossterm::execute;
+use crossterm::terminal::{BeginSynchronizedUpdate, EndSynchronizedUpdate};
 use ratatui::layout::Size;
+use terminal_snake::background::BackgroundChoice;
+use terminal_snake::color;
 use terminal_snake::config::{
-    DEFAULT_TICK_INTERVAL_MS, GlyphMode, GridSize, HUD_BOTTOM_MARGIN_Y, MAX_START_SPEED_LEVEL,
-    MIN_START_SPEED_LEVEL, MIN_TICK_INTERVAL_MS, PLAY_AREA_MARGIN_X, PLAY_AREA_MARGIN_Y,
-    configure_glyphs,
+    GlyphMode, GridSize, HUD_BOTTOM_MARGIN_Y, MAX_START_SPEED_LEVEL, MIN_START_SPEED_LEVEL,
+    PLAY_AREA_MARGIN_X, PLAY_AREA_MARGIN_Y, configure_glyphs, glyphs,
 };
-use terminal_snake::game::{GameState, GameStatus};
+use terminal_snake::controller::{MenuOutcome, apply_menu_input, wrap_next, wrap_prev};
+use terminal_snake::game::{GameState, GameStatus, GlowEffect};
+use terminal_snake::graphics::{self, GraphicsMode, PixelPlayField};
 use terminal_snake::input::{Direction, GameInput, InputHandler};
-use terminal_snake::platform::Platform;
-use terminal_snake::renderer::{self, MenuUiState};
-use terminal_snake::score::{
-    load_high_score, load_theme_selection, save_high_score, save_theme_selection,
-};
+use terminal_snake::keymap::{KeyBindings, KeyPreset};
+use terminal_snake::platform::{ColorDepth, Platform};
+use terminal_snake::renderer::{self, FieldOptions, FrameLayout, MenuUiState, RenderCache};
+use terminal_snake::scheduler::{FRAME_INTERVAL, TickScheduler, loop_wake_deadline};
+use terminal_snake::score::{load_high_score, load_theme_selection, save_high_score};
+use terminal_snake::settings::{Settings, SettingsFile, SettingsLayer};
 use terminal_snake::terminal_runtime::{AppTerminal, TerminalSession};
-use terminal_snake::theme::ThemeCatalog;
-use terminal_snake::ui::hud::{HudInfo, HudValueFlash};
-use terminal_snake::ui::menu::ThemeSelectView;
+use terminal_snake::theme::import::{self, TerminalPalette};
+use terminal_snake::theme::{Appearance, AppearancePreference, ThemeCatalog, lint};
+use terminal_snake::ui::hud::{HudInfo, HudToast, HudValueFlash};
+use terminal_snake::ui::key_editor::KeyEditor;
+use terminal_snake::ui::menu::{MenuTarget, ThemeSelectView};
+use terminal_snake::ui::theme_editor::{EditorAction, ThemeEditor};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 enum ThemeSelectionMode {
@@ -40,23 +50,32 @@ struct HudTrackedValues {
     coverage_hundredths: u32,
 }
 
-const START_MENU_ITEM_COUNT: usize = 3;
-const START_MENU_START_IDX: usize = 0;
-const START_MENU_SETTINGS_IDX: usize = 1;
-const START_MENU_QUIT_IDX: usize = 2;
+/// How long the theme picker's mock snake takes to move one cell.
+const THEME_PREVIEW_STEP: Duration = Duration::from_millis(90);
+
+/// How often the user theme directory is checked for edits.
+const THEME_RELOAD_POLL_INTERVAL: Duration = Duration::from_millis(500);
 
-const START_SETTINGS_ITEM_COUNT: usize = 5;
+const START_SETTINGS_ITEM_COUNT: usize = 9;
 const START_SETTINGS_SPEED_IDX: usize = 0;
 const START_SETTINGS_THEME_IDX: usize = 1;
-const START_SETTINGS_GRID_IDX: usize = 2;
-const START_SETTINGS_BORDER_IDX: usize = 3;
-const START_SETTINGS_BACK_IDX: usize = 4;
+const START_SETTINGS_APPEARANCE_IDX: usize = 2;
+const START_SETTINGS_BACKGROUND_IDX: usize = 3;
+const START_SETTINGS_BORDER_IDX: usize = 4;
+const START_SETTINGS_SHAPES_IDX: usize = 5;
+const START_SETTINGS_KEYS_IDX: usize = 6;
+const START_SETTINGS_STEERING_IDX: usize = 7;
+const START_SETTINGS_BACK_IDX: usize = 8;
 
 #[derive(Debug, Parser)]
 struct Cli {
-    /// Starting speed level.
-    #[arg(long, default_value_t = 1)]
-    speed: u32,
+    /// Starting speed level [default: 1].
+    #[arg(long)]
+    speed: Option<u32>,
+
+    /// Theme id to start with.
+    #[arg(long, value_name = "ID")]
+    theme: Option<String>,
 
     /// Deprecated compatibility flag; controller support has been removed.
     #[arg(long = "no-controller", hide = true)]
@@ -70,65 +89,272 @@ struct Cli {
     #[arg(long)]
     ascii_glyphs: bool,
 
-    /// Disable the checkerboard background pattern.
+    /// Play-field glyph mode: unicode (half-blocks), ascii, braille, square, or ascii-square.
+    #[arg(long, value_name = "MODE")]
+    glyphs: Option<GlyphMode>,
+
+    /// Play-field background: theme, off, checkerboard, hstripes, vstripes,
+    /// dots, vignette, or gradient.
+    #[arg(long, value_name = "PATTERN")]
+    background: Option<BackgroundChoice>,
+
+    /// Disable the background pattern (same as `--background off`).
     #[arg(long)]
     no_checkerboard: bool,
+
+    /// Hide the border around the play field.
+    #[arg(long)]
+    no_border: bool,
+
+    /// Tell food, super food and the snake apart by shape as well as color.
+    #[arg(long)]
+    shapes: bool,
+
+    /// Turn the snake toward play-field cells clicked with the mouse.
+    #[arg(long)]
+    mouse_steering: bool,
+
+    /// Key layout: default (WASD), vim (HJKL), azerty (ZQSD), or left-handed (IJKL).
+    #[arg(long, value_name = "PRESET")]
+    keys: Option<KeyPreset>,
+
+    /// Theme variant to use: light, dark, or auto (follow the terminal background).
+    #[arg(long, value_name = "MODE")]
+    appearance: Option<AppearancePreference>,
+
+    /// Pixel play-field renderer: off, auto, kitty, or sixel.
+    #[arg(long, value_name = "MODE")]
+    graphics: Option<GraphicsMode>,
+
+    /// Color output depth: truecolor, 256, 16, or none (defaults to detection).
+    #[arg(long, value_name = "DEPTH")]
+    colors: Option<ColorDepth>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+impl Cli {
+    /// Returns the settings given on the command line.
+    fn settings_layer(&self) -> SettingsLayer {
+        SettingsLayer {
+            speed: self.speed,
+            theme: self.theme.clone(),
+            appearance: self.appearance,
+            background: if self.no_checkerboard {
+                Some(BackgroundChoice::Off)
+            } else {
+                self.background
+            },
+            border: self.no_border.then_some(false),
+            shapes: self.shapes.then_some(true),
+            mouse_steering: self.mouse_steering.then_some(true),
+            glyphs: if self.ascii_glyphs {
+                Some(GlyphMode::Ascii)
+            } else {
+                self.glyphs
+            },
+            graphics: self.graphics,
+            colors: self.colors,
+            debug: self.debug.then_some(true),
+            key_preset: self.keys,
+            keys: None,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Work with theme files.
+    Theme {
+        #[command(subcommand)]
+        action: ThemeCommand,
+    },
 }
 
-fn main() -> io::Result<()> {
+#[derive(Debug, Subcommand)]
+enum ThemeCommand {
+    /// Report problems in a theme file; exits non-zero if any are found.
+    Check {
+        /// Theme JSON file to check.
+        file: PathBuf,
+    },
+    /// Convert a terminal color scheme into a user theme.
+    ///
+    /// Reads Alacritty (.toml/.yml), kitty (.conf), Windows Terminal (.json),
+    /// base16 (.yaml) and iTerm (.itermcolors) schemes.
+    Import {
+        /// Color scheme file to convert.
+        file: PathBuf,
+        /// Theme name; defaults to the scheme's name or the file name.
+        #[arg(long)]
+        name: Option<String>,
+        /// Overwrite an existing user theme with the same id.
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+fn main() -> io::Result<ExitCode> {
     let cli = Cli::parse();
+    if let Some(command) = cli.command {
+        return Ok(run_command(command));
+    }
+
+    // Precedence: CLI flag > environment > config file > defaults.
+    let (settings_file, load_error) = SettingsFile::open();
+    if let Some(error) = load_error {
+        eprintln!("Warning: failed to load settings; changes will not be saved: {error}");
+    }
+    let (env_layer, env_warnings) = SettingsLayer::from_env(|key| std::env::var(key).ok());
+    for warning in env_warnings {
+        eprintln!("Warning: {warning}");
+    }
+    let settings = cli
+        .settings_layer()
+        .or(env_layer)
+        .or(settings_file.layer().clone())
+        .resolve();
+    for conflict in settings.keys.conflicts() {
+        eprintln!("Warning: {conflict}");
+    }
+
     let platform = Platform::detect();
-    configure_glyphs(GlyphMode::resolve(cli.ascii_glyphs));
+    let color_depth = settings.colors.unwrap_or(platform.color_depth());
+    configure_glyphs(
+        GlyphMode::resolve(settings.glyphs, platform.unicode_blocks()).for_color_depth(color_depth),
+    );
 
-    run(cli, platform)
+    run(settings, settings_file, platform, color_depth).map(|()| ExitCode::SUCCESS)
 }
 
-fn run(cli: Cli, platform: Platform) -> io::Result<()> {
+fn run_command(command: Command) -> ExitCode {
+    match command {
+        Command::Theme {
+            action: ThemeCommand::Check { file },
+        } => check_theme_file(&file),
+        Command::Theme {
+            action: ThemeCommand::Import { file, name, force },
+        } => match import::import_theme(&file, name.as_deref(), force) {
+            Ok(target) => {
+                println!("Imported {} as {}", file.display(), target.display());
+                ExitCode::SUCCESS
+            }
+            Err(error) => {
+                eprintln!("{}: error: {error}", file.display());
+                ExitCode::FAILURE
+            }
+        },
+    }
+}
+
+fn check_theme_file(path: &Path) -> ExitCode {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(error) => {
+            eprintln!("{}: error: {error}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let id = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("theme");
+    // Built-in and user themes are the candidates for `extends`.
+    let catalog = ThemeCatalog::load(Appearance::Dark);
+    let report = lint::check_theme(id, &raw, catalog.sources());
+    for error in &report.errors {
+        eprintln!("{}: error: {error}", path.display());
+    }
+    for warning in &report.warnings {
+        eprintln!("{}: warning: {warning}", path.display());
+    }
+
+    if report.is_clean() {
+        println!("{}: ok", path.display());
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn run(
+    settings: Settings,
+    mut settings_file: SettingsFile,
+    platform: Platform,
+    color_depth: ColorDepth,
+) -> io::Result<()> {
     // Load before entering raw mode so any warning prints to a clean terminal.
     let mut high_score = load_high_score().unwrap_or_else(|e| {
         eprintln!("Warning: failed to load high score: {e}");
         0
     });
 
-    let mut themes = ThemeCatalog::load();
-    if let Some(saved_theme) = load_theme_selection().unwrap_or(None)
+    let detected_appearance = Appearance::detect(platform);
+    let mut appearance = settings.appearance;
+    let mut themes = ThemeCatalog::load(appearance.resolve(detected_appearance));
+    if let Some(palette) = TerminalPalette::from_platform(platform) {
+        themes.add_terminal_theme(&palette);
+    }
+    // Older versions kept the selected theme next to the high score.
+    let saved_theme = settings
+        .theme
+        .or_else(|| load_theme_selection().unwrap_or(None));
+    if let Some(saved_theme) = saved_theme
         && !themes.select_by_id(&saved_theme)
     {
         eprintln!("Warning: saved theme '{saved_theme}' is unavailable; using default.");
     }
 
-    let mut terminal_session = TerminalSession::enter()?;
+    let mut terminal_session = TerminalSession::enter(platform)?;
     let terminal = terminal_session.terminal_mut();
 
     // Derive grid bounds from ratatui's own size so the logical grid
     // matches the exact frame area the renderer will use.
     let frame_area = terminal.size()?;
-    let mut bounds = grid_bounds_from_frame(frame_area, cli.debug)?;
-    let mut play_area_is_too_small = play_area_too_small(frame_area, cli.debug);
-    let mut input = InputHandler::new();
-    let mut start_speed_level = cli.speed.clamp(1, MAX_START_SPEED_LEVEL);
+    let mut bounds = grid_bounds_from_frame(frame_area, settings.debug)?;
+    let mut play_area_is_too_small = play_area_too_small(frame_area, settings.debug);
+    let mut key_preset = settings.key_preset;
+    let mut bindings = settings.keys;
+    let mut input = InputHandler::with_bindings(bindings.clone());
+    let mut start_speed_level = settings.speed;
     let mut state = GameState::new_with_options(bounds, start_speed_level);
     state.status = GameStatus::Paused;
     let mut game_over_reference_high_score = high_score;
 
-    let mut last_tick = Instant::now();
+    let mut tick_scheduler = TickScheduler::new(Instant::now());
+    let mut needs_redraw = true;
+    let mut last_frame_at = Instant::now();
+    let mut last_frame_size = frame_area;
     let mut last_status = state.status;
     let mut last_input: Option<GameInput> = None;
     let mut last_input_tick: Option<u64> = None;
     let mut start_menu_selected_idx = 0usize;
-    let mut pause_menu_selected_idx = 0usize;
-    let mut game_over_menu_selected_idx = 0usize;
+    // Highlighted entry of the pause or game-over menu.
+    let mut menu_selected_idx = 0usize;
     let mut start_settings_open = false;
     let mut start_settings_selected_idx = 0usize;
     let mut theme_selection_mode: Option<ThemeSelectionMode> = None;
-    let mut checkerboard_enabled = !cli.no_checkerboard;
-    let mut game_border_enabled = true;
+    let mut theme_editor: Option<ThemeEditor> = None;
+    let mut key_editor: Option<KeyEditor> = None;
+    let mut background = settings.background;
+    let mut game_border_enabled = settings.border;
+    let mut shape_coding = settings.shapes;
+    let mut mouse_steering = settings.mouse_steering;
+    let mut frame_layout = FrameLayout::default();
+    let mut render_cache = RenderCache::default();
     let mut start_speed_adjust_mode = false;
-    let mut theme_selection_dirty = false;
     let mut pending_resize_reconcile = false;
     let mut last_resize_reconcile = Instant::now();
+    let mut last_theme_poll = Instant::now();
+    let theme_preview_started = Instant::now();
+    let mut theme_toast = toast_from_warnings(themes.take_warnings(), Instant::now());
     let mut hud_value_flash = HudValueFlash::default();
     let mut last_hud_values: Option<HudTrackedValues> = None;
+    let mut pixel_field = GraphicsMode::resolve(settings.graphics, platform)
+        .filter(|_| color_depth != ColorDepth::Monochrome)
+        .map(PixelPlayField::new);
 
     if play_area_is_too_small && state.status == GameStatus::Playing {
         state.status = GameStatus::Paused;
@@ -137,111 +363,299 @@ fn run(cli: Cli, platform: Platform) -> io::Result<()> {
     loop {
         if pending_resize_reconcile || last_resize_reconcile.elapsed() >= Duration::from_millis(250)
         {
-            reconcile_resize_if_needed(terminal, cli.debug, &mut bounds, &mut state)?;
+            reconcile_resize_if_needed(terminal, settings.debug, &mut bounds, &mut state)?;
             let frame_area = terminal.size()?;
-            play_area_is_too_small = play_area_too_small(frame_area, cli.debug);
+            play_area_is_too_small = play_area_too_small(frame_area, settings.debug);
             if play_area_is_too_small && state.status == GameStatus::Playing {
                 state.status = GameStatus::Paused;
-                pause_menu_selected_idx = 0;
+                menu_selected_idx = 0;
             }
             pending_resize_reconcile = false;
             last_resize_reconcile = Instant::now();
+            if frame_area != last_frame_size {
+                last_frame_size = frame_area;
+                needs_redraw = true;
+                if let Some(pixel_field) = pixel_field.as_mut() {
+                    pixel_field.resize();
+                }
+            }
         }
 
-        terminal.draw(|frame| {
-            let now = Instant::now();
-            let displayed_high_score = high_score.max(state.score);
-            let hud_values = collect_hud_tracked_values(&state, displayed_high_score);
-            update_hud_value_flash(&mut hud_value_flash, &mut last_hud_values, hud_values, now);
-
-            let start_theme_select = if state.is_start_screen()
-                && theme_selection_mode == Some(ThemeSelectionMode::StartMenu)
-            {
-                Some(ThemeSelectView {
-                    selected_idx: themes.current_index(),
-                    themes: themes.items(),
-                })
-            } else {
-                None
-            };
-
-            let pause_theme_select = if state.status == GameStatus::Paused
-                && !state.is_start_screen()
-                && theme_selection_mode == Some(ThemeSelectionMode::PauseMenu)
-            {
-                Some(ThemeSelectView {
-                    selected_idx: themes.current_index(),
-                    themes: themes.items(),
-                })
-            } else {
-                None
-            };
+        if last_theme_poll.elapsed() >= THEME_RELOAD_POLL_INTERVAL {
+            last_theme_poll = Instant::now();
+            if themes.reload_if_changed() {
+                needs_redraw = true;
+                if let Some(toast) = toast_from_warnings(themes.take_warnings(), last_theme_poll) {
+                    theme_toast = Some(toast);
+                }
+            }
+        }
 
-            renderer::render(
-                frame,
-                &state,
-                platform,
-                HudInfo {
-                    high_score: displayed_high_score,
-                    game_over_reference_high_score,
-                    theme: themes.current_theme(),
-                    debug: cli.debug,
-                    debug_line: if cli.debug {
-                        format_debug_line(&state, last_input, last_input_tick)
-                    } else {
-                        String::new()
+        // Static screens are drawn once per change; animations (theme
+        // preview, glow, HUD flashes, toasts) keep redrawing every frame.
+        let now = Instant::now();
+        let animating = (theme_selection_mode.is_some() && theme_editor.is_none())
+            || state.active_glow().is_some_and(GlowEffect::is_active)
+            || hud_value_flash.is_active(now)
+            || theme_toast
+                .as_ref()
+                .is_some_and(|toast| !toast.is_expired(now));
+        if needs_redraw || (animating && now >= last_frame_at + FRAME_INTERVAL) {
+            let pixel_cell_size = pixel_field.as_ref().and_then(PixelPlayField::cell_pixels);
+            let mut pixel_area = None;
+            if platform.synchronized_output() {
+                execute!(terminal.backend_mut(), BeginSynchronizedUpdate)?;
+            }
+            terminal.draw(|frame| {
+                let now = Instant::now();
+                let displayed_high_score = high_score.max(state.score);
+                let hud_values = collect_hud_tracked_values(&state, displayed_high_score);
+                update_hud_value_flash(&mut hud_value_flash, &mut last_hud_values, hud_values, now);
+
+                let preview_step = (now.duration_since(theme_preview_started).as_millis()
+                    / THEME_PREVIEW_STEP.as_millis()) as u64;
+                let start_theme_select = if state.is_start_screen()
+                    && theme_selection_mode == Some(ThemeSelectionMode::StartMenu)
+                {
+                    Some(ThemeSelectView {
+                        selected_idx: themes.current_index(),
+                        themes: themes.items(),
+                        preview_step,
+                        bindings: &bindings,
+                    })
+                } else {
+                    None
+                };
+
+                let pause_theme_select = if state.status == GameStatus::Paused
+                    && !state.is_start_screen()
+                    && theme_selection_mode == Some(ThemeSelectionMode::PauseMenu)
+                {
+                    Some(ThemeSelectView {
+                        selected_idx: themes.current_index(),
+                        themes: themes.items(),
+                        preview_step,
+                        bindings: &bindings,
+                    })
+                } else {
+                    None
+                };
+
+                frame_layout = renderer::render(
+                    frame,
+                    &state,
+                    HudInfo {
+                        high_score: displayed_high_score,
+                        game_over_reference_high_score,
+                        theme: theme_editor
+                            .as_ref()
+                            .map_or_else(|| themes.current_theme(), ThemeEditor::theme),
+                        debug: settings.debug,
+                        debug_line: if settings.debug {
+                            format_debug_line(&state, last_input, last_input_tick)
+                        } else {
+                            String::new()
+                        },
+                        now,
+                        value_flash: hud_value_flash,
+                        toast: theme_toast.as_ref(),
                     },
-                    now,
-                    value_flash: hud_value_flash,
-                },
-                MenuUiState {
-                    start_selected_idx: start_menu_selected_idx,
-                    start_settings_open,
-                    start_settings_selected_idx,
-                    start_speed_level,
-                    start_speed_adjust_mode,
-                    checkerboard_enabled,
-                    game_border_enabled,
-                    play_area_too_small: play_area_is_too_small,
-                    pause_selected_idx: pause_menu_selected_idx,
-                    game_over_selected_idx: game_over_menu_selected_idx,
-                    start_theme_select,
-                    pause_theme_select,
-                },
-            )
-        })?;
+                    MenuUiState {
+                        start_selected_idx: start_menu_selected_idx,
+                        start_settings_open,
+                        start_settings_selected_idx,
+                        start_speed_level,
+                        start_speed_adjust_mode,
+                        appearance,
+                        background,
+                        game_border_enabled,
+                        shape_coding,
+                        play_area_too_small: play_area_is_too_small,
+                        pause_selected_idx: menu_selected_idx,
+                        game_over_selected_idx: menu_selected_idx,
+                        start_theme_select,
+                        pause_theme_select,
+                        theme_editor: theme_editor.as_ref(),
+                        key_editor: key_editor.as_ref(),
+                        key_preset,
+                        bindings: &bindings,
+                        mouse_steering,
+                    },
+                    &mut render_cache,
+                );
+                let gameplay_area = frame_layout.gameplay_area;
+
+                if pixel_cell_size.is_some()
+                    && state.status == GameStatus::Playing
+                    && !play_area_is_too_small
+                {
+                    graphics::reserve_cells(frame.buffer_mut(), gameplay_area);
+                    pixel_area = Some(gameplay_area);
+                }
+                color::quantize_buffer(frame.buffer_mut(), color_depth);
+            })?;
+
+            if let Some(pixel_field) = pixel_field.as_mut() {
+                match (pixel_area, pixel_cell_size) {
+                    // The text composite already knows whether the field
+                    // changed; rasterize only then or when nothing is shown.
+                    (Some(area), Some(_))
+                        if !render_cache.field_changed() && !pixel_field.needs_image(area) => {}
+                    (Some(area), Some(cell_pixels)) => {
+                        let image = renderer::rasterize_play_field(
+                            &state,
+                            themes.current_theme(),
+                            FieldOptions {
+                                background: background.resolve(&themes.current_theme().background),
+                                shape_coding,
+                            },
+                            area,
+                            cell_pixels,
+                        );
+                        pixel_field.show(terminal.backend_mut(), area, image)?;
+                    }
+                    _ => pixel_field.hide(terminal.backend_mut())?,
+                }
+            }
+            if platform.synchronized_output() {
+                execute!(terminal.backend_mut(), EndSynchronizedUpdate)?;
+            }
+            needs_redraw = false;
+            last_frame_at = now;
+        }
+
+        let tick_interval = (state.status == GameStatus::Playing).then(|| state.tick_interval());
+        // The draw may have just started a HUD value flash.
+        let last_frame =
+            (animating || hud_value_flash.is_active(Instant::now())).then_some(last_frame_at);
+        input.wait_until(loop_wake_deadline(
+            Instant::now(),
+            &tick_scheduler,
+            tick_interval,
+            last_frame,
+        ))?;
 
         if let Some(game_input) = input.poll_input()? {
+            needs_redraw = true;
             if matches!(game_input, GameInput::Resize) {
                 pending_resize_reconcile = true;
                 continue;
             }
 
+            // Clicks on a menu entry select it and then act like Enter; the
+            // wheel scrolls the theme list like the arrow keys.
+            let game_input = match game_input {
+                GameInput::Click { column, row } => {
+                    if let Some(target) = frame_layout.menu_hits.target_at(column, row) {
+                        match target {
+                            MenuTarget::StartItem(idx) => start_menu_selected_idx = idx,
+                            MenuTarget::SettingsItem(idx) => start_settings_selected_idx = idx,
+                            MenuTarget::PauseItem(idx) | MenuTarget::GameOverItem(idx) => {
+                                menu_selected_idx = idx;
+                            }
+                            MenuTarget::Theme(idx) => {
+                                themes.select_index(idx);
+                                remember_theme(&mut settings_file, &themes);
+                            }
+                        }
+                        GameInput::Confirm
+                    } else {
+                        if mouse_steering
+                            && state.status == GameStatus::Playing
+                            && let Some(cell) = renderer::grid_position_at(
+                                frame_layout.gameplay_area,
+                                bounds,
+                                column,
+                                row,
+                            )
+                        {
+                            state.steer_toward(cell);
+                        }
+                        continue;
+                    }
+                }
+                GameInput::Scroll(direction)
+                    if theme_selection_mode.is_some()
+                        && theme_editor.is_none()
+                        && key_editor.is_none() =>
+                {
+                    GameInput::Direction(direction)
+                }
+                GameInput::Scroll(_) => continue,
+                other => other,
+            };
+
             last_input = Some(game_input);
             last_input_tick = Some(state.tick_count);
 
             if matches!(game_input, GameInput::Quit) {
-                persist_selected_theme_if_dirty(&themes, &mut theme_selection_dirty);
                 break;
             }
 
+            if let Some(editor) = theme_editor.as_mut() {
+                match editor.handle_input(game_input) {
+                    EditorAction::Save => match themes.save_user_theme(editor.theme()) {
+                        Ok(id) => {
+                            theme_toast =
+                                Some(HudToast::new(format!("Saved theme '{id}'"), Instant::now()));
+                            theme_editor = None;
+                            remember_theme(&mut settings_file, &themes);
+                        }
+                        Err(error) => editor.set_error(format!("Save failed: {error}")),
+                    },
+                    EditorAction::Close => theme_editor = None,
+                    EditorAction::None => {}
+                }
+                input.set_text_entry(
+                    theme_editor
+                        .as_ref()
+                        .is_some_and(ThemeEditor::wants_text_input),
+                );
+
+                continue;
+            }
+
+            if let Some(editor) = key_editor.as_mut() {
+                match editor.handle_input(game_input) {
+                    EditorAction::Save => {
+                        key_preset = editor.preset();
+                        bindings = editor.bindings().clone();
+                        input.set_bindings(bindings.clone());
+                        if let Err(error) =
+                            save_key_bindings(&mut settings_file, key_preset, &bindings)
+                        {
+                            theme_toast = Some(settings_error_toast(&error, Instant::now()));
+                        }
+                        key_editor = None;
+                    }
+                    EditorAction::Close => key_editor = None,
+                    EditorAction::None => {}
+                }
+                input.set_key_capture(key_editor.as_ref().is_some_and(KeyEditor::is_capturing));
+
+                continue;
+            }
+
             if state.is_start_screen() {
                 if theme_selection_mode == Some(ThemeSelectionMode::StartMenu) {
                     match game_input {
                         GameInput::Direction(Direction::Up) => {
                             themes.select_previous();
-                            theme_selection_dirty = true;
+                            remember_theme(&mut settings_file, &themes);
                         }
                         GameInput::Direction(Direction::Down) | GameInput::CycleTheme => {
                             themes.select_next();
-                            theme_selection_dirty = true;
+                            remember_theme(&mut settings_file, &themes);
                         }
                         GameInput::Confirm
                         | GameInput::Direction(Direction::Right)
                         | GameInput::Pause
                         | GameInput::Direction(Direction::Left) => {
                             theme_selection_mode = None;
-                            persist_selected_theme_if_dirty(&themes, &mut theme_selection_dirty);
+                            persist_settings(&mut settings_file, &mut theme_toast);
+                        }
+                        GameInput::EditTheme => {
+                            theme_editor = Some(ThemeEditor::new(themes.current_theme()));
                         }
                         _ => {}
                     }
@@ -268,6 +682,7 @@ fn run(cli: Cli, platform: Platform) -> io::Result<()> {
                         | GameInput::Direction(Direction::Left)
                         | GameInput::Pause => {
                             start_speed_adjust_mode = false;
+                            settings_file.update(|layer| layer.speed = Some(start_speed_level));
                         }
                         _ => {}
                     }
@@ -293,20 +708,45 @@ fn run(cli: Cli, platform: Platform) -> io::Result<()> {
                                 START_SETTINGS_THEME_IDX => {
                                     theme_selection_mode = Some(ThemeSelectionMode::StartMenu)
                                 }
-                                START_SETTINGS_GRID_IDX => {
-                                    checkerboard_enabled = !checkerboard_enabled;
+                                START_SETTINGS_APPEARANCE_IDX => {
+                                    appearance = appearance.next();
+                                    themes.set_appearance(appearance.resolve(detected_appearance));
+                                    settings_file
+                                        .update(|layer| layer.appearance = Some(appearance));
+                                }
+                                START_SETTINGS_BACKGROUND_IDX => {
+                                    background = background.next();
+                                    settings_file
+                                        .update(|layer| layer.background = Some(background));
                                 }
                                 START_SETTINGS_BORDER_IDX => {
                                     game_border_enabled = !game_border_enabled;
+                                    settings_file
+                                        .update(|layer| layer.border = Some(game_border_enabled));
+                                }
+                                START_SETTINGS_SHAPES_IDX => {
+                                    shape_coding = !shape_coding;
+                                    settings_file.update(|layer| layer.shapes = Some(shape_coding));
+                                }
+                                START_SETTINGS_KEYS_IDX => {
+                                    key_editor = Some(KeyEditor::new(key_preset, &bindings));
+                                }
+                                START_SETTINGS_STEERING_IDX => {
+                                    mouse_steering = !mouse_steering;
+                                    settings_file.update(|layer| {
+                                        layer.mouse_steering = Some(mouse_steering)
+                                    });
                                 }
                                 START_SETTINGS_BACK_IDX => {
                                     start_settings_open = false;
+                                    persist_settings(&mut settings_file, &mut theme_toast);
                                 }
                                 _ => {}
                             }
                         }
                         GameInput::Direction(Direction::Left) | GameInput::Pause => {
                             start_settings_open = false;
+                            persist_settings(&mut settings_file, &mut theme_toast);
                         }
                         _ => {}
                     }
@@ -314,93 +754,44 @@ fn run(cli: Cli, platform: Platform) -> io::Result<()> {
                     continue;
                 }
 
-                match game_input {
-                    GameInput::Direction(Direction::Up) => {
-                        start_menu_selected_idx =
-                            wrap_prev(start_menu_selected_idx, START_MENU_ITEM_COUNT);
-                    }
-                    GameInput::Direction(Direction::Down) => {
-                        start_menu_selected_idx =
-                            wrap_next(start_menu_selected_idx, START_MENU_ITEM_COUNT);
+                match apply_menu_input(
+                    &mut state,
+                    &mut start_menu_selected_idx,
+                    game_input,
+                    !play_area_is_too_small,
+                ) {
+                    MenuOutcome::OpenSettings => {
+                        start_settings_open = true;
+                        start_settings_selected_idx = 0;
                     }
-                    GameInput::Confirm | GameInput::Direction(Direction::Right) => {
-                        match start_menu_selected_idx {
-                            START_MENU_START_IDX => {
-                                if !play_area_is_too_small {
-                                    state = GameState::new_with_options(bounds, start_speed_level);
-                                    state.status = GameStatus::Playing;
-                                }
-                            }
-                            START_MENU_SETTINGS_IDX => {
-                                start_settings_open = true;
-                                start_settings_selected_idx = 0;
-                            }
-                            START_MENU_QUIT_IDX => {
-                                persist_selected_theme_if_dirty(
-                                    &themes,
-                                    &mut theme_selection_dirty,
-                                );
-                                break;
-                            }
-                            _ => {}
-                        }
-                    }
-                    GameInput::Pause => {}
+                    MenuOutcome::Quit => break,
                     _ => {}
                 }
 
                 continue;
             }
 
-            if state.status == GameStatus::Paused {
-                if theme_selection_mode == Some(ThemeSelectionMode::PauseMenu) {
-                    match game_input {
-                        GameInput::Direction(Direction::Up) => {
-                            themes.select_previous();
-                            theme_selection_dirty = true;
-                        }
-                        GameInput::Direction(Direction::Down) | GameInput::CycleTheme => {
-                            themes.select_next();
-                            theme_selection_dirty = true;
-                        }
-                        GameInput::Confirm
-                        | GameInput::Direction(Direction::Right)
-                        | GameInput::Pause
-                        | GameInput::Direction(Direction::Left) => {
-                            theme_selection_mode = None;
-                            persist_selected_theme_if_dirty(&themes, &mut theme_selection_dirty);
-                        }
-                        _ => {}
-                    }
-
-                    continue;
-                }
-
+            if state.status == GameStatus::Paused
+                && theme_selection_mode == Some(ThemeSelectionMode::PauseMenu)
+            {
                 match game_input {
                     GameInput::Direction(Direction::Up) => {
-                        pause_menu_selected_idx = wrap_prev(pause_menu_selected_idx, 3);
+                        themes.select_previous();
+                        remember_theme(&mut settings_file, &themes);
                     }
-                    GameInput::Direction(Direction::Down) => {
-                        pause_menu_selected_idx = wrap_next(pause_menu_selected_idx, 3);
+                    GameInput::Direction(Direction::Down) | GameInput::CycleTheme => {
+                        themes.select_next();
+                        remember_theme(&mut settings_file, &themes);
                     }
-                    GameInput::Confirm | GameInput::Direction(Direction::Right) => {
-                        match pause_menu_selected_idx {
-                            0 if !play_area_is_too_small => state.status = GameStatus::Playing,
-                            1 => theme_selection_mode = Some(ThemeSelectionMode::PauseMenu),
-                            2 => {
-                                persist_selected_theme_if_dirty(
-                                    &themes,
-                                    &mut theme_selection_dirty,
-                                );
-                                break;
-                            }
-                            _ => {}
-                        }
+                    GameInput::Confirm
+                    | GameInput::Direction(Direction::Right)
+                    | GameInput::Pause
+                    | GameInput::Direction(Direction::Left) => {
+                        theme_selection_mode = None;
+                        persist_settings(&mut settings_file, &mut theme_toast);
                     }
-                    GameInput::Pause | GameInput::Direction(Direction::Left)
-                        if !play_area_is_too_small =>
-                    {
-                        state.status = GameStatus::Playing
+                    GameInput::EditTheme => {
+                        theme_editor = Some(ThemeEditor::new(themes.current_theme()));
                     }
                     _ => {}
                 }
@@ -408,51 +799,43 @@ fn run(cli: Cli, platform: Platform) -> io::Result<()> {
                 continue;
             }
 
-            if matches!(state.status, GameStatus::GameOver | GameStatus::Victory) {
-                match game_input {
-                    GameInput::Direction(Direction::Up) => {
-                        game_over_menu_selected_idx = wrap_prev(game_over_menu_selected_idx, 2);
-                    }
-                    GameInput::Direction(Direction::Down) => {
-                        game_over_menu_selected_idx = wrap_next(game_over_menu_selected_idx, 2);
-                    }
-                    GameInput::Confirm | GameInput::Direction(Direction::Right) => {
-                        if game_over_menu_selected_idx == 0 {
-                            state = state.restart();
-                            state.status = GameStatus::Paused;
-                        } else {
-                            persist_selected_theme_if_dirty(&themes, &mut theme_selection_dirty);
-                            break;
-                        }
-                    }
-                    _ => {}
+            match apply_menu_input(
+                &mut state,
+                &mut menu_selected_idx,
+                game_input,
+                !play_area_is_too_small,
+            ) {
+                MenuOutcome::ChooseTheme => {
+                    theme_selection_mode = Some(ThemeSelectionMode::PauseMenu);
                 }
-
-                continue;
-            }
-
-            match game_input {
-                GameInput::CycleTheme => {
+                MenuOutcome::CycleTheme => {
                     themes.select_next();
-                    theme_selection_dirty = true;
+                    remember_theme(&mut settings_file, &themes);
                 }
-                other => handle_input(&mut state, other),
+                MenuOutcome::Quit => break,
+                MenuOutcome::Ignored | MenuOutcome::Changed | MenuOutcome::OpenSettings => {}
             }
         }
 
-        let tick_interval = tick_interval_for_speed(state.speed_level);
-        if last_tick.elapsed() >= tick_interval {
-            if state.status == GameStatus::Playing {
+        input.set_repeat_directions(state.status != GameStatus::Playing);
+        state.set_held_direction(input.held_direction());
+        if state.status == GameStatus::Playing {
+            let tick_interval = state.tick_interval();
+            for _ in 0..tick_scheduler.due_ticks(Instant::now(), tick_interval) {
                 state.record_tick_duration(tick_interval);
+                state.tick();
+                needs_redraw = true;
+                if state.status != GameStatus::Playing {
+                    break;
+                }
             }
-            state.tick();
-            last_tick = Instant::now();
+        } else {
+            tick_scheduler.reset(Instant::now());
         }
 
         if state.status != last_status {
             if matches!(state.status, GameStatus::GameOver | GameStatus::Victory) {
                 game_over_reference_high_score = high_score;
-                game_over_menu_selected_idx = 0;
                 theme_selection_mode = None;
                 start_speed_adjust_mode = false;
                 start_settings_open = false;
@@ -467,7 +850,6 @@ fn run(cli: Cli, platform: Platform) -> io::Result<()> {
             }
 
             if state.status == GameStatus::Paused && !state.is_start_screen() {
-                pause_menu_selected_idx = 0;
                 start_speed_adjust_mode = false;
                 start_settings_open = false;
             }
@@ -478,45 +860,60 @@ fn run(cli: Cli, platform: Platform) -> io::Result<()> {
                 start_settings_open = false;
             }
 
+            menu_selected_idx = 0;
             last_status = state.status;
         }
-
-        thread::sleep(Duration::from_millis(16));
     }
 
-    persist_selected_theme_if_dirty(&themes, &mut theme_selection_dirty);
+    // Report a failed final save once the game screen is gone.
+    drop(terminal_session);
+    if let Err(error) = settings_file.save_if_dirty() {
+        eprintln!("Failed to save settings: {error}");
+    }
 
     Ok(())
 }
 
-fn persist_selected_theme(catalog: &ThemeCatalog) {
-    if let Err(e) = save_theme_selection(catalog.current_id(), &catalog.current_theme().name) {
-        eprintln!("Failed to save theme: {e}");
-    }
+/// Builds a toast for theme load problems, noting how many were folded in.
+fn toast_from_warnings(warnings: Vec<String>, now: Instant) -> Option<HudToast> {
+    let first = warnings.first()?;
+    let message = match warnings.len() {
+        1 => first.clone(),
+        count => format!("{first} (+{} more)", count - 1),
+    };
+    Some(HudToast::new(message, now))
 }
 
-fn persist_selected_theme_if_dirty(catalog: &ThemeCatalog, dirty: &mut bool) {
-    if *dirty {
-        persist_selected_theme(catalog);
-        *dirty = false;
-    }
+fn remember_theme(settings_file: &mut SettingsFile, catalog: &ThemeCatalog) {
+    settings_file.update(|layer| layer.theme = Some(catalog.current_id().to_owned()));
 }
 
-fn handle_input(state: &mut GameState, input: GameInput) {
-    match input {
-        GameInput::Confirm if state.is_start_screen() => {
-            state.status = GameStatus::Playing;
-        }
-        GameInput::Confirm
-            if matches!(state.status, GameStatus::GameOver | GameStatus::Victory) =>
-        {
-            *state = state.restart();
-            state.status = GameStatus::Paused;
-        }
-        other => state.apply_input(other),
+/// Stores edited bindings as the preset plus the actions that differ from it.
+fn save_key_bindings(
+    settings_file: &mut SettingsFile,
+    preset: KeyPreset,
+    bindings: &KeyBindings,
+) -> io::Result<()> {
+    let overrides = bindings.overrides(preset);
+    settings_file.update(|layer| {
+        layer.key_preset = Some(preset);
+        layer.keys = (!overrides.is_empty()).then_some(overrides);
+    });
+    settings_file.save_if_dirty()
+}
+
+/// Saves pending settings changes from inside the game, where stderr is
+/// hidden behind the game screen, so a failure is shown as a HUD toast.
+fn persist_settings(settings_file: &mut SettingsFile, toast: &mut Option<HudToast>) {
+    if let Err(error) = settings_file.save_if_dirty() {
+        *toast = Some(settings_error_toast(&error, Instant::now()));
     }
 }
 
+fn settings_error_toast(error: &io::Error, now: Instant) -> HudToast {
+    HudToast::new(format!("Failed to save settings: {error}"), now)
+}
+
 /// Derives grid bounds from the ratatui frame area.
 ///
 /// This uses the exact same dimensions as the renderer, eliminating any
@@ -548,14 +945,9 @@ fn grid_bounds_from_frame(size: Size, debug_enabled: bool) -> io::Result<GridSiz
         .saturating_sub(hud_rows)
         .saturating_sub(PLAY_AREA_MARGIN_Y.saturating_mul(2));
 
-    // Each terminal row holds 2 game rows (half-block rendering),
-    // so the logical game height is double the available terminal rows.
-    let game_h = viewport_h.saturating_mul(2);
-
-    let width = viewport_w;
-    let height = game_h;
-
-    Ok(GridSize { width, height })
+    // Each terminal cell packs several logical cells (1x2 for half-blocks,
+    // 2x4 for Braille), so scale the viewport by the active glyph mode.
+    Ok(glyphs().mode.grid_size_for_viewport(viewport_w, viewport_h))
 }
 
 fn play_area_too_small(size: Size, debug_enabled: bool) -> bool {
@@ -569,9 +961,9 @@ fn play_area_too_small(size: Size, debug_enabled: bool) -> bool {
         .height
         .saturating_sub(hud_rows)
         .saturating_sub(PLAY_AREA_MARGIN_Y.saturating_mul(2));
-    let game_h = viewport_h.saturating_mul(2);
+    let game = glyphs().mode.grid_size_for_viewport(viewport_w, viewport_h);
 
-    viewport_w < MIN_GAME_AREA_CELLS || game_h < MIN_GAME_AREA_CELLS
+    game.width < MIN_GAME_AREA_CELLS || game.height < MIN_GAME_AREA_CELLS
 }
 
 fn format_debug_line(
@@ -614,14 +1006,6 @@ fn reconcile_resize_if_needed(
     Ok(())
 }
 
-fn tick_interval_for_speed(speed_level: u32) -> Duration {
-    let speed_penalty_ms = u64::from(speed_level.saturating_sub(1)) * 10;
-    let clamped_ms = DEFAULT_TICK_INTERVAL_MS
-        .saturating_sub(speed_penalty_ms)
-        .max(MIN_TICK_INTERVAL_MS);
-    Duration::from_millis(clamped_ms)
-}
-
 fn collect_hud_tracked_values(state: &GameState, displayed_high_score: u32) -> HudTrackedValues {
     HudTrackedValues {
         length: state.snake.len(),
@@ -678,11 +1062,3 @@ fn update_hud_value_flash(
 
     *previous = Some(current);
 }
-
-fn wrap_next(current: usize, len: usize) -> usize {
-    (current + 1) % len
-}
-
-fn wrap_prev(current: usize, len: usize) -> usize {
-    if current == 0 { len - 1 } else { current - 1 }
-}
@@ -0,0 +1,202 @@
+use rand::Rng;
+
+use crate::config::GridSize;
+use crate::snake::Position;
+
+/// Marks a cell that is not in the free list.
+const NOT_FREE: u32 = u32::MAX;
+
+/// Per-cell occupancy for one board, updated one cell at a time.
+///
+/// Each cell keeps a segment count rather than a single bit so a snake that
+/// has just run into itself still reads as occupied after one of the two
+/// overlapping segments leaves. Free cells are also kept in a dense list,
+/// which makes picking a random free cell constant time.
+#[derive(Debug, Clone, Default)]
+pub struct OccupancyGrid {
+    bounds: GridSize,
+    counts: Vec<u16>,
+    /// Indices of all cells with a zero count, in no particular order.
+    free: Vec<u32>,
+    /// Position of each cell in `free`, or [`NOT_FREE`].
+    free_slot: Vec<u32>,
+}
+
+impl OccupancyGrid {
+    /// Creates a grid with every cell of `bounds` free.
+    #[must_use]
+    pub fn new(bounds: GridSize) -> Self {
+        let cells = bounds.total_cells();
+        let cell_ids = 0..u32::try_from(cells).expect("board cell count must fit in u32");
+        Self {
+            bounds,
+            counts: vec![0; cells],
+            free: cell_ids.clone().collect(),
+            free_slot: cell_ids.collect(),
+        }
+    }
+
+    /// Returns the board this grid covers.
+    #[must_use]
+    pub fn bounds(&self) -> GridSize {
+        self.bounds
+    }
+
+    /// Returns how many segments cover `position`; cells outside the board
+    /// read as zero.
+    #[must_use]
+    pub fn count(&self, position: Position) -> u16 {
+        self.index(position).map_or(0, |idx| self.counts[idx])
+    }
+
+    /// Returns true if anything covers `position`.
+    #[must_use]
+    pub fn is_occupied(&self, position: Position) -> bool {
+        self.count(position) > 0
+    }
+
+    /// Returns true if `position` lies on this grid's board.
+    #[must_use]
+    pub fn contains(&self, position: Position) -> bool {
+        self.index(position).is_some()
+    }
+
+    /// Adds one segment at `position`. Returns false, changing nothing, when
+    /// the position is off the board.
+    pub fn insert(&mut self, position: Position) -> bool {
+        let Some(idx) = self.index(position) else {
+            return false;
+        };
+        if self.counts[idx] == 0 {
+            self.take_free(idx);
+        }
+        self.counts[idx] = self.counts[idx].saturating_add(1);
+        true
+    }
+
+    /// Removes one segment from `position`. Returns false, changing nothing,
+    /// when the position is off the board.
+    pub fn remove(&mut self, position: Position) -> bool {
+        let Some(idx) = self.index(position) else {
+            return false;
+        };
+        debug_assert!(self.counts[idx] > 0, "removing from an empty cell");
+        self.counts[idx] = self.counts[idx].saturating_sub(1);
+        if self.counts[idx] == 0 && self.free_slot[idx] == NOT_FREE {
+            self.free_slot[idx] = self.free.len() as u32;
+            self.free.push(idx as u32);
+        }
+        true
+    }
+
+    /// Returns the number of free cells.
+    #[must_use]
+    pub fn free_len(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Returns the `n`th free cell; the order is arbitrary but stable until
+    /// the next change.
+    #[must_use]
+    pub fn free_cell(&self, n: usize) -> Option<Position> {
+        self.free.get(n).map(|&idx| self.position(idx as usize))
+    }
+
+    /// Picks a uniformly random free cell.
+    pub fn random_free<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Position> {
+        if self.free.is_empty() {
+            return None;
+        }
+        self.free_cell(rng.gen_range(0..self.free.len()))
+    }
+
+    fn take_free(&mut self, idx: usize) {
+        let slot = self.free_slot[idx] as usize;
+        self.free.swap_remove(slot);
+        if let Some(&moved) = self.free.get(slot) {
+            self.free_slot[moved as usize] = slot as u32;
+        }
+        self.free_slot[idx] = NOT_FREE;
+    }
+
+    fn index(&self, position: Position) -> Option<usize> {
+        position
+            .is_within_bounds(self.bounds)
+            .then(|| position.y as usize * usize::from(self.bounds.width) + position.x as usize)
+    }
+
+    fn position(&self, idx: usize) -> Position {
+        let width = usize::from(self.bounds.width);
+        Position {
+            x: (idx % width) as i32,
+            y: (idx / width) as i32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use super::OccupancyGrid;
+    use crate::config::GridSize;
+    use crate::snake::Position;
+
+    const BOUNDS: GridSize = GridSize {
+        width: 4,
+        height: 3,
+    };
+
+    #[test]
+    fn counts_overlaps_and_keeps_the_free_list_in_step() {
+        let mut grid = OccupancyGrid::new(BOUNDS);
+        let cell = Position { x: 2, y: 1 };
+        assert_eq!(grid.free_len(), 12);
+
+        assert!(grid.insert(cell));
+        assert!(grid.insert(cell));
+        assert_eq!(grid.count(cell), 2);
+        assert_eq!(grid.free_len(), 11);
+
+        grid.remove(cell);
+        assert!(grid.is_occupied(cell));
+        grid.remove(cell);
+        assert!(!grid.is_occupied(cell));
+        assert_eq!(grid.free_len(), 12);
+
+        assert!(!grid.insert(Position { x: -1, y: 0 }));
+        assert!(!grid.insert(Position { x: 4, y: 0 }));
+        assert_eq!(grid.free_len(), 12);
+    }
+
+    #[test]
+    fn random_free_cells_are_never_occupied() {
+        let mut grid = OccupancyGrid::new(BOUNDS);
+        for x in 0..4 {
+            for y in 0..2 {
+                grid.insert(Position { x, y });
+            }
+        }
+        grid.remove(Position { x: 1, y: 1 });
+
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..50 {
+            let cell = grid.random_free(&mut rng).expect("free cells remain");
+            assert!(!grid.is_occupied(cell));
+        }
+        let mut free: Vec<_> = (0..grid.free_len())
+            .filter_map(|n| grid.free_cell(n))
+            .collect();
+        free.sort_by_key(|cell| (cell.y, cell.x));
+        assert_eq!(free.len(), 5);
+        assert_eq!(free[0], Position { x: 1, y: 1 });
+        assert_eq!(free[1], Position { x: 0, y: 2 });
+
+        for x in 0..4 {
+            grid.insert(Position { x, y: 2 });
+        }
+        grid.insert(Position { x: 1, y: 1 });
+        assert_eq!(grid.random_free(&mut rng), None);
+    }
+}
@@ -1,17 +1,98 @@
 use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::time::Duration;
+
+/// How long to wait for the terminal to answer capability queries.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(150);
+/// How long stdin must stay silent before late probe replies count as drained.
+const LATE_REPLY_QUIET: Duration = Duration::from_millis(50);
+/// Upper bound on draining late replies from a slow terminal.
+const LATE_REPLY_LIMIT: Duration = Duration::from_millis(500);
+
+/// Color output depth the terminal advertises.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum ColorDepth {
+    /// No color at all, as requested through `NO_COLOR`.
+    Monochrome,
+    /// The 16 named ANSI colors.
+    Ansi16,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// 24-bit RGB.
+    TrueColor,
+}
+
+impl std::str::FromStr for ColorDepth {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            value if value.eq_ignore_ascii_case("truecolor") => Ok(Self::TrueColor),
+            value if value.eq_ignore_ascii_case("24bit") => Ok(Self::TrueColor),
+            "256" => Ok(Self::Ansi256),
+            "16" => Ok(Self::Ansi16),
+            value if value.eq_ignore_ascii_case("none") => Ok(Self::Monochrome),
+            value if value.eq_ignore_ascii_case("mono") => Ok(Self::Monochrome),
+            other => Err(format!(
+                "unknown color depth '{other}' (expected truecolor, 256, 16 or none)"
+            )),
+        }
+    }
+}
 
 /// Runtime platform capabilities relevant to this game.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct Platform {
     is_wsl: bool,
+    color_depth: ColorDepth,
+    unicode_blocks: bool,
+    kitty_keyboard: bool,
+    kitty_graphics: bool,
+    sixel: bool,
+    synchronized_output: bool,
+    focus_events: bool,
+    background: Option<(u8, u8, u8)>,
+    foreground: Option<(u8, u8, u8)>,
+    palette: [Option<(u8, u8, u8)>; 16],
 }
 
 impl Platform {
     /// Detects platform details from the current runtime environment.
+    ///
+    /// When stdin and stdout are both terminals, this briefly enters raw mode
+    /// to query the terminal directly; call it before the terminal session
+    /// starts so replies cannot leak into game input.
     #[must_use]
     pub fn detect() -> Self {
+        let env = |key: &str| std::env::var(key).ok();
+        let replies = if io::stdin().is_terminal() && io::stdout().is_terminal() {
+            query_terminal(PROBE_TIMEOUT)
+                .map(|raw| parse_probe_replies(&raw))
+                .unwrap_or_default()
+        } else {
+            ProbeReplies::default()
+        };
+
+        Self::from_sources(detect_wsl(), env, replies)
+    }
+
+    fn from_sources(
+        is_wsl: bool,
+        env: impl Fn(&str) -> Option<String>,
+        replies: ProbeReplies,
+    ) -> Self {
         Self {
-            is_wsl: detect_wsl(),
+            is_wsl,
+            color_depth: color_depth_from_env(&env),
+            unicode_blocks: unicode_blocks_from_env(&env),
+            kitty_keyboard: replies.kitty_keyboard,
+            kitty_graphics: replies.kitty_graphics,
+            sixel: replies.sixel,
+            synchronized_output: replies.synchronized_output,
+            focus_events: replies.focus_events,
+            background: replies.background,
+            foreground: replies.foreground,
+            palette: replies.palette,
         }
     }
 
@@ -20,6 +101,69 @@ impl Platform {
     pub fn is_wsl(self) -> bool {
         self.is_wsl
     }
+
+    /// Returns the color depth advertised through `COLORTERM` and `TERM`,
+    /// or monochrome when `NO_COLOR` is set.
+    #[must_use]
+    pub fn color_depth(self) -> ColorDepth {
+        self.color_depth
+    }
+
+    /// Returns true when the locale and terminal are likely to render
+    /// Unicode block glyphs correctly.
+    #[must_use]
+    pub fn unicode_blocks(self) -> bool {
+        self.unicode_blocks
+    }
+
+    /// Returns true when the terminal answered the kitty keyboard protocol query.
+    #[must_use]
+    pub fn kitty_keyboard(self) -> bool {
+        self.kitty_keyboard
+    }
+
+    /// Returns true when the terminal accepted a kitty graphics protocol query.
+    #[must_use]
+    pub fn kitty_graphics(self) -> bool {
+        self.kitty_graphics
+    }
+
+    /// Returns true when the primary device attributes advertise sixel.
+    #[must_use]
+    pub fn sixel(self) -> bool {
+        self.sixel
+    }
+
+    /// Returns true when the terminal recognizes synchronized output (DEC mode 2026).
+    #[must_use]
+    pub fn synchronized_output(self) -> bool {
+        self.synchronized_output
+    }
+
+    /// Returns true when the terminal recognizes focus reporting (DEC mode 1004).
+    #[must_use]
+    pub fn focus_events(self) -> bool {
+        self.focus_events
+    }
+
+    /// Returns the terminal background color reported through OSC 11.
+    #[must_use]
+    pub fn background(self) -> Option<(u8, u8, u8)> {
+        self.background
+    }
+
+    /// Returns the terminal foreground color reported through OSC 10.
+    #[must_use]
+    pub fn foreground(self) -> Option<(u8, u8, u8)> {
+        self.foreground
+    }
+
+    /// Returns the 16 ANSI palette colors reported through OSC 4; slots the
+    /// terminal did not answer for are `None`.
+    #[must_use]
+    pub fn palette(self) -> [Option<(u8, u8, u8)>; 16] {
+        self.palette
+    }
 }
 
 fn detect_wsl() -> bool {
@@ -30,12 +174,366 @@ fn detect_wsl() -> bool {
     version.to_ascii_lowercase().contains("microsoft")
 }
 
+fn color_depth_from_env(env: &impl Fn(&str) -> Option<String>) -> ColorDepth {
+    if env("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+        return ColorDepth::Monochrome;
+    }
+
+    let colorterm = env("COLORTERM").unwrap_or_default().to_ascii_lowercase();
+    let term = env("TERM").unwrap_or_default().to_ascii_lowercase();
+
+    if matches!(colorterm.as_str(), "truecolor" | "24bit")
+        || term.contains("direct")
+        || term.contains("truecolor")
+        || env("WT_SESSION").is_some()
+    {
+        ColorDepth::TrueColor
+    } else if term.contains("256") {
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+fn unicode_blocks_from_env(env: &impl Fn(&str) -> Option<String>) -> bool {
+    if env("TERM").is_some_and(|term| term == "dumb") {
+        return false;
+    }
+
+    // The first non-empty locale variable wins, mirroring setlocale(3).
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|key| env(key).filter(|value| !value.is_empty()));
+
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_ascii_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        }
+        None => true,
+    }
+}
+
+/// Terminal answers collected from one capability probe round-trip.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+struct ProbeReplies {
+    kitty_keyboard: bool,
+    kitty_graphics: bool,
+    sixel: bool,
+    synchronized_output: bool,
+    focus_events: bool,
+    background: Option<(u8, u8, u8)>,
+    foreground: Option<(u8, u8, u8)>,
+    palette: [Option<(u8, u8, u8)>; 16],
+}
+
+/// Queries sent in one batch. Primary device attributes go last: every
+/// terminal answers it, so its reply marks the end of the batch.
+const PROBE_QUERIES: &str = concat!(
+    "\x1b]11;?\x1b\\",
+    "\x1b]10;?\x1b\\",
+    "\x1b]4;0;?\x1b\\",
+    "\x1b]4;1;?\x1b\\",
+    "\x1b]4;2;?\x1b\\",
+    "\x1b]4;3;?\x1b\\",
+    "\x1b]4;4;?\x1b\\",
+    "\x1b]4;5;?\x1b\\",
+    "\x1b]4;6;?\x1b\\",
+    "\x1b]4;7;?\x1b\\",
+    "\x1b]4;8;?\x1b\\",
+    "\x1b]4;9;?\x1b\\",
+    "\x1b]4;10;?\x1b\\",
+    "\x1b]4;11;?\x1b\\",
+    "\x1b]4;12;?\x1b\\",
+    "\x1b]4;13;?\x1b\\",
+    "\x1b]4;14;?\x1b\\",
+    "\x1b]4;15;?\x1b\\",
+    "\x1b[?u",
+    "\x1b[?2026$p",
+    "\x1b[?1004$p",
+    "\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\",
+    "\x1b[c",
+);
+
+#[cfg(unix)]
+fn query_terminal(timeout: Duration) -> Option<String> {
+    use std::io::Read;
+    use std::time::Instant;
+
+    crossterm::terminal::enable_raw_mode().ok()?;
+
+    let mut raw = Vec::new();
+    let mut stdout = io::stdout();
+    if stdout
+        .write_all(PROBE_QUERIES.as_bytes())
+        .and_then(|()| stdout.flush())
+        .is_ok()
+    {
+        let deadline = Instant::now() + timeout;
+        let mut stdin = io::stdin().lock();
+        let mut chunk = [0u8; 256];
+        while !contains_device_attributes(&raw) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || !stdin_readable(remaining) {
+                break;
+            }
+            match stdin.read(&mut chunk) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => raw.extend_from_slice(&chunk[..read]),
+            }
+        }
+        if !contains_device_attributes(&raw) {
+            drain_late_replies(&mut stdin);
+        }
+    }
+
+    let _ = crossterm::terminal::disable_raw_mode();
+    Some(String::from_utf8_lossy(&raw).into_owned())
+}
+
+/// Discards replies that arrive after the probe timed out so they are not
+/// read later as key presses. Stops once the device attributes reply (always
+/// the last one) shows up or stdin has been quiet for [`LATE_REPLY_QUIET`].
+#[cfg(unix)]
+fn drain_late_replies(stdin: &mut impl std::io::Read) {
+    use std::time::Instant;
+
+    let deadline = Instant::now() + LATE_REPLY_LIMIT;
+    let mut late = Vec::new();
+    let mut chunk = [0u8; 256];
+    while !contains_device_attributes(&late) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() || !stdin_readable(remaining.min(LATE_REPLY_QUIET)) {
+            break;
+        }
+        match stdin.read(&mut chunk) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => late.extend_from_slice(&chunk[..read]),
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn query_terminal(_timeout: Duration) -> Option<String> {
+    None
+}
+
+#[cfg(unix)]
+fn stdin_readable(timeout: Duration) -> bool {
+    let mut fds = libc::pollfd {
+        fd: libc::STDIN_FILENO,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let millis = timeout.as_millis().clamp(1, i32::MAX as u128) as libc::c_int;
+    // SAFETY: `fds` is a valid, initialized pollfd array of length 1.
+    let ready = unsafe { libc::poll(&mut fds, 1, millis) };
+    ready > 0 && fds.revents & libc::POLLIN != 0
+}
+
+fn contains_device_attributes(raw: &[u8]) -> bool {
+    csi_private_replies(&String::from_utf8_lossy(raw))
+        .iter()
+        .any(|(_, final_byte)| *final_byte == 'c')
+}
+
+/// Extracts `CSI ? <params> <final>` replies as (params, final byte) pairs.
+fn csi_private_replies(raw: &str) -> Vec<(&str, char)> {
+    let mut replies = Vec::new();
+    let mut rest = raw;
+    while let Some(start) = rest.find("\x1b[?") {
+        let body = &rest[start + 3..];
+        let Some(end) = body.find(|ch: char| ('@'..='~').contains(&ch)) else {
+            break;
+        };
+        let final_byte = body[end..].chars().next().unwrap_or_default();
+        replies.push((&body[..end], final_byte));
+        rest = &body[end + 1..];
+    }
+    replies
+}
+
+fn parse_probe_replies(raw: &str) -> ProbeReplies {
+    let mut replies = ProbeReplies::default();
+
+    for (params, final_byte) in csi_private_replies(raw) {
+        match final_byte {
+            'u' => replies.kitty_keyboard = true,
+            'y' => {
+                let mut fields = params.trim_end_matches('$').split(';');
+                let mode = fields.next();
+                // DECRPM: 1/2 = set/reset, 3 = permanently set; 0/4 = unsupported.
+                let supported = matches!(fields.next(), Some("1" | "2" | "3"));
+                match mode {
+                    Some("2026") => replies.synchronized_output = supported,
+                    Some("1004") => replies.focus_events = supported,
+                    _ => {}
+                }
+            }
+            'c' => replies.sixel = params.split(';').any(|attr| attr == "4"),
+            _ => {}
+        }
+    }
+
+    replies.kitty_graphics = raw.contains("\x1b_Gi=31;OK");
+    replies.background = raw
+        .find("\x1b]11;")
+        .and_then(|start| parse_osc_rgb(&raw[start + 5..]));
+    replies.foreground = raw
+        .find("\x1b]10;")
+        .and_then(|start| parse_osc_rgb(&raw[start + 5..]));
+
+    for (start, _) in raw.match_indices("\x1b]4;") {
+        let reply = &raw[start + 4..];
+        let Some((index, spec)) = reply.split_once(';') else {
+            continue;
+        };
+        if let Some(slot) = index
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| replies.palette.get_mut(index))
+        {
+            *slot = parse_osc_rgb(spec);
+        }
+    }
+    replies
+}
+
+/// Parses an X11 `rgb:R/G/B` color spec with 1-4 hex digits per channel.
+fn parse_osc_rgb(reply: &str) -> Option<(u8, u8, u8)> {
+    let spec = reply.strip_prefix("rgb:")?;
+    let end = spec.find(['\x07', '\x1b']).unwrap_or(spec.len());
+    let mut channels = spec[..end].split('/').map(|channel| {
+        if channel.is_empty() || channel.len() > 4 {
+            return None;
+        }
+        let value = u32::from_str_radix(channel, 16).ok()?;
+        let max = (1u32 << (4 * channel.len())) - 1;
+        Some(((value * 255 + max / 2) / max) as u8)
+    });
+
+    let r = channels.next()??;
+    let g = channels.next()??;
+    let b = channels.next()??;
+    Some((r, g, b))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::Platform;
+    use super::{
+        ColorDepth, ProbeReplies, color_depth_from_env, contains_device_attributes,
+        parse_probe_replies, unicode_blocks_from_env,
+    };
+
+    fn env_from(pairs: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        move |key| {
+            pairs
+                .iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| (*value).to_owned())
+        }
+    }
+
+    #[test]
+    fn color_depth_reads_colorterm_and_term() {
+        assert_eq!(
+            color_depth_from_env(&env_from(&[("COLORTERM", "truecolor")])),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            color_depth_from_env(&env_from(&[("TERM", "xterm-256color")])),
+            ColorDepth::Ansi256
+        );
+        assert_eq!(
+            color_depth_from_env(&env_from(&[("TERM", "linux")])),
+            ColorDepth::Ansi16
+        );
+    }
+
+    #[test]
+    fn no_color_forces_monochrome() {
+        assert_eq!(
+            color_depth_from_env(&env_from(&[("NO_COLOR", "1"), ("COLORTERM", "truecolor")])),
+            ColorDepth::Monochrome
+        );
+        assert_eq!(
+            color_depth_from_env(&env_from(&[("NO_COLOR", ""), ("COLORTERM", "truecolor")])),
+            ColorDepth::TrueColor
+        );
+    }
+
+    #[test]
+    fn non_utf8_locale_disables_unicode_blocks() {
+        assert!(!unicode_blocks_from_env(&env_from(&[("LANG", "C")])));
+        assert!(!unicode_blocks_from_env(&env_from(&[
+            ("LC_ALL", "POSIX"),
+            ("LANG", "en_US.UTF-8"),
+        ])));
+        assert!(unicode_blocks_from_env(&env_from(&[(
+            "LC_CTYPE",
+            "en_US.utf8"
+        )])));
+        assert!(!unicode_blocks_from_env(&env_from(&[("TERM", "dumb")])));
+    }
+
+    #[test]
+    fn probe_replies_are_parsed() {
+        let raw = concat!(
+            "\x1b]11;rgb:1e1e/2020/3030\x1b\\",
+            "\x1b[?1u",
+            "\x1b[?2026;2$y",
+            "\x1b[?1004;0$y",
+            "\x1b_Gi=31;OK\x1b\\",
+            "\x1b[?62;4;22c",
+        );
+
+        assert_eq!(
+            parse_probe_replies(raw),
+            ProbeReplies {
+                kitty_keyboard: true,
+                kitty_graphics: true,
+                sixel: true,
+                synchronized_output: true,
+                focus_events: false,
+                background: Some((0x1e, 0x20, 0x30)),
+                foreground: None,
+                palette: [None; 16],
+            }
+        );
+    }
+
+    #[test]
+    fn palette_replies_are_parsed() {
+        let replies = parse_probe_replies(concat!(
+            "\x1b]10;rgb:c0c0/caca/f5f5\x1b\\",
+            "\x1b]4;1;rgb:f7f7/7676/8e8e\x07",
+            "\x1b]4;15;rgb:ff/ff/ff\x1b\\",
+            "\x1b[?62c",
+        ));
+
+        assert_eq!(replies.foreground, Some((0xc0, 0xca, 0xf5)));
+        assert_eq!(replies.palette[1], Some((0xf7, 0x76, 0x8e)));
+        assert_eq!(replies.palette[15], Some((255, 255, 255)));
+        assert_eq!(replies.palette[0], None);
+    }
+
+    #[test]
+    fn silent_terminal_yields_no_capabilities() {
+        assert_eq!(parse_probe_replies("\x1b[?1;2c"), ProbeReplies::default());
+    }
+
+    #[test]
+    fn device_attributes_reply_marks_the_end_of_the_batch() {
+        assert!(!contains_device_attributes(
+            b"\x1b]11;rgb:0/0/0\x1b\\\x1b[?1u"
+        ));
+        assert!(!contains_device_attributes(b"\x1b[?2026;2$y\x1b[?62;4"));
+        assert!(contains_device_attributes(b"\x1b[?2026;2$y\x1b[?62;4c"));
+    }
 
     #[test]
-    fn platform_detection_runs_without_panicking() {
-        let _ = Platform::detect();
+    fn short_osc_color_channels_are_scaled() {
+        let replies = parse_probe_replies("\x1b]11;rgb:f/8/0\x07");
+        assert_eq!(replies.background, Some((255, 136, 0)));
     }
 }
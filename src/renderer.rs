@@ -1,19 +1,31 @@
+use std::collections::VecDeque;
+
 use ratatui::Frame;
+use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::Style;
-use ratatui::widgets::Block;
+use ratatui::widgets::{Block, Widget};
 
+use crate::background::{BackgroundChoice, BackgroundPattern};
+use crate::color::color_to_rgb;
 use crate::config::{
-    DEFAULT_TICK_INTERVAL_MS, GridSize, MIN_TICK_INTERVAL_MS, PLAY_AREA_MARGIN_X,
-    PLAY_AREA_MARGIN_Y, Theme, glyphs,
+    CellGlyphs, DEFAULT_TICK_INTERVAL_MS, GLYPH_BRAILLE_BASE, GlyphMode, GridSize,
+    MIN_TICK_INTERVAL_MS, PLAY_AREA_MARGIN_X, PLAY_AREA_MARGIN_Y, SnakeSkin, Theme, glyphs,
 };
+use crate::food::Food;
 use crate::game::{GameState, GameStatus, GlowEffect, GlowTrigger};
-use crate::platform::Platform;
-use crate::snake::Position;
-use crate::ui::hud::{HudInfo, render_hud};
+use crate::graphics::{CellPixels, PixelImage};
+use crate::input::Direction;
+use crate::keymap::{KeyBindings, KeyPreset};
+use crate::snake::{Position, Snake};
+use crate::theme::AppearancePreference;
+use crate::ui::hud::{HudInfo, render_hud, render_toast};
+use crate::ui::key_editor::KeyEditor;
 use crate::ui::menu::{
-    ThemeSelectView, render_game_over_menu, render_pause_menu, render_start_menu,
+    MenuHits, ThemeSelectView, render_game_over_menu, render_key_editor, render_pause_menu,
+    render_start_menu, render_theme_editor,
 };
+use crate::ui::theme_editor::ThemeEditor;
 
 pub struct MenuUiState<'a> {
     pub start_selected_idx: usize,
@@ -22,27 +34,59 @@ pub struct MenuUiState<'a> {
     pub start_speed_level: u32,
     /// Whether the speed-adjust sub-mode is active (Up/Down changes speed value).
     pub start_speed_adjust_mode: bool,
-    pub checkerboard_enabled: bool,
+    pub appearance: AppearancePreference,
+    pub background: BackgroundChoice,
     pub game_border_enabled: bool,
+    pub shape_coding: bool,
     pub play_area_too_small: bool,
     pub pause_selected_idx: usize,
     pub game_over_selected_idx: usize,
     pub start_theme_select: Option<ThemeSelectView<'a>>,
     pub pause_theme_select: Option<ThemeSelectView<'a>>,
+    /// Open theme editor; replaces the menus so the play field stays visible.
+    pub theme_editor: Option<&'a ThemeEditor>,
+    /// Open key binding editor; replaces the start menu.
+    pub key_editor: Option<&'a KeyEditor>,
+    pub key_preset: KeyPreset,
+    /// Active key bindings, shown in menu footers.
+    pub bindings: &'a KeyBindings,
+    pub mouse_steering: bool,
+}
+
+/// Play-field display settings that do not come from the theme.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldOptions<'a> {
+    pub background: &'a BackgroundPattern,
+    /// Tells entities apart by shape as well as color: super food blinks, the
+    /// head is marked in half-block mode and the body is hatched in square
+    /// modes.
+    pub shape_coding: bool,
 }
 
+/// Ticks in one super food blink cycle under shape coding; the food is
+/// hidden for the last tick of each cycle.
+const SUPER_FOOD_BLINK_TICKS: u64 = 4;
+
 /// What occupies a single logical game cell.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum CellKind {
     Empty,
     SnakeHead,
-    /// Carries body segment index (excluding head/tail) for color banding.
-    SnakeBody(usize),
+    /// Carries the segment's place in the body for the theme's snake skin.
+    SnakeBody(BodySegment),
     SnakeTail,
     Food,
     SuperFood,
 }
 
+/// Position of a body segment, counted from the segment after the head.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BodySegment {
+    index: usize,
+    /// Number of body segments, excluding head and tail.
+    body_len: usize,
+}
+
 #[derive(Debug, Clone, Copy)]
 struct CellRender {
     kind: CellKind,
@@ -51,46 +95,77 @@ struct CellRender {
     snake_body_flash_amount: f32,
 }
 
+impl CellRender {
+    const EMPTY: Self = Self {
+        kind: CellKind::Empty,
+        bg: ratatui::style::Color::Reset,
+        bg_flash_amount: 0.0,
+        snake_body_flash_amount: 0.0,
+    };
+}
+
+/// Screen layout of a rendered frame, kept for the next round of input.
+#[derive(Debug, Clone, Default)]
+pub struct FrameLayout {
+    /// Gameplay viewport, for pixel overlays and mouse steering.
+    pub gameplay_area: Rect,
+    /// Clickable menu entries drawn this frame.
+    pub menu_hits: MenuHits,
+}
+
 /// Renders the full game frame from immutable state.
+///
+/// The play field is drawn through `cache`, which should be kept for the
+/// next frame. Returns the frame layout so callers can overlay the gameplay
+/// viewport with pixel output and hit-test mouse clicks.
 pub fn render(
     frame: &mut Frame<'_>,
     state: &GameState,
-    platform: Platform,
     hud_info: HudInfo<'_>,
     menu_ui: MenuUiState<'_>,
-) {
+    cache: &mut RenderCache,
+) -> FrameLayout {
     let area = frame.area();
+    let buf = frame.buffer_mut();
 
     let theme = hud_info.theme;
-    frame.render_widget(
-        Block::default().style(Style::new().bg(theme.terminal_bg)),
-        area,
-    );
+    Block::default()
+        .style(Style::new().bg(theme.terminal_bg))
+        .render(area, buf);
 
-    let play_area = render_hud(frame, area, state, platform, &hud_info);
+    let play_area = render_hud(buf, area, state, &hud_info);
 
     let gameplay_area = inset_play_area(play_area);
-    frame.render_widget(
-        Block::default().style(Style::new().bg(theme.field_bg)),
-        gameplay_area,
-    );
+    Block::default()
+        .style(Style::new().bg(theme.field_bg))
+        .render(gameplay_area, buf);
     if menu_ui.game_border_enabled {
-        render_play_area_border(frame, play_area, gameplay_area, theme);
+        render_play_area_border(buf, play_area, gameplay_area, theme);
     } else {
-        render_play_area_hud_margin(frame, play_area, gameplay_area, theme);
+        render_play_area_hud_margin(buf, play_area, gameplay_area, theme);
     }
 
     render_play_area(
-        frame,
+        buf,
         gameplay_area,
         state,
         theme,
-        menu_ui.checkerboard_enabled,
+        FieldOptions {
+            background: menu_ui.background.resolve(&theme.background),
+            shape_coding: menu_ui.shape_coding,
+        },
+        cache,
     );
 
-    if state.is_start_screen() {
+    let menu_hits = if let Some(editor) = menu_ui.theme_editor {
+        render_theme_editor(buf, play_area, editor, menu_ui.bindings);
+        MenuHits::default()
+    } else if let Some(editor) = menu_ui.key_editor {
+        render_key_editor(buf, play_area, editor, theme);
+        MenuHits::default()
+    } else if state.is_start_screen() {
         render_start_menu(
-            frame,
+            buf,
             play_area,
             hud_info.high_score,
             hud_info.theme,
@@ -100,16 +175,68 @@ pub fn render(
             menu_ui.start_settings_selected_idx,
             menu_ui.start_speed_level,
             menu_ui.start_speed_adjust_mode,
-            menu_ui.checkerboard_enabled,
+            menu_ui.appearance,
+            menu_ui.background,
             menu_ui.game_border_enabled,
+            menu_ui.shape_coding,
+            menu_ui.key_preset,
+            menu_ui.bindings,
+            menu_ui.mouse_steering,
             menu_ui.start_theme_select,
-        );
-        return;
+        )
+    } else {
+        render_status_menu(buf, play_area, state, &hud_info, menu_ui)
+    };
+
+    if let Some(toast) = hud_info.toast {
+        render_toast(buf, play_area, toast, theme, hud_info.now);
+    }
+
+    FrameLayout {
+        gameplay_area,
+        menu_hits,
+    }
+}
+
+/// Maps a terminal cell inside the gameplay viewport to the logical grid
+/// cell drawn there, using the active glyph mode's cell packing.
+#[must_use]
+pub fn grid_position_at(
+    gameplay_area: Rect,
+    bounds: GridSize,
+    column: u16,
+    row: u16,
+) -> Option<Position> {
+    if column < gameplay_area.x
+        || column >= gameplay_area.right()
+        || row < gameplay_area.y
+        || row >= gameplay_area.bottom()
+    {
+        return None;
+    }
+    let geometry = glyphs().mode.cell_geometry();
+    let x = usize::from(column - gameplay_area.x) * usize::from(geometry.cols_per_term_cell)
+        / usize::from(geometry.term_cols_per_cell);
+    let y = usize::from(row - gameplay_area.y) * usize::from(geometry.rows_per_term_cell);
+    if x >= usize::from(bounds.width) || y >= usize::from(bounds.height) {
+        return None;
     }
+    Some(Position {
+        x: i32::try_from(x).ok()?,
+        y: i32::try_from(y).ok()?,
+    })
+}
 
+fn render_status_menu(
+    buf: &mut Buffer,
+    play_area: Rect,
+    state: &GameState,
+    hud_info: &HudInfo<'_>,
+    menu_ui: MenuUiState<'_>,
+) -> MenuHits {
     match state.status {
         GameStatus::Paused => render_pause_menu(
-            frame,
+            buf,
             play_area,
             hud_info.theme,
             menu_ui.play_area_too_small,
@@ -117,7 +244,7 @@ pub fn render(
             menu_ui.pause_theme_select,
         ),
         GameStatus::GameOver => render_game_over_menu(
-            frame,
+            buf,
             play_area,
             state.score,
             hud_info.game_over_reference_high_score,
@@ -125,11 +252,13 @@ pub fn render(
             state.play_area_coverage_percent(),
             state.death_reason,
             state.elapsed_duration(),
+            state.sprint_duration(),
             hud_info.theme,
             menu_ui.game_over_selected_idx,
+            menu_ui.bindings,
         ),
         GameStatus::Victory => render_game_over_menu(
-            frame,
+            buf,
             play_area,
             state.score,
             hud_info.game_over_reference_high_score,
@@ -137,52 +266,57 @@ pub fn render(
             state.play_area_coverage_percent(),
             state.death_reason,
             state.elapsed_duration(),
+            state.sprint_duration(),
             hud_info.theme,
             menu_ui.game_over_selected_idx,
+            menu_ui.bindings,
         ),
-        _ => {}
+        _ => MenuHits::default(),
     }
 }
 
-fn render_play_area_border(
-    frame: &mut Frame<'_>,
+/// Draws the thin frame around `gameplay_area` in the margin of `play_area`.
+pub fn render_play_area_border(
+    buf: &mut Buffer,
     play_area: Rect,
     gameplay_area: Rect,
     theme: &Theme,
 ) {
     let style = Style::new().fg(theme.ui_bright).bg(theme.terminal_bg);
-    let buffer = frame.buffer_mut();
 
     if gameplay_area.y > play_area.y {
         let top_y = gameplay_area.y - 1;
         for x in gameplay_area.x..gameplay_area.right() {
-            buffer.set_string(x, top_y, "▁", style);
+            buf.set_string(x, top_y, "▁", style);
         }
     }
 
     if gameplay_area.bottom() < play_area.bottom() {
         let bottom_y = gameplay_area.bottom();
         for x in gameplay_area.x..gameplay_area.right() {
-            buffer.set_string(x, bottom_y, "▔", style);
+            buf.set_string(x, bottom_y, "▔", style);
         }
     }
 
     if gameplay_area.x > play_area.x {
         let left_x = gameplay_area.x - 1;
         for y in gameplay_area.y..gameplay_area.bottom() {
-            buffer.set_string(left_x, y, "▕", style);
+            buf.set_string(left_x, y, "▕", style);
         }
     }
 
     if gameplay_area.right() < play_area.right() {
         let right_x = gameplay_area.right();
         for y in gameplay_area.y..gameplay_area.bottom() {
-            buffer.set_string(right_x, y, "▏", style);
+            buf.set_string(right_x, y, "▏", style);
         }
     }
 }
 
-fn inset_play_area(area: Rect) -> Rect {
+/// Returns the gameplay viewport inside `play_area`, inset by the play area
+/// margins.
+#[must_use]
+pub fn inset_play_area(area: Rect) -> Rect {
     let horizontal_margin = PLAY_AREA_MARGIN_X.saturating_mul(2);
     let vertical_margin = PLAY_AREA_MARGIN_Y.saturating_mul(2);
 
@@ -195,7 +329,7 @@ fn inset_play_area(area: Rect) -> Rect {
 }
 
 fn render_play_area_hud_margin(
-    frame: &mut Frame<'_>,
+    buf: &mut Buffer,
     play_area: Rect,
     gameplay_area: Rect,
     theme: &Theme,
@@ -207,201 +341,897 @@ fn render_play_area_hud_margin(
     let y = gameplay_area.bottom();
     let style = Style::new().fg(theme.terminal_bg).bg(theme.field_bg);
     let half_upper = glyphs().half_upper;
-    let buffer = frame.buffer_mut();
 
     for x in gameplay_area.x..gameplay_area.right() {
-        buffer.set_string(x, y, half_upper, style);
+        buf.set_string(x, y, half_upper, style);
     }
 }
 
-/// Returns the checkerboard background color for a given game-grid cell.
-fn checker_bg(col: usize, game_row: usize, theme: &Theme) -> ratatui::style::Color {
-    let tile_x = col / 6;
-    let tile_y = game_row / 6;
-    if (tile_x + tile_y).is_multiple_of(2) {
-        theme.field_bg
-    } else {
-        theme.field_bg_alt
+/// Returns the background color of a game-grid cell under `pattern`.
+///
+/// Named colors cannot be blended, so partial shades snap to the nearer of
+/// `field_bg` and `field_bg_alt`.
+fn pattern_bg(
+    pattern: &BackgroundPattern,
+    col: usize,
+    game_row: usize,
+    bounds: GridSize,
+    theme: &Theme,
+) -> ratatui::style::Color {
+    let shade = pattern.shade(col, game_row, bounds);
+    match (theme.field_bg, theme.field_bg_alt) {
+        (ratatui::style::Color::Rgb(..), ratatui::style::Color::Rgb(..)) => {
+            lerp_color(theme.field_bg, theme.field_bg_alt, shade)
+        }
+        _ if shade >= 0.5 => theme.field_bg_alt,
+        _ => theme.field_bg,
     }
 }
 
-/// Builds a color grid from game state and composites half-block row-pairs.
-fn render_play_area(
-    frame: &mut Frame<'_>,
-    inner: Rect,
-    state: &GameState,
-    theme: &Theme,
-    checkerboard_enabled: bool,
-) {
-    let bounds = state.bounds();
-    let grid = build_cell_grid(state, bounds);
-    let glow = state.active_glow();
-    let snake_cells = build_snake_cell_mask(state, bounds);
-    let level_up_neighbor_flash = glow.and_then(level_up_neighbor_flash_amount).unwrap_or(0.0);
-    let neighbor_flash_mask = if level_up_neighbor_flash > 0.0 {
-        Some(build_snake_neighbor_mask(state, bounds, &snake_cells))
-    } else {
-        None
-    };
-    let super_food_ripple_flash = glow.and_then(super_food_ripple_flash_amount).unwrap_or(0.0);
-    let super_food_ripple_center = super_food_ripple_center_position(state, glow);
-    let super_food_ripple_center_idx = super_food_ripple_center.and_then(|center| {
-        if center.is_within_bounds(bounds) {
-            Some(center.y as usize * usize::from(bounds.width) + center.x as usize)
+/// Per-cell buffers behind [`PlayFieldLayers`], kept between frames so
+/// building the layers reuses their storage.
+#[derive(Debug, Clone, Default)]
+struct LayerBuffers {
+    grid: Vec<CellKind>,
+    neighbor_flash: Vec<bool>,
+    super_food_ripple: Vec<bool>,
+    super_food_body_expansion: Vec<bool>,
+}
+
+/// Per-frame logical-cell layers shared by every play-field compositor.
+struct PlayFieldLayers<'a> {
+    bounds: GridSize,
+    grid: &'a [CellKind],
+    body_len: usize,
+    background: &'a BackgroundPattern,
+    shape_coding: bool,
+    /// Whether super food is in the hidden phase of its blink.
+    super_food_hidden: bool,
+    level_up_neighbor_flash: f32,
+    neighbor_flash_mask: Option<&'a [bool]>,
+    super_food_ripple_flash: f32,
+    super_food_ripple_center_idx: Option<usize>,
+    super_food_body_expansion_mask: Option<&'a [bool]>,
+    super_food_ripple_mask: Option<&'a [bool]>,
+}
+
+impl<'a> PlayFieldLayers<'a> {
+    fn build(
+        state: &GameState,
+        glow: Option<&GlowEffect>,
+        options: FieldOptions<'a>,
+        buffers: &'a mut LayerBuffers,
+    ) -> Self {
+        let super_food_hidden = super_food_hidden(state, options.shape_coding);
+        fill_cell_grid(&mut buffers.grid, state, state.bounds(), super_food_hidden);
+        Self::with_grid(state, glow, options, buffers)
+    }
+
+    /// Builds the layers over `buffers.grid` as it stands, refilling only the
+    /// glow masks.
+    fn with_grid(
+        state: &GameState,
+        glow: Option<&GlowEffect>,
+        options: FieldOptions<'a>,
+        buffers: &'a mut LayerBuffers,
+    ) -> Self {
+        let bounds = state.bounds();
+        let level_up_neighbor_flash = glow.and_then(level_up_neighbor_flash_amount).unwrap_or(0.0);
+        let has_neighbor_flash = level_up_neighbor_flash > 0.0;
+        if has_neighbor_flash {
+            fill_snake_neighbor_mask(&mut buffers.neighbor_flash, state, bounds, &buffers.grid);
+        }
+        let super_food_ripple_flash = glow.and_then(super_food_ripple_flash_amount).unwrap_or(0.0);
+        let super_food_ripple_center = super_food_ripple_center_position(state, glow)
+            .filter(|_| super_food_ripple_flash > 0.0);
+        let super_food_ripple_center_idx = super_food_ripple_center.and_then(|center| {
+            if center.is_within_bounds(bounds) {
+                Some(center.y as usize * usize::from(bounds.width) + center.x as usize)
+            } else {
+                None
+            }
+        });
+        if let Some(center) = super_food_ripple_center {
+            fill_super_food_body_expansion_mask(
+                &mut buffers.super_food_body_expansion,
+                bounds,
+                center,
+            );
+            fill_super_food_ripple_mask(
+                &mut buffers.super_food_ripple,
+                bounds,
+                &buffers.grid,
+                center,
+            );
+        }
+
+        let buffers: &'a LayerBuffers = buffers;
+        let has_ripple = super_food_ripple_center.is_some();
+        Self {
+            bounds,
+            grid: &buffers.grid,
+            body_len: state.snake.len().saturating_sub(2),
+            background: options.background,
+            shape_coding: options.shape_coding,
+            super_food_hidden: super_food_hidden(state, options.shape_coding),
+            level_up_neighbor_flash,
+            neighbor_flash_mask: has_neighbor_flash.then_some(buffers.neighbor_flash.as_slice()),
+            super_food_ripple_flash,
+            super_food_ripple_center_idx,
+            super_food_body_expansion_mask: has_ripple
+                .then_some(buffers.super_food_body_expansion.as_slice()),
+            super_food_ripple_mask: has_ripple.then_some(buffers.super_food_ripple.as_slice()),
+        }
+    }
+
+    /// Resolves one logical cell; rows past the grid bottom render as empty.
+    fn cell(&self, col: usize, game_row: usize, theme: &Theme) -> CellRender {
+        let width = usize::from(self.bounds.width);
+        let in_grid = game_row < usize::from(self.bounds.height) && col < width;
+        let idx = game_row * width + col;
+        let mask_hit = |mask: Option<&[bool]>| in_grid && mask.is_some_and(|m| m[idx]);
+
+        let mut kind = if in_grid {
+            self.grid[idx]
         } else {
-            None
+            CellKind::Empty
+        };
+        if mask_hit(self.super_food_body_expansion_mask) && matches!(kind, CellKind::Empty) {
+            kind = CellKind::SnakeBody(BodySegment {
+                index: 0,
+                body_len: self.body_len,
+            });
         }
-    });
-    let super_food_body_expansion_mask = if super_food_ripple_flash > 0.0 {
-        build_super_food_body_expansion_mask(bounds, super_food_ripple_center)
-    } else {
-        None
+
+        let bg_flash_amount = if mask_hit(self.super_food_ripple_mask) {
+            self.super_food_ripple_flash
+        } else if mask_hit(self.neighbor_flash_mask) {
+            self.level_up_neighbor_flash
+        } else {
+            0.0
+        };
+        let snake_body_flash_amount = if in_grid
+            && matches!(kind, CellKind::SnakeBody(_))
+            && self.super_food_ripple_center_idx == Some(idx)
+        {
+            self.super_food_ripple_flash
+        } else {
+            0.0
+        };
+        let bg = pattern_bg(self.background, col, game_row, self.bounds, theme);
+
+        CellRender {
+            kind,
+            bg,
+            bg_flash_amount,
+            snake_body_flash_amount,
+        }
+    }
+}
+
+/// Segments in the theme preview's mock snake.
+const PREVIEW_SNAKE_LEN: usize = 8;
+
+/// Draws a small mock game with `theme` into `area`, using the same
+/// compositor as the real play field (half-blocks unless another glyph mode
+/// is active) with the theme's own background pattern.
+///
+/// The snake loops around the board one cell per `step`, past a normal and a
+/// super food in the middle.
+pub fn render_preview_board(buf: &mut Buffer, area: Rect, theme: &Theme, step: u64) {
+    Block::default()
+        .style(Style::new().bg(theme.field_bg))
+        .render(area, buf);
+    let bounds = glyphs()
+        .mode
+        .grid_size_for_viewport(area.width, area.height);
+    let state = preview_state(bounds, step);
+    let options = FieldOptions {
+        background: &theme.background,
+        shape_coding: false,
     };
-    let super_food_ripple_mask = if super_food_ripple_flash > 0.0 {
-        build_super_food_ripple_mask(bounds, &snake_cells, super_food_ripple_center)
-    } else {
-        None
+    render_play_area(
+        buf,
+        area,
+        &state,
+        theme,
+        options,
+        &mut RenderCache::default(),
+    );
+}
+
+fn preview_state(bounds: GridSize, step: u64) -> GameState {
+    let mut state = GameState::new_with_seed(bounds, 0);
+    state.status = GameStatus::Playing;
+    state.foods.clear();
+
+    let path = preview_loop(bounds);
+    if path.len() <= PREVIEW_SNAKE_LEN {
+        return state;
+    }
+
+    let head_idx = (step % path.len() as u64) as usize;
+    let segments = (0..PREVIEW_SNAKE_LEN)
+        .map(|offset| path[(head_idx + path.len() - offset) % path.len()])
+        .collect::<Vec<_>>();
+    let neck = segments[1];
+    let head = segments[0];
+    let direction = match (head.x - neck.x, head.y - neck.y) {
+        (1, _) => Direction::Right,
+        (-1, _) => Direction::Left,
+        (_, 1) => Direction::Down,
+        _ => Direction::Up,
     };
+    if let Ok(snake) = Snake::from_segments(segments, direction) {
+        state.snake = snake;
+    }
+
+    let center = Position {
+        x: i32::from(bounds.width) / 2,
+        y: i32::from(bounds.height) / 2,
+    };
+    state.foods.push(Food::new(Position {
+        x: center.x - 2,
+        ..center
+    }));
+    state.foods.push(Food::new_super(
+        Position {
+            x: center.x + 2,
+            ..center
+        },
+        u32::MAX,
+    ));
+    state
+}
+
+/// Returns the clockwise ring of cells one in from the edge of `bounds`.
+fn preview_loop(bounds: GridSize) -> Vec<Position> {
+    let right = i32::from(bounds.width) - 2;
+    let bottom = i32::from(bounds.height) - 2;
+    if right <= 1 || bottom <= 1 {
+        return Vec::new();
+    }
+
+    let top_edge = (1..right).map(|x| Position { x, y: 1 });
+    let right_edge = (1..bottom).map(|y| Position { x: right, y });
+    let bottom_edge = (2..=right).rev().map(|x| Position { x, y: bottom });
+    let left_edge = (2..=bottom).rev().map(|y| Position { x: 1, y });
+    top_edge
+        .chain(right_edge)
+        .chain(bottom_edge)
+        .chain(left_edge)
+        .collect()
+}
+
+/// Draws the play field into `inner` through `cache`, compositing only the
+/// cells that changed since the cache last drew.
+pub fn render_play_area(
+    buf: &mut Buffer,
+    inner: Rect,
+    state: &GameState,
+    theme: &Theme,
+    options: FieldOptions<'_>,
+    cache: &mut RenderCache,
+) {
+    cache.draw(buf, inner, state, theme, options);
+}
+
+/// Snake moves one frame can catch up on before the field is composited from
+/// scratch; the tick scheduler plays back at most three per frame.
+const MAX_CACHED_STEPS: usize = 4;
+
+/// Glyph of one composited cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CellGlyph {
+    Str(&'static str),
+    Char(char),
+}
+
+/// One composited terminal cell, or a two-column pair in square modes.
+#[derive(Debug, Clone, Copy)]
+struct TermCell {
+    glyph: CellGlyph,
+    fg: ratatui::style::Color,
+    bg: ratatui::style::Color,
+}
+
+/// What the cached field was composited for; any change recomposites it.
+#[derive(Debug, Clone, PartialEq)]
+struct FieldKey {
+    area: Rect,
+    bounds: GridSize,
+    mode: GlyphMode,
+    theme: Theme,
+    background: BackgroundPattern,
+    shape_coding: bool,
+}
 
-    let buffer = frame.buffer_mut();
-    let game_h = usize::from(bounds.height);
-    // Each terminal row composites two game rows.
-    let term_rows = game_h.div_ceil(2);
+impl FieldKey {
+    fn matches(
+        &self,
+        area: Rect,
+        bounds: GridSize,
+        mode: GlyphMode,
+        theme: &Theme,
+        options: FieldOptions<'_>,
+    ) -> bool {
+        self.area == area
+            && self.bounds == bounds
+            && self.mode == mode
+            && self.shape_coding == options.shape_coding
+            && self.background == *options.background
+            && self.theme == *theme
+    }
+}
 
-    for term_row in 0..term_rows {
-        let top_game_row = term_row * 2;
-        let bot_game_row = term_row * 2 + 1;
-        let y = inner.y.saturating_add(term_row as u16);
-        if y >= inner.bottom() {
-            break;
+/// Play-field render state kept from one frame to the next.
+///
+/// Holds the logical cell grid and the composited terminal cells of the last
+/// frame. A frame without a tick copies the cached cells into the buffer as
+/// they are. After a tick only cells whose content changed are composited
+/// again: the new and old head, vacated tail cells, food that came or went,
+/// and body segments whose skin color shifted as the snake moved. Glow
+/// effects, resizes and changes of theme or field options recomposite the
+/// whole field.
+#[derive(Debug, Clone, Default)]
+pub struct RenderCache {
+    key: Option<FieldKey>,
+    layers: LayerBuffers,
+    /// Snake segments as of the last frame; front is the head.
+    snake: VecDeque<Position>,
+    head_direction: Option<Direction>,
+    /// Food positions and whether each is super food, as of the last frame.
+    foods: Vec<(Position, bool)>,
+    super_food_hidden: bool,
+    /// Whether the last frame was drawn with a glow effect.
+    glow_drawn: bool,
+    /// Composited cells, row-major over `cols` columns.
+    cells: Vec<TermCell>,
+    cols: usize,
+    rows: usize,
+    /// Indices into `cells` that need compositing again.
+    dirty: Vec<usize>,
+    /// Whether the last draw composited any cell.
+    changed: bool,
+}
+
+impl RenderCache {
+    /// Returns whether the last frame changed any play-field cell, so pixel
+    /// renderers can skip rasterizing a field that looks the same.
+    #[must_use]
+    pub fn field_changed(&self) -> bool {
+        self.changed
+    }
+
+    fn draw(
+        &mut self,
+        buffer: &mut Buffer,
+        inner: Rect,
+        state: &GameState,
+        theme: &Theme,
+        options: FieldOptions<'_>,
+    ) {
+        let mode = glyphs().mode;
+        let glow = state.active_glow();
+        let key_matches = self
+            .key
+            .as_ref()
+            .is_some_and(|key| key.matches(inner, state.bounds(), mode, theme, options));
+        let hidden = super_food_hidden(state, options.shape_coding);
+
+        if glow.is_none() && !self.glow_drawn && key_matches && self.update(state, theme, hidden) {
+            self.changed = !self.dirty.is_empty();
+            self.composite_dirty(state, theme, options, mode);
+        } else {
+            self.changed = true;
+            if !key_matches {
+                self.key = Some(FieldKey {
+                    area: inner,
+                    bounds: state.bounds(),
+                    mode,
+                    theme: theme.clone(),
+                    background: options.background.clone(),
+                    shape_coding: options.shape_coding,
+                });
+            }
+            self.rebuild(inner, state, theme, options, mode);
         }
+        self.glow_drawn = glow.is_some();
+        self.blit(buffer, inner, mode);
+    }
 
-        for col in 0..usize::from(bounds.width) {
-            let x = inner.x.saturating_add(col as u16);
-            if x >= inner.right() {
-                break;
+    /// Rebuilds the grid and composites every cell.
+    fn rebuild(
+        &mut self,
+        inner: Rect,
+        state: &GameState,
+        theme: &Theme,
+        options: FieldOptions<'_>,
+        mode: GlyphMode,
+    ) {
+        let bounds = state.bounds();
+        let geometry = mode.cell_geometry();
+        self.cols = usize::from(bounds.width)
+            .div_ceil(usize::from(geometry.cols_per_term_cell))
+            .min(usize::from(inner.width / geometry.term_cols_per_cell));
+        self.rows = usize::from(bounds.height)
+            .div_ceil(usize::from(geometry.rows_per_term_cell))
+            .min(usize::from(inner.height));
+
+        self.snake.clear();
+        self.snake.extend(state.snake.segments().copied());
+        self.head_direction = Some(state.snake.direction());
+        self.foods.clear();
+        self.foods.extend(
+            state
+                .foods
+                .iter()
+                .map(|food| (food.position, food.is_super())),
+        );
+        self.super_food_hidden = super_food_hidden(state, options.shape_coding);
+        self.dirty.clear();
+
+        let glow = state.active_glow();
+        let head_direction = state.snake.direction();
+        let layers = PlayFieldLayers::build(state, glow, options, &mut self.layers);
+        self.cells.clear();
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                self.cells.push(composite_cell(
+                    &layers,
+                    theme,
+                    glow,
+                    mode,
+                    head_direction,
+                    col,
+                    row,
+                ));
             }
+        }
+    }
+
+    /// Brings the grid up to date with `state` and queues the cells that
+    /// look different. Returns false when the snake cannot be followed on
+    /// from the last frame, as after a restart.
+    fn update(&mut self, state: &GameState, theme: &Theme, super_food_hidden: bool) -> bool {
+        let snake = &state.snake;
+        let Some(&old_head) = self.snake.front() else {
+            return false;
+        };
+        let Some(steps) = snake
+            .segments()
+            .take(MAX_CACHED_STEPS + 1)
+            .position(|segment| *segment == old_head)
+        else {
+            return false;
+        };
 
-            let mut top_kind = grid[top_game_row * usize::from(bounds.width) + col];
-            let top_idx = top_game_row * usize::from(bounds.width) + col;
-            let mut bot_kind = if bot_game_row < game_h {
-                grid[bot_game_row * usize::from(bounds.width) + col]
+        let resized = snake.len() != self.snake.len();
+        let moved = steps > 0 || resized;
+        if moved {
+            for idx in (0..steps).rev() {
+                let Some(&segment) = snake.segments().nth(idx) else {
+                    return false;
+                };
+                self.snake.push_front(segment);
+            }
+            while self.snake.len() > snake.len() {
+                if let Some(vacated) = self.snake.pop_back() {
+                    self.set_cell(vacated, CellKind::Empty, theme);
+                }
+            }
+            let followed = if skin_shifts(theme, resized) {
+                self.follow_snake(state, theme)
             } else {
-                CellKind::Empty
+                self.follow_snake_ends(state, steps, theme)
             };
-            let bot_idx = bot_game_row * usize::from(bounds.width) + col;
+            if !followed {
+                return false;
+            }
+        }
 
-            if super_food_body_expansion_mask
-                .as_ref()
-                .is_some_and(|mask| mask[top_idx])
-                && matches!(top_kind, CellKind::Empty)
-            {
-                top_kind = CellKind::SnakeBody(0);
+        if self.head_direction != Some(snake.direction()) {
+            self.head_direction = Some(snake.direction());
+            self.mark_dirty(snake.head());
+        }
+        self.update_foods(state, theme, super_food_hidden, moved);
+        true
+    }
+
+    /// Rewrites every snake cell. Returns false if the cached segments no
+    /// longer match the snake.
+    fn follow_snake(&mut self, state: &GameState, theme: &Theme) -> bool {
+        let snake_len = state.snake.len();
+        if self.snake.len() != snake_len {
+            return false;
+        }
+        for (idx, segment) in state.snake.segments().enumerate() {
+            if self.snake[idx] != *segment {
+                return false;
             }
-            if bot_game_row < game_h
-                && super_food_body_expansion_mask
-                    .as_ref()
-                    .is_some_and(|mask| mask[bot_idx])
-                && matches!(bot_kind, CellKind::Empty)
-            {
-                bot_kind = CellKind::SnakeBody(0);
+            self.set_cell(*segment, segment_kind(idx, snake_len), theme);
+        }
+        true
+    }
+
+    /// Rewrites only the cells at either end of the snake that a move of
+    /// `steps` cells changed, for skins whose body colors stay put. Returns
+    /// false if the cached segments no longer match the snake.
+    fn follow_snake_ends(&mut self, state: &GameState, steps: usize, theme: &Theme) -> bool {
+        let snake = &state.snake;
+        let snake_len = snake.len();
+        if self.snake.len() != snake_len
+            || self.snake.front() != Some(&snake.head())
+            || self.snake.back() != Some(&snake.tail())
+        {
+            return false;
+        }
+        // New head cells, the old head that became body, and the tail.
+        let head_end = 0..=steps.min(snake_len - 1);
+        for idx in head_end.chain([snake_len - 1]) {
+            self.set_cell(self.snake[idx], segment_kind(idx, snake_len), theme);
+        }
+        true
+    }
+
+    /// Moves food cells to match `state`. Food never overrides the snake,
+    /// so after the snake moved every food cell is checked again.
+    fn update_foods(
+        &mut self,
+        state: &GameState,
+        theme: &Theme,
+        super_food_hidden: bool,
+        snake_moved: bool,
+    ) {
+        let changed = super_food_hidden != self.super_food_hidden
+            || self.foods.len() != state.foods.len()
+            || self
+                .foods
+                .iter()
+                .zip(&state.foods)
+                .any(|(&(position, is_super), food)| {
+                    position != food.position || is_super != food.is_super()
+                });
+
+        if changed {
+            for idx in 0..self.foods.len() {
+                let (position, _) = self.foods[idx];
+                if self.kind_at(position).is_some_and(is_food_kind) {
+                    self.set_cell(position, CellKind::Empty, theme);
+                }
             }
+            self.foods.clear();
+            self.foods.extend(
+                state
+                    .foods
+                    .iter()
+                    .map(|food| (food.position, food.is_super())),
+            );
+            self.super_food_hidden = super_food_hidden;
+        }
 
-            let top_neighbor_flash = neighbor_flash_mask
-                .as_ref()
-                .is_some_and(|mask| mask[top_idx]);
-            let bot_neighbor_flash = bot_game_row < game_h
-                && neighbor_flash_mask
-                    .as_ref()
-                    .is_some_and(|mask| mask[bot_idx]);
-            let top_super_flash = super_food_ripple_mask
-                .as_ref()
-                .is_some_and(|mask| mask[top_idx]);
-            let bot_super_flash = bot_game_row < game_h
-                && super_food_ripple_mask
-                    .as_ref()
-                    .is_some_and(|mask| mask[bot_idx]);
-            let top_flash_amount = if top_super_flash {
-                super_food_ripple_flash
-            } else if top_neighbor_flash {
-                level_up_neighbor_flash
-            } else {
-                0.0
-            };
-            let bot_flash_amount = if bot_super_flash {
-                super_food_ripple_flash
-            } else if bot_neighbor_flash {
-                level_up_neighbor_flash
-            } else {
-                0.0
-            };
-            let top_body_flash_amount = if matches!(top_kind, CellKind::SnakeBody(_))
-                && super_food_ripple_center_idx == Some(top_idx)
-            {
-                super_food_ripple_flash
-            } else {
-                0.0
-            };
-            let bot_body_flash_amount = if bot_game_row < game_h
-                && matches!(bot_kind, CellKind::SnakeBody(_))
-                && super_food_ripple_center_idx == Some(bot_idx)
-            {
-                super_food_ripple_flash
-            } else {
-                0.0
-            };
+        if changed || snake_moved {
+            for idx in 0..self.foods.len() {
+                let (position, is_super) = self.foods[idx];
+                if self
+                    .kind_at(position)
+                    .is_some_and(|kind| !is_snake_kind(kind))
+                {
+                    self.set_cell(position, food_kind(is_super, super_food_hidden), theme);
+                }
+            }
+        }
+    }
 
-            let top_bg = if checkerboard_enabled {
-                checker_bg(col, top_game_row, theme)
-            } else {
-                theme.field_bg
-            };
-            let bot_bg = if checkerboard_enabled {
-                checker_bg(col, bot_game_row, theme)
-            } else {
-                theme.field_bg
-            };
-            let top = CellRender {
-                kind: top_kind,
-                bg: top_bg,
-                bg_flash_amount: top_flash_amount,
-                snake_body_flash_amount: top_body_flash_amount,
-            };
-            let bot = CellRender {
-                kind: bot_kind,
-                bg: bot_bg,
-                bg_flash_amount: bot_flash_amount,
-                snake_body_flash_amount: bot_body_flash_amount,
-            };
-            let (glyph, fg, bg) = composite_half_block(top, bot, theme, glow);
-            buffer.set_string(x, y, glyph, Style::new().fg(fg).bg(bg));
+    fn grid_index(&self, position: Position) -> Option<usize> {
+        let bounds = self.key.as_ref()?.bounds;
+        position
+            .is_within_bounds(bounds)
+            .then(|| position.y as usize * usize::from(bounds.width) + position.x as usize)
+    }
+
+    fn kind_at(&self, position: Position) -> Option<CellKind> {
+        self.grid_index(position).map(|idx| self.layers.grid[idx])
+    }
+
+    /// Writes one grid cell, queueing it if it now looks different.
+    fn set_cell(&mut self, position: Position, kind: CellKind, theme: &Theme) {
+        let Some(idx) = self.grid_index(position) else {
+            return;
+        };
+        let old = std::mem::replace(&mut self.layers.grid[idx], kind);
+        if !looks_alike(old, kind, theme) {
+            self.mark_dirty(position);
+        }
+    }
+
+    fn mark_dirty(&mut self, position: Position) {
+        let (Ok(x), Ok(y)) = (usize::try_from(position.x), usize::try_from(position.y)) else {
+            return;
+        };
+        let geometry = glyphs().mode.cell_geometry();
+        let col = x / usize::from(geometry.cols_per_term_cell);
+        let row = y / usize::from(geometry.rows_per_term_cell);
+        if col < self.cols && row < self.rows {
+            self.dirty.push(row * self.cols + col);
+        }
+    }
+
+    fn composite_dirty(
+        &mut self,
+        state: &GameState,
+        theme: &Theme,
+        options: FieldOptions<'_>,
+        mode: GlyphMode,
+    ) {
+        if self.dirty.is_empty() {
+            return;
+        }
+        let head_direction = state.snake.direction();
+        let layers = PlayFieldLayers::with_grid(state, None, options, &mut self.layers);
+        for &idx in &self.dirty {
+            self.cells[idx] = composite_cell(
+                &layers,
+                theme,
+                None,
+                mode,
+                head_direction,
+                idx % self.cols,
+                idx / self.cols,
+            );
+        }
+        self.dirty.clear();
+    }
+
+    /// Copies the composited cells into `buffer`.
+    fn blit(&self, buffer: &mut Buffer, inner: Rect, mode: GlyphMode) {
+        let step = usize::from(mode.cell_geometry().term_cols_per_cell);
+        for (idx, cell) in self.cells.iter().enumerate() {
+            let x = inner.x.saturating_add(((idx % self.cols) * step) as u16);
+            let y = inner.y.saturating_add((idx / self.cols) as u16);
+            let style = Style::new().fg(cell.fg).bg(cell.bg);
+            match cell.glyph {
+                // Square-mode pairs span two columns.
+                CellGlyph::Str(glyph) if step > 1 => {
+                    buffer.set_string(x, y, glyph, style);
+                }
+                CellGlyph::Str(glyph) => {
+                    if let Some(target) = buffer.cell_mut((x, y)) {
+                        target.set_symbol(glyph).set_style(style);
+                    }
+                }
+                CellGlyph::Char(glyph) => {
+                    if let Some(target) = buffer.cell_mut((x, y)) {
+                        target.set_char(glyph).set_style(style);
+                    }
+                }
+            }
         }
     }
 }
 
-fn build_snake_cell_mask(state: &GameState, bounds: GridSize) -> Vec<bool> {
-    let width = usize::from(bounds.width);
-    let height = usize::from(bounds.height);
-    let mut snake_cells = vec![false; width * height];
+/// Returns whether a move can change the color of body segments that kept
+/// their cell: index-keyed skins shift with every move, and length-keyed
+/// ones when the snake grows.
+fn skin_shifts(theme: &Theme, resized: bool) -> bool {
+    match &theme.snake_skin {
+        SnakeSkin::Banded => redden_color(theme.snake_body, 0.8) != theme.snake_body,
+        SnakeSkin::Gradient(stops) => stops.len() > 1,
+        SnakeSkin::Stripes { colors, .. } => colors.len() > 1,
+        SnakeSkin::Length { colors, .. } => resized && colors.len() > 1,
+    }
+}
 
-    for segment in state.snake.segments() {
-        if segment.is_within_bounds(bounds) {
-            let idx = segment.y as usize * width + segment.x as usize;
-            snake_cells[idx] = true;
+/// Returns whether two kinds draw the same outside glow effects, so body
+/// segments that keep their skin color are not composited again.
+fn looks_alike(a: CellKind, b: CellKind, theme: &Theme) -> bool {
+    match (a, b) {
+        (CellKind::SnakeBody(a), CellKind::SnakeBody(b)) => {
+            skin_color(theme, a) == skin_color(theme, b)
         }
+        _ => a == b,
     }
+}
 
-    snake_cells
+/// Composites one terminal cell of the packed grid with the active glyph
+/// mode: a half-block row pair, a 2x4 Braille block or a square cell.
+fn composite_cell(
+    layers: &PlayFieldLayers,
+    theme: &Theme,
+    glow: Option<&GlowEffect>,
+    mode: GlyphMode,
+    head_direction: Direction,
+    col: usize,
+    row: usize,
+) -> TermCell {
+    let (glyph, fg, bg) = match mode {
+        GlyphMode::Unicode => {
+            let top = layers.cell(col, row * 2, theme);
+            let bot = layers.cell(col, row * 2 + 1, theme);
+            let (glyph, fg, bg) = composite_half_block(top, bot, theme, glow, layers.shape_coding);
+            (CellGlyph::Str(glyph), fg, bg)
+        }
+        GlyphMode::Braille => {
+            let mut cells = [CellRender::EMPTY; 8];
+            for (slot, cell) in cells.iter_mut().enumerate() {
+                *cell = layers.cell(col * 2 + slot / 4, row * 4 + slot % 4, theme);
+            }
+            let (glyph, fg, bg) = composite_braille(&cells, theme, glow);
+            (CellGlyph::Char(glyph), fg, bg)
+        }
+        GlyphMode::Ascii => {
+            let top = layers.cell(col, row * 2, theme);
+            let bot = layers.cell(col, row * 2 + 1, theme);
+            let (glyph, fg, bg) =
+                composite_ascii(top, bot, theme, glow, head_direction, layers.shape_coding);
+            (CellGlyph::Str(glyph), fg, bg)
+        }
+        GlyphMode::Square | GlyphMode::AsciiSquare => {
+            let cell = layers.cell(col, row, theme);
+            let (glyph, fg, bg) =
+                composite_square(cell, theme, glow, head_direction, layers.shape_coding);
+            (CellGlyph::Str(glyph), fg, bg)
+        }
+    };
+    TermCell { glyph, fg, bg }
 }
 
-fn build_snake_neighbor_mask(
+/// Rasterizes the play field for a pixel graphics protocol.
+///
+/// The image covers `area` (terminal cells) at `cell_pixels` per cell and uses
+/// the active glyph mode's logical grid, so gameplay bounds are unchanged.
+/// Snake segments are drawn as rounded discs bridged to their neighbors;
+/// food and glow effects get soft radial halos.
+#[must_use]
+pub fn rasterize_play_field(
+    state: &GameState,
+    theme: &Theme,
+    options: FieldOptions<'_>,
+    area: Rect,
+    cell_pixels: CellPixels,
+) -> PixelImage {
+    let glow = state.active_glow();
+    let mut buffers = LayerBuffers::default();
+    let layers = PlayFieldLayers::build(state, glow, options, &mut buffers);
+    let geometry = glyphs().mode.cell_geometry();
+    let cell_w = f32::from(cell_pixels.width) * f32::from(geometry.term_cols_per_cell)
+        / f32::from(geometry.cols_per_term_cell);
+    let cell_h = f32::from(cell_pixels.height) / f32::from(geometry.rows_per_term_cell);
+    let rgb = |color| {
+        let (r, g, b) = color_to_rgb(color).unwrap_or((0, 0, 0));
+        [r, g, b]
+    };
+
+    let mut image = PixelImage::new(
+        u32::from(area.width) * u32::from(cell_pixels.width),
+        u32::from(area.height) * u32::from(cell_pixels.height),
+        rgb(theme.field_bg),
+    );
+
+    let width = usize::from(layers.bounds.width);
+    let center = |col: i32, row: i32| ((col as f32 + 0.5) * cell_w, (row as f32 + 0.5) * cell_h);
+    let unit = cell_w.min(cell_h);
+
+    for row in 0..usize::from(layers.bounds.height) {
+        for col in 0..width {
+            let cell = layers.cell(col, row, theme);
+            let bg = apply_neighbor_flash(cell.bg, cell.bg_flash_amount);
+            let (x0, y0) = (col as f32 * cell_w, row as f32 * cell_h);
+            image.fill_rect(x0, y0, x0 + cell_w, y0 + cell_h, rgb(bg));
+
+            // Super food ripple expands the body into empty neighbors.
+            if matches!(cell.kind, CellKind::SnakeBody(_))
+                && layers.grid[row * width + col] == CellKind::Empty
+            {
+                let color = cell_color(cell.kind, theme, glow, cell.snake_body_flash_amount);
+                let (cx, cy) = center(col as i32, row as i32);
+                image.fill_circle(cx, cy, unit * 0.4, rgb(color), 1.0);
+            }
+        }
+    }
+
+    for food in &state.foods {
+        let (cx, cy) = center(food.position.x, food.position.y);
+        if food.is_super() && layers.super_food_hidden {
+            continue;
+        }
+        if food.is_super() {
+            image.fill_glow(cx, cy, unit * 1.2, rgb(theme.super_food), 0.45);
+            image.fill_circle(cx, cy, unit * 0.42, rgb(theme.super_food), 1.0);
+        } else {
+            image.fill_glow(cx, cy, unit * 0.8, rgb(theme.food), 0.25);
+            image.fill_circle(cx, cy, unit * 0.36, rgb(theme.food), 1.0);
+        }
+    }
+
+    if let Some(effect) = glow.filter(|effect| effect.trigger == GlowTrigger::SpeedLevelUp) {
+        let halo = rgb(glow_target_color(effect.trigger, theme));
+        for segment in state.snake.segments() {
+            let (cx, cy) = center(segment.x, segment.y);
+            image.fill_glow(cx, cy, unit * 1.1, halo, 0.35 * effect.intensity());
+        }
+    }
+
+    // Paint tail first so the head always ends up on top.
+    let segments: Vec<Position> = state.snake.segments().copied().collect();
+    let radius = unit * 0.4;
+    for idx in (0..segments.len()).rev() {
+        let segment = segments[idx];
+        if !segment.is_within_bounds(layers.bounds) {
+            continue;
+        }
+
+        let cell = layers.cell(segment.x as usize, segment.y as usize, theme);
+        let color = rgb(cell_color(
+            cell.kind,
+            theme,
+            glow,
+            cell.snake_body_flash_amount,
+        ));
+        let (cx, cy) = center(segment.x, segment.y);
+
+        // Bridge toward the next segment unless the snake wrapped an edge.
+        if let Some(next) = idx.checked_sub(1).map(|next| segments[next])
+            && (next.x - segment.x).abs() + (next.y - segment.y).abs() == 1
+        {
+            let (nx, ny) = center(next.x, next.y);
+            image.fill_rect(
+                cx.min(nx) - if cx == nx { radius } else { 0.0 },
+                cy.min(ny) - if cy == ny { radius } else { 0.0 },
+                cx.max(nx) + if cx == nx { radius } else { 0.0 },
+                cy.max(ny) + if cy == ny { radius } else { 0.0 },
+                color,
+            );
+        }
+
+        if idx == 0 {
+            image.fill_circle(cx, cy, unit * 0.46, color, 1.0);
+            let (dx, dy) = match state.snake.direction() {
+                Direction::Up => (0.0, -1.0),
+                Direction::Down => (0.0, 1.0),
+                Direction::Left => (-1.0, 0.0),
+                Direction::Right => (1.0, 0.0),
+            };
+            let eye = rgb(theme.field_bg);
+            for side in [-1.0, 1.0] {
+                let ex = cx + dx * unit * 0.18 - dy * side * unit * 0.2;
+                let ey = cy + dy * unit * 0.18 + dx * side * unit * 0.2;
+                image.fill_circle(ex, ey, unit * 0.08, eye, 1.0);
+            }
+        } else {
+            image.fill_circle(cx, cy, radius, color, 1.0);
+        }
+    }
+
+    image
+}
+
+/// Clears `mask` to `len` unset cells, keeping its allocation.
+fn reset_mask(mask: &mut Vec<bool>, len: usize) {
+    mask.clear();
+    mask.resize(len, false);
+}
+
+fn is_snake_kind(kind: CellKind) -> bool {
+    matches!(
+        kind,
+        CellKind::SnakeHead | CellKind::SnakeBody(_) | CellKind::SnakeTail
+    )
+}
+
+fn is_food_kind(kind: CellKind) -> bool {
+    matches!(kind, CellKind::Food | CellKind::SuperFood)
+}
+
+fn fill_snake_neighbor_mask(
+    neighbors: &mut Vec<bool>,
     state: &GameState,
     bounds: GridSize,
-    snake_cells: &[bool],
-) -> Vec<bool> {
+    grid: &[CellKind],
+) {
     let width = usize::from(bounds.width);
     let height = usize::from(bounds.height);
-    let mut neighbors = vec![false; width * height];
+    reset_mask(neighbors, width * height);
 
     for segment in state.snake.segments() {
         for dy in -1..=1 {
@@ -421,25 +1251,22 @@ fn build_snake_neighbor_mask(
                 }
 
                 let idx = ny as usize * width + nx as usize;
-                if !snake_cells[idx] {
+                if !is_snake_kind(grid[idx]) {
                     neighbors[idx] = true;
                 }
             }
         }
     }
-
-    neighbors
 }
 
-fn build_super_food_ripple_mask(
+fn fill_super_food_ripple_mask(
+    mask: &mut Vec<bool>,
     bounds: GridSize,
-    snake_cells: &[bool],
-    center: Option<Position>,
-) -> Option<Vec<bool>> {
-    let center = center?;
-
+    grid: &[CellKind],
+    center: Position,
+) {
     let width = usize::from(bounds.width);
-    let mut mask = vec![false; width * usize::from(bounds.height)];
+    reset_mask(mask, width * usize::from(bounds.height));
 
     for dy in -1..=1 {
         for dx in -1..=1 {
@@ -454,22 +1281,16 @@ fn build_super_food_ripple_mask(
             }
 
             let idx = ny as usize * width + nx as usize;
-            if !snake_cells[idx] {
+            if !is_snake_kind(grid[idx]) {
                 mask[idx] = true;
             }
         }
     }
-
-    Some(mask)
 }
 
-fn build_super_food_body_expansion_mask(
-    bounds: GridSize,
-    center: Option<Position>,
-) -> Option<Vec<bool>> {
-    let center = center?;
+fn fill_super_food_body_expansion_mask(mask: &mut Vec<bool>, bounds: GridSize, center: Position) {
     let width = usize::from(bounds.width);
-    let mut mask = vec![false; width * usize::from(bounds.height)];
+    reset_mask(mask, width * usize::from(bounds.height));
 
     for dy in -1..=1 {
         for dx in -1..=1 {
@@ -483,8 +1304,6 @@ fn build_super_food_body_expansion_mask(
             mask[idx] = true;
         }
     }
-
-    Some(mask)
 }
 
 fn super_food_ripple_center_position(
@@ -516,55 +1335,99 @@ fn tick_interval_for_speed(speed_level: u32) -> std::time::Duration {
     std::time::Duration::from_millis(clamped_ms)
 }
 
-/// Populates a flat grid of `CellKind` values indexed by `row * width + col`.
-fn build_cell_grid(state: &GameState, bounds: GridSize) -> Vec<CellKind> {
+/// Returns whether super food is in the hidden phase of its shape-coding
+/// blink.
+fn super_food_hidden(state: &GameState, shape_coding: bool) -> bool {
+    shape_coding && state.tick_count % SUPER_FOOD_BLINK_TICKS == SUPER_FOOD_BLINK_TICKS - 1
+}
+
+/// Returns the kind drawn for a food, which is empty while super food is
+/// blinked out.
+fn food_kind(is_super: bool, super_food_hidden: bool) -> CellKind {
+    match (is_super, super_food_hidden) {
+        (false, _) => CellKind::Food,
+        (true, false) => CellKind::SuperFood,
+        (true, true) => CellKind::Empty,
+    }
+}
+
+/// Returns the kind of the snake segment at `idx`, where 0 is the head.
+fn segment_kind(idx: usize, snake_len: usize) -> CellKind {
+    if idx == 0 {
+        CellKind::SnakeHead
+    } else if idx + 1 == snake_len {
+        CellKind::SnakeTail
+    } else {
+        CellKind::SnakeBody(BodySegment {
+            index: idx - 1,
+            body_len: snake_len.saturating_sub(2),
+        })
+    }
+}
+
+/// Fills `grid` with the `CellKind` of every cell, indexed by
+/// `row * width + col`.
+fn fill_cell_grid(
+    grid: &mut Vec<CellKind>,
+    state: &GameState,
+    bounds: GridSize,
+    super_food_hidden: bool,
+) {
     let w = usize::from(bounds.width);
     let h = usize::from(bounds.height);
-    let mut grid = vec![CellKind::Empty; w * h];
+    grid.clear();
+    grid.resize(w * h, CellKind::Empty);
 
     // Food
     for food in &state.foods {
         let fp = food.position;
         if fp.is_within_bounds(bounds) {
-            let kind = if food.is_super() {
-                CellKind::SuperFood
-            } else {
-                CellKind::Food
-            };
-            grid[fp.y as usize * w + fp.x as usize] = kind;
+            grid[fp.y as usize * w + fp.x as usize] = food_kind(food.is_super(), super_food_hidden);
         }
     }
 
     // Snake segments — index 0 is the head.
     let snake_len = state.snake.len();
     for (idx, seg) in state.snake.segments().enumerate() {
-        if !seg.is_within_bounds(bounds) {
-            continue;
+        if seg.is_within_bounds(bounds) {
+            grid[seg.y as usize * w + seg.x as usize] = segment_kind(idx, snake_len);
         }
-        let kind = if idx == 0 {
-            CellKind::SnakeHead
-        } else if idx + 1 == snake_len {
-            CellKind::SnakeTail
-        } else {
-            CellKind::SnakeBody(idx - 1)
-        };
-        grid[seg.y as usize * w + seg.x as usize] = kind;
     }
-
-    grid
 }
 
 /// Returns (glyph, fg_color, bg_color) for a terminal cell compositing two game rows.
+///
+/// With `shape_coding`, the cell holding the head is drawn as a head-colored
+/// mark over whatever fills the other half, or over the field background
+/// when that half has the head's color.
 fn composite_half_block(
     top: CellRender,
     bot: CellRender,
     theme: &Theme,
     glow: Option<&GlowEffect>,
+    shape_coding: bool,
 ) -> (&'static str, ratatui::style::Color, ratatui::style::Color) {
     let palette = glyphs();
     let top_bg = apply_neighbor_flash(top.bg, top.bg_flash_amount);
     let bot_bg = apply_neighbor_flash(bot.bg, bot.bg_flash_amount);
 
+    if shape_coding {
+        let halves = match (top.kind, bot.kind) {
+            (CellKind::SnakeHead, _) => Some((top_bg, bot, bot_bg)),
+            (_, CellKind::SnakeHead) => Some((bot_bg, top, top_bg)),
+            _ => None,
+        };
+        if let Some((head_bg, other, other_bg)) = halves {
+            let fg = cell_color(CellKind::SnakeHead, theme, glow, 0.0);
+            let bg = match other.kind {
+                CellKind::Empty => other_bg,
+                kind => cell_color(kind, theme, glow, other.snake_body_flash_amount),
+            };
+            let bg = if bg == fg { head_bg } else { bg };
+            return (palette.head_mark, fg, bg);
+        }
+    }
+
     match (top.kind, bot.kind) {
         (CellKind::Empty, CellKind::Empty) => (palette.half_upper, top_bg, bot_bg),
         (top_kind, CellKind::Empty) => (
@@ -585,11 +1448,199 @@ fn composite_half_block(
     }
 }
 
+/// Returns (glyph, fg_color, bg_color) for one square logical cell.
+///
+/// Each entity gets its own glyph pair from the palette, so the head points
+/// in the direction of travel and food stays distinguishable from the snake
+/// even without color. Shape coding swaps in a hatched body glyph so the
+/// body also differs from the tail and food in texture.
+fn composite_square(
+    cell: CellRender,
+    theme: &Theme,
+    glow: Option<&GlowEffect>,
+    head_direction: Direction,
+    shape_coding: bool,
+) -> (&'static str, ratatui::style::Color, ratatui::style::Color) {
+    let glyphs = glyphs().cell;
+    let bg = apply_neighbor_flash(cell.bg, cell.bg_flash_amount);
+    if matches!(cell.kind, CellKind::Empty) {
+        return (glyphs.empty, bg, bg);
+    }
+
+    (
+        entity_glyph(glyphs, cell.kind, head_direction, shape_coding),
+        cell_color(cell.kind, theme, glow, cell.snake_body_flash_amount),
+        bg,
+    )
+}
+
+/// Returns (glyph, fg_color, bg_color) for an ASCII terminal cell
+/// compositing two game rows.
+///
+/// ASCII has no half blocks, so the cell shows the character of the
+/// higher-priority entity of the two rows in that entity's color, over the
+/// other row's color. Empty pairs are blank over the top row's background.
+fn composite_ascii(
+    top: CellRender,
+    bot: CellRender,
+    theme: &Theme,
+    glow: Option<&GlowEffect>,
+    head_direction: Direction,
+    shape_coding: bool,
+) -> (&'static str, ratatui::style::Color, ratatui::style::Color) {
+    let glyphs = glyphs().compact;
+    let (entity, other) = if entity_priority(bot.kind) > entity_priority(top.kind) {
+        (bot, top)
+    } else {
+        (top, bot)
+    };
+    let entity_bg = apply_neighbor_flash(entity.bg, entity.bg_flash_amount);
+    if matches!(entity.kind, CellKind::Empty) {
+        return (glyphs.empty, entity_bg, entity_bg);
+    }
+
+    let fg = cell_color(entity.kind, theme, glow, entity.snake_body_flash_amount);
+    let bg = match other.kind {
+        CellKind::Empty => apply_neighbor_flash(other.bg, other.bg_flash_amount),
+        kind => cell_color(kind, theme, glow, other.snake_body_flash_amount),
+    };
+    let bg = if bg == fg { entity_bg } else { bg };
+    (
+        entity_glyph(glyphs, entity.kind, head_direction, shape_coding),
+        fg,
+        bg,
+    )
+}
+
+/// Picks the palette glyph for a non-empty cell.
+fn entity_glyph(
+    glyphs: CellGlyphs,
+    kind: CellKind,
+    head_direction: Direction,
+    shape_coding: bool,
+) -> &'static str {
+    match kind {
+        CellKind::Empty => glyphs.empty,
+        CellKind::SnakeHead => match head_direction {
+            Direction::Up => glyphs.head[0],
+            Direction::Down => glyphs.head[1],
+            Direction::Left => glyphs.head[2],
+            Direction::Right => glyphs.head[3],
+        },
+        CellKind::SnakeBody(_) if shape_coding => glyphs.hatched_body,
+        CellKind::SnakeBody(_) => glyphs.body,
+        CellKind::SnakeTail => glyphs.tail,
+        CellKind::Food => glyphs.food,
+        CellKind::SuperFood => glyphs.super_food,
+    }
+}
+
+/// Dot bit for each Braille slot, ordered column-major (left column rows
+/// 0–3, then right column rows 0–3) to match `render_play_area`.
+const BRAILLE_DOT_BITS: [u32; 8] = [0x01, 0x02, 0x04, 0x40, 0x08, 0x10, 0x20, 0x80];
+
+/// Returns (glyph, fg_color, bg_color) for a terminal cell holding a 2x4 block
+/// of logical cells as Braille dots.
+///
+/// A Braille glyph carries a single foreground color, so the dots take the
+/// color of the highest-priority entity in the block (head, super food, food,
+/// tail, then body). This keeps food and the head visible when they share a
+/// cell with body segments. Among cells of the winning kind the most frequent
+/// color is used. The background is the most common background among the
+/// empty cells.
+fn composite_braille(
+    cells: &[CellRender; 8],
+    theme: &Theme,
+    glow: Option<&GlowEffect>,
+) -> (char, ratatui::style::Color, ratatui::style::Color) {
+    let mut pattern = 0u32;
+    let mut best_priority = 0u8;
+    let mut fg_votes = ColorVotes::default();
+    let mut bg_votes = ColorVotes::default();
+
+    for (slot, cell) in cells.iter().enumerate() {
+        let priority = entity_priority(cell.kind);
+        if priority == 0 {
+            bg_votes.vote(apply_neighbor_flash(cell.bg, cell.bg_flash_amount));
+            continue;
+        }
+
+        pattern |= BRAILLE_DOT_BITS[slot];
+        if priority > best_priority {
+            best_priority = priority;
+            fg_votes = ColorVotes::default();
+        }
+        if priority == best_priority {
+            fg_votes.vote(cell_color(
+                cell.kind,
+                theme,
+                glow,
+                cell.snake_body_flash_amount,
+            ));
+        }
+    }
+
+    let bg = bg_votes
+        .majority()
+        .unwrap_or_else(|| apply_neighbor_flash(cells[0].bg, cells[0].bg_flash_amount));
+    let fg = fg_votes.majority().unwrap_or(bg);
+    let glyph = char::from_u32(GLYPH_BRAILLE_BASE + pattern).unwrap_or(' ');
+
+    (glyph, fg, bg)
+}
+
+/// Ranks entities for cells that can show only one of several: head, super
+/// food, food, tail, then body.
+fn entity_priority(kind: CellKind) -> u8 {
+    match kind {
+        CellKind::Empty => 0,
+        CellKind::SnakeBody(_) => 1,
+        CellKind::SnakeTail => 2,
+        CellKind::Food => 3,
+        CellKind::SuperFood => 4,
+        CellKind::SnakeHead => 5,
+    }
+}
+
+/// Color tallies for one Braille cell, which has eight dots at most.
+#[derive(Debug, Clone, Copy, Default)]
+struct ColorVotes {
+    entries: [(ratatui::style::Color, usize); 8],
+    len: usize,
+}
+
+impl ColorVotes {
+    fn vote(&mut self, color: ratatui::style::Color) {
+        if let Some(entry) = self.entries[..self.len]
+            .iter_mut()
+            .find(|(existing, _)| *existing == color)
+        {
+            entry.1 += 1;
+        } else if self.len < self.entries.len() {
+            self.entries[self.len] = (color, 1);
+            self.len += 1;
+        }
+    }
+
+    /// Returns the most frequent color, preferring the earliest on ties.
+    fn majority(&self) -> Option<ratatui::style::Color> {
+        self.entries[..self.len]
+            .iter()
+            .fold(
+                None,
+                |best: Option<(ratatui::style::Color, usize)>, &(color, count)| match best {
+                    Some((_, best_count)) if best_count >= count => best,
+                    _ => Some((color, count)),
+                },
+            )
+            .map(|(color, _)| color)
+    }
+}
+
 /// Maps a non-empty `CellKind` to its theme color, with optional glow blending.
 ///
-/// Snake body uses alternating 3-segment bands: even bands use the base
-/// `snake_body` color; odd bands have the red channel boosted by 10%.
-/// When a glow effect is active, snake cells are blended toward the glow color.
+/// Snake body colors come from the theme's [`SnakeSkin`]. When a glow effect
+/// is active, snake cells are blended toward the glow color.
 fn cell_color(
     kind: CellKind,
     theme: &Theme,
@@ -611,13 +1662,8 @@ fn cell_color(
                 base
             }
         }
-        CellKind::SnakeBody(idx) => {
-            let band = idx / 3;
-            let base = if band % 2 == 0 {
-                theme.snake_body
-            } else {
-                redden_color(theme.snake_body, 0.8)
-            };
+        CellKind::SnakeBody(segment) => {
+            let base = skin_color(theme, segment);
             if let Some(effect) = glow {
                 match effect.trigger {
                     GlowTrigger::SpeedLevelUp => {
@@ -652,6 +1698,49 @@ fn cell_color(
     }
 }
 
+/// Returns the color the theme's skin gives one body segment.
+///
+/// Gradients place the head at 0.0 and the tail at 1.0, so body segments
+/// fall strictly between the first and last stops.
+fn skin_color(theme: &Theme, segment: BodySegment) -> ratatui::style::Color {
+    match &theme.snake_skin {
+        SnakeSkin::Banded => {
+            // Odd three-segment bands have the red channel boosted by 10%.
+            if (segment.index / 3).is_multiple_of(2) {
+                theme.snake_body
+            } else {
+                redden_color(theme.snake_body, 0.8)
+            }
+        }
+        SnakeSkin::Gradient(stops) => {
+            let t = (segment.index + 1) as f32 / (segment.body_len + 1) as f32;
+            gradient_color(stops, t).unwrap_or(theme.snake_body)
+        }
+        SnakeSkin::Stripes { colors, width } => colors
+            .get((segment.index / (*width).max(1)) % colors.len().max(1))
+            .copied()
+            .unwrap_or(theme.snake_body),
+        SnakeSkin::Length { colors, length } => {
+            let snake_len = segment.body_len + 2;
+            let t = snake_len as f32 / (*length).max(1) as f32;
+            gradient_color(colors, t).unwrap_or(theme.snake_body)
+        }
+    }
+}
+
+/// Blends between evenly spaced `stops` at `t` (0.0–1.0).
+fn gradient_color(stops: &[ratatui::style::Color], t: f32) -> Option<ratatui::style::Color> {
+    match stops {
+        [] => None,
+        [only] => Some(*only),
+        _ => {
+            let scaled = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+            let idx = (scaled.floor() as usize).min(stops.len() - 2);
+            Some(lerp_color(stops[idx], stops[idx + 1], scaled - idx as f32))
+        }
+    }
+}
+
 /// Returns the glow target color for a given trigger type.
 fn glow_target_color(trigger: GlowTrigger, theme: &Theme) -> ratatui::style::Color {
     use ratatui::style::Color;
@@ -711,72 +1800,6 @@ fn brighten_color(color: ratatui::style::Color, amount: f32) -> ratatui::style::
     )
 }
 
-fn color_to_rgb(color: ratatui::style::Color) -> Option<(u8, u8, u8)> {
-    use ratatui::style::Color;
-
-    let rgb = match color {
-        Color::Rgb(r, g, b) => (r, g, b),
-        Color::Black => (0, 0, 0),
-        Color::Red => (205, 49, 49),
-        Color::Green => (13, 188, 121),
-        Color::Yellow => (229, 229, 16),
-        Color::Blue => (36, 114, 200),
-        Color::Magenta => (188, 63, 188),
-        Color::Cyan => (17, 168, 205),
-        Color::Gray => (229, 229, 229),
-        Color::DarkGray => (102, 102, 102),
-        Color::LightRed => (241, 76, 76),
-        Color::LightGreen => (35, 209, 139),
-        Color::LightYellow => (245, 245, 67),
-        Color::LightBlue => (59, 142, 234),
-        Color::LightMagenta => (214, 112, 214),
-        Color::LightCyan => (41, 184, 219),
-        Color::White => (255, 255, 255),
-        Color::Indexed(index) => xterm_index_to_rgb(index),
-        Color::Reset => return None,
-    };
-
-    Some(rgb)
-}
-
-fn xterm_index_to_rgb(index: u8) -> (u8, u8, u8) {
-    if index < 16 {
-        const ANSI16: [(u8, u8, u8); 16] = [
-            (0, 0, 0),
-            (128, 0, 0),
-            (0, 128, 0),
-            (128, 128, 0),
-            (0, 0, 128),
-            (128, 0, 128),
-            (0, 128, 128),
-            (192, 192, 192),
-            (128, 128, 128),
-            (255, 0, 0),
-            (0, 255, 0),
-            (255, 255, 0),
-            (0, 0, 255),
-            (255, 0, 255),
-            (0, 255, 255),
-            (255, 255, 255),
-        ];
-        return ANSI16[index as usize];
-    }
-
-    if index <= 231 {
-        let i = index - 16;
-        let r = i / 36;
-        let g = (i % 36) / 6;
-        let b = i % 6;
-
-        let level = |v: u8| -> u8 { if v == 0 { 0 } else { 55 + v * 40 } };
-
-        return (level(r), level(g), level(b));
-    }
-
-    let gray = 8 + (index - 232) * 10;
-    (gray, gray, gray)
-}
-
 /// Linearly interpolates between two `Rgb` colors at factor `t` (0.0–1.0).
 /// Returns `from` unchanged when either color is a named (non-RGB) color.
 fn lerp_color(
@@ -810,3 +1833,427 @@ fn redden_color(color: ratatui::style::Color, factor: f32) -> ratatui::style::Co
         other => other,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Color;
+
+    use super::{
+        BodySegment, CellKind, CellRender, FieldOptions, LayerBuffers, PREVIEW_SNAKE_LEN,
+        PlayFieldLayers, RenderCache, cell_color, composite_ascii, composite_braille,
+        composite_half_block, composite_square, grid_position_at, pattern_bg, preview_loop,
+        preview_state, skin_color,
+    };
+    use crate::background::BackgroundPattern;
+    use crate::config::{GridSize, SnakeSkin, fallback_theme, glyphs};
+    use crate::food::Food;
+    use crate::game::{GameState, GameStatus, GlowEffect};
+    use crate::input::Direction;
+    use crate::snake::Position;
+
+    fn cell(kind: CellKind) -> CellRender {
+        CellRender {
+            kind,
+            ..CellRender::EMPTY
+        }
+    }
+
+    #[test]
+    fn clicks_map_to_the_grid_cell_drawn_under_them() {
+        // Library tests run with the default half-block palette: one column
+        // and two rows per terminal cell.
+        assert_eq!(glyphs().mode, crate::config::GlyphMode::Unicode);
+        let area = ratatui::layout::Rect::new(4, 2, 20, 10);
+        let bounds = GridSize {
+            width: 20,
+            height: 20,
+        };
+
+        assert_eq!(
+            grid_position_at(area, bounds, 4, 2),
+            Some(Position { x: 0, y: 0 })
+        );
+        assert_eq!(
+            grid_position_at(area, bounds, 10, 5),
+            Some(Position { x: 6, y: 6 })
+        );
+        assert_eq!(grid_position_at(area, bounds, 3, 5), None);
+        assert_eq!(grid_position_at(area, bounds, 10, 12), None);
+    }
+
+    #[test]
+    fn cached_frames_match_frames_composited_from_scratch() {
+        let area = ratatui::layout::Rect::new(2, 1, 16, 8);
+        let bounds = glyphs()
+            .mode
+            .grid_size_for_viewport(area.width, area.height);
+        let options = FieldOptions {
+            background: &BackgroundPattern::Checkerboard,
+            shape_coding: true,
+        };
+        // Banded and length skins recolor the body as it moves or grows;
+        // a one-color stripe skin only changes at the ends.
+        let skins = [
+            SnakeSkin::Banded,
+            SnakeSkin::Stripes {
+                colors: vec![Color::Rgb(10, 200, 10)],
+                width: 1,
+            },
+            SnakeSkin::Length {
+                colors: vec![Color::Rgb(0, 0, 0), Color::Rgb(200, 200, 200)],
+                length: 12,
+            },
+        ];
+
+        for skin in skins {
+            let mut theme = fallback_theme();
+            theme.snake_skin = skin;
+            let mut state = GameState::new_with_seed(bounds, 11);
+            state.foods = vec![
+                Food::new(Position { x: 11, y: 8 }),
+                Food::new(Position { x: 13, y: 12 }),
+                Food::new_super(Position { x: 4, y: 5 }, 500),
+            ];
+            let draw = |cache: &mut RenderCache, state: &GameState| {
+                let mut buffer =
+                    ratatui::buffer::Buffer::empty(ratatui::layout::Rect::new(0, 0, 20, 10));
+                cache.draw(&mut buffer, area, state, &theme, options);
+                buffer
+            };
+
+            let mut cache = RenderCache::default();
+            for _ in 0..60 {
+                // Circle the board one cell in from the edge.
+                let head = state.snake.head();
+                let turn = match (head.x, head.y) {
+                    (13, y) if y < 13 => Direction::Down,
+                    (x, 13) if x > 2 => Direction::Left,
+                    (2, y) if y > 2 => Direction::Up,
+                    (x, 2) if x < 13 => Direction::Right,
+                    _ => state.snake.direction(),
+                };
+                state.snake.buffer_direction(turn);
+                state.tick();
+                if state.status != GameStatus::Playing {
+                    break;
+                }
+
+                let cached = draw(&mut cache, &state);
+                assert!(cache.field_changed());
+                // Glow colors follow the wall clock, so two draws can differ.
+                if state.active_glow().is_none() {
+                    assert_eq!(cached, draw(&mut RenderCache::default(), &state));
+                }
+                // A frame without a tick reuses every cached cell.
+                assert_eq!(draw(&mut cache, &state), cached);
+                assert_eq!(cache.field_changed(), state.active_glow().is_some());
+            }
+            assert!(state.snake.len() > 2, "the snake should have eaten");
+        }
+    }
+
+    #[test]
+    fn shape_coding_blinks_super_food_out_on_the_last_tick_of_each_cycle() {
+        let bounds = GridSize {
+            width: 10,
+            height: 10,
+        };
+        let mut state = GameState::new_with_seed(bounds, 0);
+        state.foods = vec![Food::new_super(Position { x: 7, y: 7 }, 50)];
+        let has_super_food = |state: &GameState, shape_coding| {
+            let options = FieldOptions {
+                background: &BackgroundPattern::Plain,
+                shape_coding,
+            };
+            PlayFieldLayers::build(state, None, options, &mut LayerBuffers::default())
+                .grid
+                .contains(&CellKind::SuperFood)
+        };
+
+        state.tick_count = 2;
+        assert!(has_super_food(&state, true));
+        state.tick_count = 3;
+        assert!(!has_super_food(&state, true));
+        assert!(has_super_food(&state, false));
+        state.tick_count = 4;
+        assert!(has_super_food(&state, true));
+    }
+
+    #[test]
+    fn shape_coding_marks_head_and_hatches_square_body() {
+        let theme = fallback_theme();
+        let head = cell(CellKind::SnakeHead);
+        let food = cell(CellKind::Food);
+        let body = cell(CellKind::SnakeBody(segment(0, 3)));
+
+        let (glyph, fg, bg) = composite_half_block(head, food, &theme, None, true);
+        assert_eq!(glyph, glyphs().head_mark);
+        assert_eq!((fg, bg), (theme.snake_head, theme.food));
+        let (glyph, ..) = composite_half_block(head, food, &theme, None, false);
+        assert_eq!(glyph, glyphs().half_upper);
+
+        let (glyph, ..) = composite_square(body, &theme, None, Direction::Up, true);
+        assert_eq!(glyph, glyphs().cell.hatched_body);
+        let (glyph, ..) = composite_square(body, &theme, None, Direction::Up, false);
+        assert_eq!(glyph, glyphs().cell.body);
+    }
+
+    #[test]
+    fn braille_dots_take_the_color_of_the_highest_priority_entity() {
+        let theme = fallback_theme();
+        let body = cell(CellKind::SnakeBody(segment(0, 3)));
+        let mut block = [CellRender::EMPTY; 8];
+        block[0] = body;
+        block[4] = body;
+
+        let (glyph, fg, _) = composite_braille(&block, &theme, None);
+        assert_eq!(glyph, '\u{2809}');
+        assert_eq!(fg, cell_color(body.kind, &theme, None, 0.0));
+
+        let ranked = [
+            (CellKind::SnakeTail, theme.snake_tail),
+            (CellKind::Food, theme.food),
+            (CellKind::SuperFood, theme.super_food),
+            (CellKind::SnakeHead, theme.snake_head),
+        ];
+        for (slot, (kind, color)) in ranked.into_iter().enumerate() {
+            block[slot + 1] = cell(kind);
+            let (_, fg, _) = composite_braille(&block, &theme, None);
+            assert_eq!(fg, color, "{kind:?} should outrank the entities before it");
+        }
+
+        // A lower-priority entity added later does not take the color back.
+        block[7] = cell(CellKind::Food);
+        let (_, fg, _) = composite_braille(&block, &theme, None);
+        assert_eq!(fg, theme.snake_head);
+    }
+
+    #[test]
+    fn braille_ties_use_the_majority_color_and_empty_dots_set_the_background() {
+        let mut theme = fallback_theme();
+        theme.snake_skin = SnakeSkin::Stripes {
+            colors: vec![Color::Yellow, Color::Magenta],
+            width: 1,
+        };
+        let body = cell(CellKind::SnakeBody(segment(0, 3)));
+        let striped = cell(CellKind::SnakeBody(segment(1, 3)));
+        assert_eq!(cell_color(striped.kind, &theme, None, 0.0), Color::Magenta);
+
+        let empty_on = |bg| CellRender {
+            bg,
+            ..CellRender::EMPTY
+        };
+        let block = [
+            striped,
+            body,
+            body,
+            empty_on(Color::Red),
+            empty_on(Color::Blue),
+            empty_on(Color::Blue),
+            empty_on(Color::Red),
+            empty_on(Color::Blue),
+        ];
+        let (_, fg, bg) = composite_braille(&block, &theme, None);
+        assert_eq!(fg, Color::Yellow);
+        assert_eq!(bg, Color::Blue);
+
+        // A full block has no empty dot to vote, so the first cell's
+        // background shows through.
+        let full = [CellRender {
+            bg: Color::Green,
+            ..body
+        }; 8];
+        let (glyph, _, bg) = composite_braille(&full, &theme, None);
+        assert_eq!(glyph, '\u{28ff}');
+        assert_eq!(bg, Color::Green);
+
+        let (glyph, fg, bg) = composite_braille(&[empty_on(Color::Red); 8], &theme, None);
+        assert_eq!(glyph, '\u{2800}');
+        assert_eq!((fg, bg), (Color::Red, Color::Red));
+    }
+
+    #[test]
+    fn ascii_cells_show_the_higher_priority_entity_over_the_other_row() {
+        let theme = fallback_theme();
+        let body = cell(CellKind::SnakeBody(segment(0, 3)));
+        let body_color = cell_color(body.kind, &theme, None, 0.0);
+        let compact = glyphs().compact;
+
+        let (glyph, fg, bg) = composite_ascii(
+            body,
+            cell(CellKind::Food),
+            &theme,
+            None,
+            Direction::Up,
+            false,
+        );
+        assert_eq!((glyph, fg, bg), (compact.food, theme.food, body_color));
+
+        let (glyph, fg, bg) = composite_ascii(
+            CellRender::EMPTY,
+            cell(CellKind::SnakeHead),
+            &theme,
+            None,
+            Direction::Left,
+            false,
+        );
+        assert_eq!(glyph, compact.head[2]);
+        assert_eq!((fg, bg), (theme.snake_head, CellRender::EMPTY.bg));
+
+        let (glyph, ..) = composite_ascii(body, body, &theme, None, Direction::Up, true);
+        assert_eq!(glyph, compact.hatched_body);
+        let (glyph, ..) = composite_ascii(
+            CellRender::EMPTY,
+            CellRender::EMPTY,
+            &theme,
+            None,
+            Direction::Up,
+            false,
+        );
+        assert_eq!(glyph, compact.empty);
+    }
+
+    fn segment(index: usize, body_len: usize) -> BodySegment {
+        BodySegment { index, body_len }
+    }
+
+    #[test]
+    fn pattern_backgrounds_blend_rgb_and_snap_named_field_colors() {
+        let bounds = GridSize {
+            width: 10,
+            height: 11,
+        };
+        let mut theme = fallback_theme();
+        theme.field_bg = Color::Rgb(0, 0, 0);
+        theme.field_bg_alt = Color::Rgb(100, 100, 100);
+
+        let gradient = BackgroundPattern::Gradient;
+        assert_eq!(pattern_bg(&gradient, 0, 0, bounds, &theme), theme.field_bg);
+        assert_eq!(
+            pattern_bg(&gradient, 0, 5, bounds, &theme),
+            Color::Rgb(50, 50, 50)
+        );
+        assert_eq!(
+            pattern_bg(&gradient, 0, 10, bounds, &theme),
+            theme.field_bg_alt
+        );
+
+        theme.field_bg = Color::Black;
+        theme.field_bg_alt = Color::DarkGray;
+        assert_eq!(pattern_bg(&gradient, 0, 3, bounds, &theme), Color::Black);
+        assert_eq!(pattern_bg(&gradient, 0, 7, bounds, &theme), Color::DarkGray);
+    }
+
+    #[test]
+    fn gradient_skin_runs_from_head_stop_to_tail_stop() {
+        let mut theme = fallback_theme();
+        theme.snake_skin = SnakeSkin::Gradient(vec![Color::Rgb(0, 0, 0), Color::Rgb(200, 100, 0)]);
+
+        assert_eq!(skin_color(&theme, segment(0, 3)), Color::Rgb(50, 25, 0));
+        assert_eq!(skin_color(&theme, segment(1, 3)), Color::Rgb(100, 50, 0));
+        assert_eq!(skin_color(&theme, segment(2, 3)), Color::Rgb(150, 75, 0));
+    }
+
+    #[test]
+    fn stripe_and_length_skins_pick_colors_by_segment_and_size() {
+        let mut theme = fallback_theme();
+        theme.snake_skin = SnakeSkin::Stripes {
+            colors: vec![Color::Red, Color::Blue],
+            width: 2,
+        };
+        let stripes = (0..6)
+            .map(|index| skin_color(&theme, segment(index, 6)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            stripes,
+            [
+                Color::Red,
+                Color::Red,
+                Color::Blue,
+                Color::Blue,
+                Color::Red,
+                Color::Red
+            ]
+        );
+
+        theme.snake_skin = SnakeSkin::Length {
+            colors: vec![Color::Rgb(0, 0, 0), Color::Rgb(100, 100, 100)],
+            length: 10,
+        };
+        assert_eq!(skin_color(&theme, segment(0, 3)), Color::Rgb(50, 50, 50));
+        assert_eq!(skin_color(&theme, segment(2, 3)), Color::Rgb(50, 50, 50));
+        assert_eq!(
+            skin_color(&theme, segment(0, 40)),
+            Color::Rgb(100, 100, 100)
+        );
+    }
+
+    #[test]
+    fn glow_still_blends_over_skin_colors() {
+        let mut theme = fallback_theme();
+        theme.snake_skin = SnakeSkin::Stripes {
+            colors: vec![Color::Rgb(10, 20, 30)],
+            width: 1,
+        };
+        let kind = CellKind::SnakeBody(segment(0, 4));
+        let glow = GlowEffect::speed_level_up();
+
+        assert_eq!(cell_color(kind, &theme, None, 0.0), Color::Rgb(10, 20, 30));
+        assert_ne!(
+            cell_color(kind, &theme, Some(&glow), 0.0),
+            Color::Rgb(10, 20, 30)
+        );
+    }
+
+    #[test]
+    fn preview_loop_is_a_closed_ring_of_neighbors() {
+        let path = preview_loop(GridSize {
+            width: 10,
+            height: 6,
+        });
+        assert_eq!(path.len(), 2 * (8 - 1) + 2 * (4 - 1));
+        for (idx, cell) in path.iter().enumerate() {
+            let next = path[(idx + 1) % path.len()];
+            assert_eq!((cell.x - next.x).abs() + (cell.y - next.y).abs(), 1);
+        }
+    }
+
+    #[test]
+    fn preview_snake_advances_one_cell_per_step() {
+        let bounds = GridSize {
+            width: 28,
+            height: 16,
+        };
+        let first = preview_state(bounds, 0);
+        let second = preview_state(bounds, 1);
+
+        assert_eq!(first.snake.len(), PREVIEW_SNAKE_LEN);
+        assert!(
+            first
+                .snake
+                .segments()
+                .all(|cell| cell.is_within_bounds(bounds))
+        );
+        assert_eq!(second.snake.segments().nth(1), Some(&first.snake.head()));
+        assert_eq!(first.foods.len(), 2);
+        assert!(first.foods.iter().any(|food| food.is_super()));
+        assert!(
+            first
+                .foods
+                .iter()
+                .all(|food| !first.snake.occupies(food.position))
+        );
+    }
+
+    #[test]
+    fn tiny_preview_board_does_not_panic() {
+        let state = preview_state(
+            GridSize {
+                width: 3,
+                height: 2,
+            },
+            5,
+        );
+        assert!(state.foods.len() <= 2);
+    }
+}
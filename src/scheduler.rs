@@ -0,0 +1,221 @@
+use std::time::{Duration, Instant};
+
+/// Time between frames while something on screen animates.
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Longest the game loop blocks with nothing to do.
+///
+/// A static screen wakes the loop at most four times a second, to check the
+/// terminal size and reload edited themes, and redraws nothing unless one of
+/// those changed.
+pub const IDLE_WAKE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Most ticks run back to back after a stall, such as a slow frame or a
+/// suspended process. Further missed ticks are dropped rather than played
+/// all at once.
+pub const MAX_CATCH_UP_TICKS: u32 = 3;
+
+/// Fixed-timestep tick clock.
+///
+/// Each deadline is the previous deadline plus the tick interval, not the
+/// time the previous tick happened to run, so frame cost and wake-up jitter
+/// do not add up to drift.
+#[derive(Debug, Clone)]
+pub struct TickScheduler {
+    last_tick: Instant,
+    dropped_ticks: u64,
+}
+
+impl TickScheduler {
+    #[must_use]
+    pub fn new(now: Instant) -> Self {
+        Self {
+            last_tick: now,
+            dropped_ticks: 0,
+        }
+    }
+
+    /// Restarts the clock so the next tick is one interval after `now`, e.g.
+    /// while the game is paused.
+    pub fn reset(&mut self, now: Instant) {
+        self.last_tick = now;
+    }
+
+    /// Returns when the next tick is due.
+    #[must_use]
+    pub fn next_tick(&self, interval: Duration) -> Instant {
+        self.last_tick + interval
+    }
+
+    /// Returns how many ticks to run now and advances the clock past them.
+    ///
+    /// At most [`MAX_CATCH_UP_TICKS`] are returned; the rest are dropped and
+    /// the clock restarts from `now`.
+    pub fn due_ticks(&mut self, now: Instant, interval: Duration) -> u32 {
+        if interval.is_zero() {
+            self.last_tick = now;
+            return 1;
+        }
+
+        let mut due = 0;
+        while self.next_tick(interval) <= now {
+            if due == MAX_CATCH_UP_TICKS {
+                let behind = now.duration_since(self.last_tick).as_nanos() / interval.as_nanos();
+                self.dropped_ticks = self
+                    .dropped_ticks
+                    .saturating_add(u64::try_from(behind).unwrap_or(u64::MAX));
+                self.last_tick = now;
+                break;
+            }
+            self.last_tick += interval;
+            due += 1;
+        }
+        due
+    }
+
+    /// Returns how many ticks were dropped after stalls.
+    #[must_use]
+    pub fn dropped_ticks(&self) -> u64 {
+        self.dropped_ticks
+    }
+}
+
+/// Returns when the game loop should wake up if no input arrives first: at
+/// the next tick or animation frame, and no later than
+/// [`IDLE_WAKE_INTERVAL`] from `now`.
+#[must_use]
+pub fn wake_deadline(
+    now: Instant,
+    next_tick: Option<Instant>,
+    next_frame: Option<Instant>,
+) -> Instant {
+    [next_tick, next_frame]
+        .into_iter()
+        .flatten()
+        .fold(now + IDLE_WAKE_INTERVAL, Instant::min)
+}
+
+/// Returns when the game loop should next wake up with no input.
+///
+/// `tick_interval` is set while the game is playing and `last_frame` while
+/// something on screen animates; the next animation frame is due one
+/// [`FRAME_INTERVAL`] after it. Both the binary and
+/// [`crate::controller::GameController`] schedule their wake-ups here.
+#[must_use]
+pub fn loop_wake_deadline(
+    now: Instant,
+    scheduler: &TickScheduler,
+    tick_interval: Option<Duration>,
+    last_frame: Option<Instant>,
+) -> Instant {
+    let next_tick = tick_interval.map(|interval| scheduler.next_tick(interval));
+    let next_frame = last_frame.map(|frame| frame + FRAME_INTERVAL);
+    wake_deadline(now, next_tick, next_frame)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{
+        FRAME_INTERVAL, IDLE_WAKE_INTERVAL, MAX_CATCH_UP_TICKS, TickScheduler, loop_wake_deadline,
+        wake_deadline,
+    };
+
+    const INTERVAL: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn deadlines_advance_by_the_interval_without_drift() {
+        let start = Instant::now();
+        let mut scheduler = TickScheduler::new(start);
+
+        assert_eq!(scheduler.due_ticks(start + INTERVAL / 2, INTERVAL), 0);
+        // Waking late does not push later deadlines back.
+        assert_eq!(
+            scheduler.due_ticks(start + INTERVAL + INTERVAL / 3, INTERVAL),
+            1
+        );
+        assert_eq!(scheduler.next_tick(INTERVAL), start + INTERVAL * 2);
+        assert_eq!(scheduler.due_ticks(start + INTERVAL * 2, INTERVAL), 1);
+        assert_eq!(scheduler.next_tick(INTERVAL), start + INTERVAL * 3);
+    }
+
+    #[test]
+    fn stalls_catch_up_a_few_ticks_and_drop_the_rest() {
+        let start = Instant::now();
+        let mut scheduler = TickScheduler::new(start);
+
+        assert_eq!(scheduler.due_ticks(start + INTERVAL * 2, INTERVAL), 2);
+        assert_eq!(scheduler.dropped_ticks(), 0);
+
+        let stalled = start + INTERVAL * 12;
+        assert_eq!(scheduler.due_ticks(stalled, INTERVAL), MAX_CATCH_UP_TICKS);
+        assert_eq!(scheduler.dropped_ticks(), 7);
+        assert_eq!(scheduler.next_tick(INTERVAL), stalled + INTERVAL);
+    }
+
+    #[test]
+    fn reset_restarts_the_clock_after_a_pause() {
+        let start = Instant::now();
+        let mut scheduler = TickScheduler::new(start);
+
+        let resumed = start + INTERVAL * 30;
+        scheduler.reset(resumed);
+        assert_eq!(scheduler.due_ticks(resumed, INTERVAL), 0);
+        assert_eq!(scheduler.next_tick(INTERVAL), resumed + INTERVAL);
+        assert_eq!(scheduler.dropped_ticks(), 0);
+    }
+
+    #[test]
+    fn wake_deadline_is_the_earliest_of_tick_frame_and_idle_wake() {
+        let now = Instant::now();
+        assert_eq!(wake_deadline(now, None, None), now + IDLE_WAKE_INTERVAL);
+
+        let tick = now + INTERVAL;
+        let frame = now + Duration::from_millis(16);
+        assert_eq!(wake_deadline(now, Some(tick), None), tick);
+        assert_eq!(wake_deadline(now, Some(tick), Some(frame)), frame);
+        assert_eq!(
+            wake_deadline(now, Some(now + Duration::from_secs(5)), None),
+            now + IDLE_WAKE_INTERVAL
+        );
+    }
+
+    #[test]
+    fn idle_loop_wakes_at_most_four_times_a_second() {
+        let start = Instant::now();
+        let scheduler = TickScheduler::new(start);
+
+        let mut now = start;
+        let mut wakes = 0;
+        loop {
+            now = loop_wake_deadline(now, &scheduler, None, None);
+            if now > start + Duration::from_secs(1) {
+                break;
+            }
+            wakes += 1;
+        }
+        assert_eq!(wakes, 4);
+    }
+
+    #[test]
+    fn loop_wakes_for_the_next_tick_or_animation_frame() {
+        let start = Instant::now();
+        let scheduler = TickScheduler::new(start);
+
+        assert_eq!(
+            loop_wake_deadline(start, &scheduler, Some(INTERVAL), None),
+            start + INTERVAL
+        );
+        assert_eq!(
+            loop_wake_deadline(start, &scheduler, Some(INTERVAL), Some(start)),
+            start + FRAME_INTERVAL
+        );
+        // A late frame is redrawn straight away rather than skipped.
+        let late = start + INTERVAL / 2;
+        assert_eq!(
+            loop_wake_deadline(late, &scheduler, None, Some(start)),
+            start + FRAME_INTERVAL
+        );
+    }
+}
@@ -0,0 +1,593 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::background::BackgroundChoice;
+use crate::config::{GlyphMode, MAX_START_SPEED_LEVEL, MIN_START_SPEED_LEVEL};
+use crate::graphics::GraphicsMode;
+use crate::keymap::{Action, Key, KeyBindings, KeyPreset};
+use crate::platform::ColorDepth;
+use crate::theme::AppearancePreference;
+
+const APP_DIR_NAME: &str = "terminal-snake";
+const CONFIG_FILE_NAME: &str = "config.json";
+
+/// Prefix of the environment variables that override config file settings,
+/// e.g. `TERMINAL_SNAKE_SPEED`.
+const ENV_PREFIX: &str = "TERMINAL_SNAKE_";
+
+/// Settings after layering CLI flags, environment, config file and defaults.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    pub speed: u32,
+    /// Theme id to select at startup; `None` keeps the catalog default.
+    pub theme: Option<String>,
+    pub appearance: AppearancePreference,
+    pub background: BackgroundChoice,
+    pub border: bool,
+    pub shapes: bool,
+    /// Whether clicking the play field turns the snake toward the click.
+    pub mouse_steering: bool,
+    /// Glyph mode; `None` detects it from the terminal.
+    pub glyphs: Option<GlyphMode>,
+    /// Pixel renderer; `None` leaves it off.
+    pub graphics: Option<GraphicsMode>,
+    /// Color depth; `None` detects it from the terminal.
+    pub colors: Option<ColorDepth>,
+    pub debug: bool,
+    pub key_preset: KeyPreset,
+    /// The preset's bindings with any per-action overrides applied.
+    pub keys: KeyBindings,
+}
+
+/// One source of settings. Unset fields fall through to the next layer.
+///
+/// This is also the shape of `config.json`, where every key is optional.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SettingsLayer {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "named")]
+    pub appearance: Option<AppearancePreference>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "named")]
+    pub background: Option<BackgroundChoice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub border: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shapes: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mouse_steering: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "named")]
+    pub glyphs: Option<GlyphMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "named")]
+    pub graphics: Option<GraphicsMode>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "named")]
+    pub colors: Option<ColorDepth>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub debug: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "named")]
+    pub key_preset: Option<KeyPreset>,
+    /// Keys per action, replacing the preset's keys for that action.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keys: Option<BTreeMap<Action, Vec<Key>>>,
+}
+
+impl SettingsLayer {
+    /// Reads `TERMINAL_SNAKE_*` variables through `env`.
+    ///
+    /// Values that do not parse are skipped and described in the returned
+    /// warnings.
+    pub fn from_env(env: impl Fn(&str) -> Option<String>) -> (Self, Vec<String>) {
+        let mut warnings = Vec::new();
+        let read = |key: &str| -> Option<String> {
+            env(&format!("{ENV_PREFIX}{key}")).filter(|value| !value.is_empty())
+        };
+        let layer = Self {
+            speed: parse_env("SPEED", read("SPEED"), &mut warnings),
+            theme: read("THEME"),
+            appearance: parse_env("APPEARANCE", read("APPEARANCE"), &mut warnings),
+            background: parse_env("BACKGROUND", read("BACKGROUND"), &mut warnings),
+            border: parse_env_flag("BORDER", read("BORDER"), &mut warnings),
+            shapes: parse_env_flag("SHAPES", read("SHAPES"), &mut warnings),
+            mouse_steering: parse_env_flag("MOUSE_STEERING", read("MOUSE_STEERING"), &mut warnings),
+            glyphs: parse_env("GLYPHS", read("GLYPHS"), &mut warnings),
+            graphics: parse_env("GRAPHICS", read("GRAPHICS"), &mut warnings),
+            colors: parse_env("COLORS", read("COLORS"), &mut warnings),
+            debug: parse_env_flag("DEBUG", read("DEBUG"), &mut warnings),
+            key_preset: parse_env("KEY_PRESET", read("KEY_PRESET"), &mut warnings),
+            keys: None,
+        };
+        (layer, warnings)
+    }
+
+    /// Returns this layer with unset fields taken from `fallback`.
+    #[must_use]
+    pub fn or(self, fallback: Self) -> Self {
+        Self {
+            speed: self.speed.or(fallback.speed),
+            theme: self.theme.or(fallback.theme),
+            appearance: self.appearance.or(fallback.appearance),
+            background: self.background.or(fallback.background),
+            border: self.border.or(fallback.border),
+            shapes: self.shapes.or(fallback.shapes),
+            mouse_steering: self.mouse_steering.or(fallback.mouse_steering),
+            glyphs: self.glyphs.or(fallback.glyphs),
+            graphics: self.graphics.or(fallback.graphics),
+            colors: self.colors.or(fallback.colors),
+            debug: self.debug.or(fallback.debug),
+            key_preset: self.key_preset.or(fallback.key_preset),
+            keys: self.keys.or(fallback.keys),
+        }
+    }
+
+    /// Fills unset fields with defaults.
+    #[must_use]
+    pub fn resolve(self) -> Settings {
+        let key_preset = self.key_preset.unwrap_or_default();
+        Settings {
+            speed: self
+                .speed
+                .unwrap_or(MIN_START_SPEED_LEVEL)
+                .clamp(MIN_START_SPEED_LEVEL, MAX_START_SPEED_LEVEL),
+            theme: self.theme,
+            appearance: self.appearance.unwrap_or_default(),
+            background: self.background.unwrap_or_default(),
+            border: self.border.unwrap_or(true),
+            shapes: self.shapes.unwrap_or(false),
+            mouse_steering: self.mouse_steering.unwrap_or(false),
+            glyphs: self.glyphs,
+            graphics: self.graphics,
+            colors: self.colors,
+            debug: self.debug.unwrap_or(false),
+            key_preset,
+            keys: KeyBindings::preset(key_preset).with_overrides(&self.keys.unwrap_or_default()),
+        }
+    }
+}
+
+fn parse_env<T: FromStr<Err: Display>>(
+    key: &str,
+    value: Option<String>,
+    warnings: &mut Vec<String>,
+) -> Option<T> {
+    let value = value?;
+    match value.parse() {
+        Ok(parsed) => Some(parsed),
+        Err(error) => {
+            warnings.push(format!("ignoring {ENV_PREFIX}{key}: {error}"));
+            None
+        }
+    }
+}
+
+fn parse_env_flag(key: &str, value: Option<String>, warnings: &mut Vec<String>) -> Option<bool> {
+    let value = value?;
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Some(true),
+        "0" | "false" | "no" | "off" => Some(false),
+        _ => {
+            warnings.push(format!(
+                "ignoring {ENV_PREFIX}{key}: '{value}' is not on or off"
+            ));
+            None
+        }
+    }
+}
+
+/// A setting stored in the config file by name.
+trait SettingName: FromStr<Err = String> + Copy {
+    /// Name written to the config file; parses back to the same value.
+    fn setting_name(self) -> &'static str;
+}
+
+impl SettingName for AppearancePreference {
+    fn setting_name(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Dark => "dark",
+            Self::Light => "light",
+        }
+    }
+}
+
+impl SettingName for BackgroundChoice {
+    fn setting_name(self) -> &'static str {
+        match self {
+            Self::Theme => "theme",
+            Self::Off => "off",
+            Self::Checkerboard => "checkerboard",
+            Self::HorizontalStripes => "hstripes",
+            Self::VerticalStripes => "vstripes",
+            Self::DotGrid => "dots",
+            Self::Vignette => "vignette",
+            Self::Gradient => "gradient",
+        }
+    }
+}
+
+impl SettingName for KeyPreset {
+    fn setting_name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Vim => "vim",
+            Self::Azerty => "azerty",
+            Self::LeftHanded => "left-handed",
+        }
+    }
+}
+
+impl SettingName for GlyphMode {
+    fn setting_name(self) -> &'static str {
+        match self {
+            Self::Unicode => "unicode",
+            Self::Ascii => "ascii",
+            Self::Braille => "braille",
+            Self::Square => "square",
+            Self::AsciiSquare => "ascii-square",
+        }
+    }
+}
+
+impl SettingName for GraphicsMode {
+    fn setting_name(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Auto => "auto",
+            Self::Kitty => "kitty",
+            Self::Sixel => "sixel",
+        }
+    }
+}
+
+impl SettingName for ColorDepth {
+    fn setting_name(self) -> &'static str {
+        match self {
+            Self::TrueColor => "truecolor",
+            Self::Ansi256 => "256",
+            Self::Ansi16 => "16",
+            Self::Monochrome => "none",
+        }
+    }
+}
+
+/// Serde adapter storing optional settings under their [`SettingName`].
+mod named {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::SettingName;
+
+    pub fn serialize<S: Serializer, T: SettingName>(
+        value: &Option<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(value) => serializer.serialize_str(value.setting_name()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: SettingName>(
+        deserializer: D,
+    ) -> Result<Option<T>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|name| name.parse().map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+/// The config file layer as edited from the settings menu.
+///
+/// Edits are kept in memory until [`SettingsFile::save_if_dirty`]. A file
+/// that failed to load is never written back, so a typo in it is not
+/// silently replaced by defaults.
+#[derive(Debug, Clone, Default)]
+pub struct SettingsFile {
+    layer: SettingsLayer,
+    writable: bool,
+    dirty: bool,
+}
+
+impl SettingsFile {
+    /// Loads the config file, returning a read-only empty layer and the
+    /// error when it cannot be read or parsed.
+    pub fn open() -> (Self, Option<io::Error>) {
+        match load_settings_file() {
+            Ok(layer) => (
+                Self {
+                    layer,
+                    writable: true,
+                    dirty: false,
+                },
+                None,
+            ),
+            Err(error) => (Self::default(), Some(error)),
+        }
+    }
+
+    #[must_use]
+    pub fn layer(&self) -> &SettingsLayer {
+        &self.layer
+    }
+
+    /// Applies `edit` to the layer, marking it unsaved if anything changed.
+    pub fn update(&mut self, edit: impl FnOnce(&mut SettingsLayer)) {
+        let before = self.layer.clone();
+        edit(&mut self.layer);
+        self.dirty |= self.layer != before;
+    }
+
+    /// Writes unsaved edits to the config file.
+    pub fn save_if_dirty(&mut self) -> io::Result<()> {
+        if !self.dirty || !self.writable {
+            return Ok(());
+        }
+        self.dirty = false;
+        save_settings_file(&self.layer)
+    }
+}
+
+/// Returns the platform-correct config file path.
+#[must_use]
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_DIR_NAME).join(CONFIG_FILE_NAME))
+}
+
+/// Loads the config file layer.
+///
+/// Returns an empty layer when the file does not exist yet, and `Err` when
+/// it exists but cannot be read or parsed.
+pub fn load_settings_file() -> io::Result<SettingsLayer> {
+    match config_path() {
+        Some(path) => load_settings_file_from_path(&path),
+        None => Ok(SettingsLayer::default()),
+    }
+}
+
+/// Writes the config file layer, creating the config directory if needed.
+pub fn save_settings_file(layer: &SettingsLayer) -> io::Result<()> {
+    let path = config_path().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, "no config directory is available")
+    })?;
+    write_settings_file_to_path(&path, layer)
+}
+
+fn load_settings_file_from_path(path: &Path) -> io::Result<SettingsLayer> {
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(SettingsLayer::default()),
+        Err(e) => return Err(e),
+    };
+
+    serde_json::from_str::<SettingsLayer>(&raw)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn write_settings_file_to_path(path: &Path, layer: &SettingsLayer) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let json = serde_json::to_string_pretty(layer)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+    fs::write(path, json + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::{
+        Settings, SettingsFile, SettingsLayer, load_settings_file_from_path,
+        write_settings_file_to_path,
+    };
+    use crate::background::BackgroundChoice;
+    use crate::config::{GlyphMode, MAX_START_SPEED_LEVEL};
+    use crate::graphics::GraphicsMode;
+    use crate::keymap::{Action, Key, KeyBindings, KeyPreset};
+    use crate::platform::ColorDepth;
+    use crate::theme::AppearancePreference;
+
+    fn env_layer(vars: &[(&str, &str)]) -> (SettingsLayer, Vec<String>) {
+        let vars = vars
+            .iter()
+            .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+            .collect::<HashMap<_, _>>();
+        SettingsLayer::from_env(|key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn defaults_apply_when_no_layer_sets_a_value() {
+        assert_eq!(
+            SettingsLayer::default().resolve(),
+            Settings {
+                speed: 1,
+                theme: None,
+                appearance: AppearancePreference::Auto,
+                background: BackgroundChoice::Theme,
+                border: true,
+                shapes: false,
+                mouse_steering: false,
+                glyphs: None,
+                graphics: None,
+                colors: None,
+                debug: false,
+                key_preset: KeyPreset::Default,
+                keys: KeyBindings::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn cli_beats_environment_which_beats_file() {
+        let cli = SettingsLayer {
+            speed: Some(7),
+            ..SettingsLayer::default()
+        };
+        let (env, warnings) = env_layer(&[
+            ("TERMINAL_SNAKE_SPEED", "5"),
+            ("TERMINAL_SNAKE_BORDER", "off"),
+        ]);
+        assert!(warnings.is_empty());
+        let file = SettingsLayer {
+            speed: Some(3),
+            border: Some(true),
+            shapes: Some(true),
+            theme: Some("nord".to_owned()),
+            ..SettingsLayer::default()
+        };
+
+        let settings = cli.or(env).or(file).resolve();
+        assert_eq!(settings.speed, 7);
+        assert!(!settings.border);
+        assert!(settings.shapes);
+        assert_eq!(settings.theme.as_deref(), Some("nord"));
+    }
+
+    #[test]
+    fn environment_values_parse_and_bad_ones_warn() {
+        let (layer, warnings) = env_layer(&[
+            ("TERMINAL_SNAKE_GLYPHS", "braille"),
+            ("TERMINAL_SNAKE_GRAPHICS", "sixel"),
+            ("TERMINAL_SNAKE_COLORS", "256"),
+            ("TERMINAL_SNAKE_SHAPES", "yes"),
+            ("TERMINAL_SNAKE_DEBUG", "maybe"),
+            ("TERMINAL_SNAKE_BACKGROUND", "moire"),
+            ("TERMINAL_SNAKE_THEME", ""),
+        ]);
+
+        assert_eq!(layer.glyphs, Some(GlyphMode::Braille));
+        assert_eq!(layer.graphics, Some(GraphicsMode::Sixel));
+        assert_eq!(layer.colors, Some(ColorDepth::Ansi256));
+        assert_eq!(layer.shapes, Some(true));
+        assert_eq!(layer.debug, None);
+        assert_eq!(layer.background, None);
+        assert_eq!(layer.theme, None);
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].starts_with("ignoring TERMINAL_SNAKE_BACKGROUND"));
+        assert!(warnings[1].starts_with("ignoring TERMINAL_SNAKE_DEBUG"));
+    }
+
+    #[test]
+    fn key_overrides_apply_on_top_of_the_preset() {
+        let layer = SettingsLayer {
+            key_preset: Some(KeyPreset::Vim),
+            keys: Some(BTreeMap::from([(Action::Quit, vec![Key::Char('x')])])),
+            ..SettingsLayer::default()
+        };
+
+        let keys = layer.resolve().keys;
+        assert_eq!(keys.action_for(Key::Char('k')), Some(Action::Up));
+        assert_eq!(keys.action_for(Key::Char('x')), Some(Action::Quit));
+        assert_eq!(keys.action_for(Key::Char('q')), None);
+    }
+
+    #[test]
+    fn speed_is_clamped_to_the_selectable_range() {
+        let layer = SettingsLayer {
+            speed: Some(99),
+            ..SettingsLayer::default()
+        };
+        assert_eq!(layer.resolve().speed, MAX_START_SPEED_LEVEL);
+    }
+
+    #[test]
+    fn config_file_round_trips_and_omits_unset_keys() {
+        let path = unique_test_path("round_trip");
+        let layer = SettingsLayer {
+            speed: Some(4),
+            theme: Some("ember".to_owned()),
+            appearance: Some(AppearancePreference::Light),
+            background: Some(BackgroundChoice::DotGrid),
+            border: Some(false),
+            glyphs: Some(GlyphMode::Square),
+            graphics: Some(GraphicsMode::Kitty),
+            colors: Some(ColorDepth::Monochrome),
+            key_preset: Some(KeyPreset::LeftHanded),
+            keys: Some(BTreeMap::from([(
+                Action::Pause,
+                vec![Key::Space, Key::F(2)],
+            )])),
+            ..SettingsLayer::default()
+        };
+
+        write_settings_file_to_path(&path, &layer).expect("settings save should succeed");
+        let raw = fs::read_to_string(&path).expect("settings file should exist");
+        assert!(raw.contains("\"background\": \"dots\""));
+        assert!(raw.contains("\"colors\": \"none\""));
+        assert!(raw.contains("\"key_preset\": \"left-handed\""));
+        assert!(raw.contains("\"pause\": ["));
+        assert!(!raw.contains("shapes"));
+        let loaded = load_settings_file_from_path(&path).expect("load should succeed");
+        assert_eq!(loaded, layer);
+
+        cleanup_test_path(&path);
+    }
+
+    #[test]
+    fn missing_file_is_empty_and_invalid_file_is_an_error() {
+        let path = unique_test_path("invalid");
+        assert_eq!(
+            load_settings_file_from_path(&path).expect("missing file should load"),
+            SettingsLayer::default()
+        );
+
+        for raw in [
+            r#"{ "spead": 3 }"#,
+            r#"{ "glyphs": "emoji" }"#,
+            r#"{ "keys": { "jump": ["j"] } }"#,
+            r#"{ "keys": { "up": ["ctrl"] } }"#,
+            "not-json",
+        ] {
+            fs::create_dir_all(path.parent().expect("test path should have a parent"))
+                .expect("test directory should be creatable");
+            fs::write(&path, raw).expect("test file write should succeed");
+            assert!(
+                load_settings_file_from_path(&path).is_err(),
+                "{raw} should not load"
+            );
+        }
+
+        cleanup_test_path(&path);
+    }
+
+    #[test]
+    fn settings_file_tracks_only_real_edits() {
+        let mut file = SettingsFile::default();
+        file.update(|layer| layer.border = None);
+        assert!(!file.dirty);
+
+        file.update(|layer| layer.border = Some(false));
+        assert!(file.dirty);
+        assert_eq!(file.layer().border, Some(false));
+    }
+
+    fn unique_test_path(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after epoch")
+            .as_nanos();
+
+        std::env::temp_dir()
+            .join("snake-settings-tests")
+            .join(format!("{label}-{nanos}"))
+            .join("config.json")
+    }
+
+    fn cleanup_test_path(path: &Path) {
+        if let Some(parent) = path.parent() {
+            let _ = fs::remove_dir_all(parent);
+        }
+    }
+}
@@ -4,6 +4,7 @@ use thiserror::Error;
 
 use crate::config::GridSize;
 use crate::input::Direction;
+use crate::occupancy::OccupancyGrid;
 
 /// Grid position in logical cell coordinates.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
@@ -49,6 +50,10 @@ pub struct Snake {
     buffered_direction: Direction,
     next_buffered_direction: Option<Direction>,
     grow_remaining: u32,
+    /// Segments per cell, sized to the board on the first move. Segments
+    /// off that board are counted in `off_grid` and found by scanning.
+    occupancy: OccupancyGrid,
+    off_grid: usize,
 }
 
 /// Construction errors for [`Snake`].
@@ -67,13 +72,7 @@ impl Snake {
         body.push_front(start);
         body.push_back(initial_tail_segment(start, direction));
 
-        Self {
-            body,
-            direction,
-            buffered_direction: direction,
-            next_buffered_direction: None,
-            grow_remaining: 0,
-        }
+        Self::with_body(body, direction)
     }
 
     /// Creates a snake from explicit body segments (front is head).
@@ -87,13 +86,20 @@ impl Snake {
             return Err(SnakeBuildError::EmptySegments);
         }
 
-        Ok(Self {
-            body: VecDeque::from(segments),
+        Ok(Self::with_body(VecDeque::from(segments), direction))
+    }
+
+    fn with_body(body: VecDeque<Position>, direction: Direction) -> Self {
+        let off_grid = body.len();
+        Self {
+            body,
             direction,
             buffered_direction: direction,
             next_buffered_direction: None,
             grow_remaining: 0,
-        })
+            occupancy: OccupancyGrid::default(),
+            off_grid,
+        }
     }
 
     /// Queues growth of one segment on the next movement tick.
@@ -109,6 +115,7 @@ impl Snake {
     /// Applies one buffered movement step.
     pub fn move_forward(&mut self, bounds: GridSize) {
         debug_assert!(bounds.width > 0 && bounds.height > 0);
+        self.fit_to_bounds(bounds);
 
         self.direction = self.buffered_direction;
         let next_head = self.next_head_position();
@@ -118,10 +125,42 @@ impl Snake {
         }
 
         self.body.push_front(next_head);
+        self.mark(next_head);
         if self.grow_remaining > 0 {
             self.grow_remaining -= 1;
-        } else {
-            let _ = self.body.pop_back();
+        } else if let Some(tail) = self.body.pop_back() {
+            self.unmark(tail);
+        }
+    }
+
+    /// Sizes the occupancy grid to `bounds`, rebuilding it only when the
+    /// board changed.
+    pub fn fit_to_bounds(&mut self, bounds: GridSize) {
+        if self.occupancy.bounds() == bounds {
+            return;
+        }
+        self.occupancy = OccupancyGrid::new(bounds);
+        self.off_grid = 0;
+        for index in 0..self.body.len() {
+            self.mark(self.body[index]);
+        }
+    }
+
+    /// Returns the occupancy grid, covering the board of the last move.
+    #[must_use]
+    pub fn occupancy(&self) -> &OccupancyGrid {
+        &self.occupancy
+    }
+
+    fn mark(&mut self, position: Position) {
+        if !self.occupancy.insert(position) {
+            self.off_grid += 1;
+        }
+    }
+
+    fn unmark(&mut self, position: Position) {
+        if !self.occupancy.remove(position) {
+            self.off_grid -= 1;
         }
     }
 
@@ -181,17 +220,34 @@ impl Snake {
             .expect("snake body must always contain at least one segment")
     }
 
+    /// Returns the current tail position.
+    #[must_use]
+    pub fn tail(&self) -> Position {
+        *self
+            .body
+            .back()
+            .expect("snake body must always contain at least one segment")
+    }
+
     /// Returns true if any segment occupies `position`.
     #[must_use]
     pub fn occupies(&self, position: Position) -> bool {
-        self.body.contains(&position)
+        if self.occupancy.contains(position) {
+            self.occupancy.is_occupied(position)
+        } else {
+            self.off_grid > 0 && self.body.contains(&position)
+        }
     }
 
     /// Returns true if the head overlaps any non-head segment.
     #[must_use]
     pub fn head_overlaps_body(&self) -> bool {
         let head = self.head();
-        self.body.iter().skip(1).any(|segment| *segment == head)
+        if self.occupancy.contains(head) {
+            self.occupancy.count(head) > 1
+        } else {
+            self.body.iter().skip(1).any(|segment| *segment == head)
+        }
     }
 
     /// Returns current segment count.
@@ -222,6 +278,10 @@ impl Snake {
         for segment in &mut self.body {
             *segment = segment.wrapped(bounds);
         }
+        // Segments moved, so the grid is rebuilt even if the board did not
+        // change.
+        self.occupancy = OccupancyGrid::default();
+        self.fit_to_bounds(bounds);
     }
 }
 
@@ -383,6 +443,42 @@ mod tests {
         assert!(snake.next_buffered_direction.is_none());
     }
 
+    #[test]
+    fn occupancy_follows_moves_growth_and_self_collision() {
+        let bounds = GridSize {
+            width: 8,
+            height: 8,
+        };
+        let mut snake = Snake::new(Position { x: 2, y: 2 }, Direction::Right);
+        assert!(snake.occupies(Position { x: 1, y: 2 }));
+
+        snake.grow_by(3);
+        for direction in [Direction::Right, Direction::Down, Direction::Left] {
+            snake.buffer_direction(direction);
+            snake.move_forward(bounds);
+        }
+        assert_eq!(snake.occupancy().free_len(), 64 - snake.len());
+        for y in 0..8 {
+            for x in 0..8 {
+                let cell = Position { x, y };
+                assert_eq!(snake.occupies(cell), snake.segments().any(|s| *s == cell));
+            }
+        }
+        assert!(!snake.head_overlaps_body());
+
+        // Turning up runs the five-segment snake into its own body.
+        snake.buffer_direction(Direction::Up);
+        snake.move_forward(bounds);
+        assert!(snake.head_overlaps_body());
+
+        snake.wrap_into_bounds(GridSize {
+            width: 3,
+            height: 3,
+        });
+        assert_eq!(snake.occupancy().bounds().width, 3);
+        assert!(snake.segments().all(|segment| snake.occupies(*segment)));
+    }
+
     #[test]
     fn from_segments_rejects_empty_segments() {
         let result = Snake::from_segments(Vec::new(), Direction::Right);
@@ -0,0 +1,472 @@
+//! Headless render snapshots for catching layout regressions.
+//!
+//! Each [`Scene`] draws a fixed game state and theme through
+//! [`renderer::render`] on a `TestBackend` at several terminal sizes. The
+//! text form of every frame, cell symbols plus a style map, is checked in
+//! under `tests/golden/render/` and compared by the `render_snapshots` test.
+//! After an intended change to the screens, regenerate them with
+//! `cargo run --features snapshots --bin snapshots -- update` and review the
+//! diff. The module is only built with the `snapshots` feature.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+use unicode_width::UnicodeWidthStr;
+
+use crate::background::BackgroundChoice;
+use crate::config::{GridSize, Theme, fallback_theme, glyphs};
+use crate::food::Food;
+use crate::game::{DeathReason, GameState, GameStatus};
+use crate::input::Direction;
+use crate::keymap::{KeyBindings, KeyPreset};
+use crate::renderer::{self, FrameLayout, MenuUiState, RenderCache};
+use crate::snake::{Position, Snake};
+use crate::theme::{Appearance, AppearancePreference, ThemeCatalog, ThemeItem};
+use crate::ui::hud::{HudInfo, HudValueFlash};
+use crate::ui::menu::ThemeSelectView;
+
+/// Directory the golden snapshots are checked in to.
+pub const SNAPSHOT_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/render");
+
+const SNAPSHOT_EXTENSION: &str = "snap";
+
+/// Smallest board, in cells per side, the game lets the player start on.
+const MIN_GAME_AREA_CELLS: u16 = 30;
+
+/// High score shown in the HUD and compared against on the game-over screen.
+const HIGH_SCORE: u32 = 250;
+
+/// Keys of the style legend, in order of first use.
+const STYLE_KEYS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Screen a scene shows, over the board state that goes with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Screen {
+    Start,
+    StartSettings,
+    Playing,
+    Paused,
+    PauseThemeSelect,
+    GameOver,
+    Victory,
+}
+
+/// One screen drawn with one theme at a set of terminal sizes.
+#[derive(Debug, Clone, Copy)]
+pub struct Scene {
+    pub name: &'static str,
+    screen: Screen,
+    /// Bundled theme id, or `None` for the fallback theme.
+    theme: Option<&'static str>,
+    /// Terminal sizes as (columns, rows).
+    pub sizes: &'static [(u16, u16)],
+}
+
+/// Every snapshot scene. The start screen sizes step through the title
+/// fallbacks: plain text, block-font "snake" only, and the full block title.
+pub const SCENES: &[Scene] = &[
+    Scene {
+        name: "start",
+        screen: Screen::Start,
+        theme: None,
+        sizes: &[(32, 16), (48, 20), (64, 24), (80, 24), (100, 30), (140, 44)],
+    },
+    Scene {
+        name: "start_settings",
+        screen: Screen::StartSettings,
+        theme: Some("ember"),
+        sizes: &[(48, 24), (100, 30)],
+    },
+    Scene {
+        name: "playing",
+        screen: Screen::Playing,
+        theme: None,
+        sizes: &[(40, 16), (80, 24)],
+    },
+    Scene {
+        name: "playing_ember",
+        screen: Screen::Playing,
+        theme: Some("ember"),
+        sizes: &[(80, 24)],
+    },
+    Scene {
+        name: "paused",
+        screen: Screen::Paused,
+        theme: Some("high-contrast"),
+        sizes: &[(32, 16), (80, 24)],
+    },
+    Scene {
+        name: "pause_theme_select",
+        screen: Screen::PauseThemeSelect,
+        theme: Some("ember"),
+        sizes: &[(80, 24), (120, 36)],
+    },
+    Scene {
+        name: "game_over",
+        screen: Screen::GameOver,
+        theme: None,
+        sizes: &[(40, 20), (60, 36), (100, 40)],
+    },
+    Scene {
+        name: "victory",
+        screen: Screen::Victory,
+        theme: Some("ember"),
+        sizes: &[(80, 30)],
+    },
+];
+
+/// Rendered text of one scene at one size.
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// File stem, e.g. `start_80x24`.
+    pub name: String,
+    pub contents: String,
+}
+
+/// How a rendered snapshot compares with its golden file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotStatus {
+    Matches,
+    Missing,
+    /// Differs from the golden file, first at this 1-based line.
+    Differs {
+        line: usize,
+    },
+}
+
+impl Snapshot {
+    /// Returns the golden file for this snapshot.
+    #[must_use]
+    pub fn path(&self) -> PathBuf {
+        Path::new(SNAPSHOT_DIR).join(format!("{}.{SNAPSHOT_EXTENSION}", self.name))
+    }
+
+    /// Compares the snapshot with its golden file.
+    pub fn compare(&self) -> io::Result<SnapshotStatus> {
+        let golden = match fs::read_to_string(self.path()) {
+            Ok(golden) => golden,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                return Ok(SnapshotStatus::Missing);
+            }
+            Err(error) => return Err(error),
+        };
+        if golden == self.contents {
+            return Ok(SnapshotStatus::Matches);
+        }
+        let line = golden
+            .lines()
+            .zip(self.contents.lines())
+            .position(|(expected, actual)| expected != actual)
+            .unwrap_or_else(|| golden.lines().count().min(self.contents.lines().count()));
+        Ok(SnapshotStatus::Differs { line: line + 1 })
+    }
+}
+
+/// Renders every scene at every size.
+#[must_use]
+pub fn render_all() -> Vec<Snapshot> {
+    let catalog = ThemeCatalog::builtin(Appearance::Dark);
+    SCENES
+        .iter()
+        .flat_map(|scene| {
+            let catalog = &catalog;
+            scene.sizes.iter().map(move |&(width, height)| Snapshot {
+                name: format!("{}_{width}x{height}", scene.name),
+                contents: render_snapshot(scene, catalog, width, height),
+            })
+        })
+        .collect()
+}
+
+/// Returns golden files in [`SNAPSHOT_DIR`] that no scene renders any more.
+pub fn stale_files(snapshots: &[Snapshot]) -> io::Result<Vec<PathBuf>> {
+    let current: HashSet<PathBuf> = snapshots.iter().map(Snapshot::path).collect();
+    let entries = match fs::read_dir(SNAPSHOT_DIR) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let mut stale = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path
+            .extension()
+            .is_some_and(|ext| ext == SNAPSHOT_EXTENSION)
+            && !current.contains(&path)
+        {
+            stale.push(path);
+        }
+    }
+    stale.sort();
+    Ok(stale)
+}
+
+fn render_snapshot(scene: &Scene, catalog: &ThemeCatalog, width: u16, height: u16) -> String {
+    let theme_idx = scene.theme.map(|id| {
+        (0..catalog.len())
+            .find(|&idx| catalog.id_at(idx) == Some(id))
+            .unwrap_or_else(|| panic!("bundled theme '{id}' should exist"))
+    });
+    let theme = theme_idx
+        .and_then(|idx| catalog.theme_at(idx))
+        .cloned()
+        .unwrap_or_else(fallback_theme);
+    let theme_select = (scene.screen == Screen::PauseThemeSelect)
+        .then(|| (theme_idx.unwrap_or(0), catalog.items()));
+
+    let mut terminal =
+        Terminal::new(TestBackend::new(width, height)).expect("test backend should not fail");
+    let probe = GameState::new_with_seed(GridSize::default(), 0);
+    let gameplay_area =
+        draw(&mut terminal, &probe, scene.screen, &theme, None, false).gameplay_area;
+    let bounds = glyphs()
+        .mode
+        .grid_size_for_viewport(gameplay_area.width, gameplay_area.height);
+    let too_small = bounds.width < MIN_GAME_AREA_CELLS || bounds.height < MIN_GAME_AREA_CELLS;
+    let state = scene_state(scene.screen, bounds);
+    draw(
+        &mut terminal,
+        &state,
+        scene.screen,
+        &theme,
+        theme_select,
+        too_small,
+    );
+
+    let mut contents = format!(
+        "scene: {}\ntheme: {}\nsize: {width}x{height}\nglyphs: {:?}\n\n",
+        scene.name,
+        scene.theme.unwrap_or("fallback"),
+        glyphs().mode,
+    );
+    contents.push_str(&format_buffer(terminal.backend().buffer()));
+    mask_version(&contents)
+}
+
+fn draw(
+    terminal: &mut Terminal<TestBackend>,
+    state: &GameState,
+    screen: Screen,
+    theme: &Theme,
+    theme_select: Option<(usize, &[ThemeItem])>,
+    play_area_too_small: bool,
+) -> FrameLayout {
+    let bindings = KeyBindings::default();
+    let mut layout = FrameLayout::default();
+    terminal
+        .draw(|frame| {
+            layout = renderer::render(
+                frame,
+                state,
+                HudInfo {
+                    high_score: HIGH_SCORE.max(state.score),
+                    game_over_reference_high_score: HIGH_SCORE,
+                    theme,
+                    debug: false,
+                    debug_line: String::new(),
+                    now: Instant::now(),
+                    value_flash: HudValueFlash::default(),
+                    toast: None,
+                },
+                MenuUiState {
+                    start_selected_idx: 0,
+                    start_settings_open: screen == Screen::StartSettings,
+                    start_settings_selected_idx: 3,
+                    start_speed_level: 1,
+                    start_speed_adjust_mode: false,
+                    appearance: AppearancePreference::Auto,
+                    background: BackgroundChoice::Theme,
+                    game_border_enabled: true,
+                    shape_coding: false,
+                    play_area_too_small,
+                    pause_selected_idx: 0,
+                    game_over_selected_idx: 0,
+                    start_theme_select: None,
+                    pause_theme_select: theme_select.map(|(selected_idx, themes)| {
+                        ThemeSelectView {
+                            selected_idx,
+                            themes,
+                            preview_step: 0,
+                            bindings: &bindings,
+                        }
+                    }),
+                    theme_editor: None,
+                    key_editor: None,
+                    key_preset: KeyPreset::Default,
+                    bindings: &bindings,
+                    mouse_steering: false,
+                },
+                &mut RenderCache::default(),
+            );
+        })
+        .expect("test backend should not fail");
+    layout
+}
+
+/// Builds the board shown behind `screen`: a fresh game on the start
+/// screens, otherwise a game in progress with a bent snake, one food and
+/// one super food.
+fn scene_state(screen: Screen, bounds: GridSize) -> GameState {
+    let mut state = GameState::new_with_seed(bounds, 7);
+    if matches!(screen, Screen::Start | Screen::StartSettings) {
+        state.status = GameStatus::Paused;
+        return state;
+    }
+
+    let cx = i32::from(bounds.width / 2);
+    let cy = i32::from(bounds.height / 2);
+    let row = (0..6).map(|dx| Position { x: cx - dx, y: cy });
+    let column = (1..5).map(|dy| Position {
+        x: cx - 5,
+        y: cy + dy,
+    });
+    state.snake = Snake::from_segments(row.chain(column).collect(), Direction::Right)
+        .expect("snake has segments");
+    state.foods = vec![
+        Food::new(Position { x: cx + 4, y: cy }),
+        Food::new_super(
+            Position {
+                x: cx - 3,
+                y: cy - 3,
+            },
+            20,
+        ),
+    ];
+    state.tick_count = 96;
+    state.score = 140;
+    state.speed_level = 2;
+    for _ in 0..state.tick_count {
+        state.record_tick_duration(Duration::from_millis(125));
+    }
+
+    state.status = match screen {
+        Screen::Paused | Screen::PauseThemeSelect => GameStatus::Paused,
+        Screen::GameOver => {
+            state.death_reason = Some(DeathReason::WallCollision);
+            GameStatus::GameOver
+        }
+        Screen::Victory => {
+            state.score = 320;
+            GameStatus::Victory
+        }
+        _ => GameStatus::Playing,
+    };
+    state
+}
+
+/// Writes the buffer as text: one `|`-delimited row of symbols per line,
+/// then the same grid with one legend key per cell for its style, then the
+/// legend.
+#[must_use]
+pub fn format_buffer(buf: &Buffer) -> String {
+    let mut legend: Vec<(Color, Color, Modifier)> = Vec::new();
+    let mut symbols = String::new();
+    let mut styles = String::new();
+
+    for y in buf.area.top()..buf.area.bottom() {
+        symbols.push('|');
+        styles.push('|');
+        let mut covered = 0;
+        for x in buf.area.left()..buf.area.right() {
+            let cell = &buf[(x, y)];
+            // The cell after a wide glyph is drawn over by it.
+            if covered == 0 {
+                symbols.push_str(cell.symbol());
+                covered = cell.symbol().width().max(1);
+            }
+            covered -= 1;
+
+            let style = (cell.fg, cell.bg, cell.modifier);
+            let idx = legend
+                .iter()
+                .position(|entry| *entry == style)
+                .unwrap_or_else(|| {
+                    legend.push(style);
+                    legend.len() - 1
+                });
+            styles.push(style_key(idx));
+        }
+        symbols.push_str("|\n");
+        styles.push_str("|\n");
+    }
+
+    let mut out = symbols;
+    out.push('\n');
+    out.push_str(&styles);
+    out.push('\n');
+    for (idx, (fg, bg, modifier)) in legend.into_iter().enumerate() {
+        let _ = write!(out, "{} fg={fg} bg={bg}", style_key(idx));
+        if !modifier.is_empty() {
+            let _ = write!(out, " {modifier:?}");
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Returns the legend key for the `idx`th style: letters and digits, then
+/// Latin-1 and Latin Extended letters.
+fn style_key(idx: usize) -> char {
+    STYLE_KEYS.chars().nth(idx).unwrap_or_else(|| {
+        u32::try_from(idx - STYLE_KEYS.len())
+            .ok()
+            .and_then(|offset| char::from_u32(0xC0 + offset))
+            .unwrap_or('?')
+    })
+}
+
+/// Hides the crate version on the title screen so a release does not
+/// rewrite every start screen snapshot.
+fn mask_version(contents: &str) -> String {
+    let version = env!("CARGO_PKG_VERSION");
+    contents.replace(
+        &format!("v{version}"),
+        &format!("v{}", "#".repeat(version.len())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::buffer::Buffer;
+    use ratatui::layout::Rect;
+    use ratatui::style::{Color, Style};
+
+    use super::{format_buffer, mask_version, style_key};
+
+    #[test]
+    fn buffers_format_as_symbol_and_style_grids() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buf.set_string(0, 0, "ab", Style::new().fg(Color::Red));
+        buf.set_string(1, 1, "界", Style::new().bg(Color::Rgb(1, 2, 3)));
+
+        assert_eq!(
+            format_buffer(&buf),
+            "|ab  |\n| 界 |\n\n|aabb|\n|bcbb|\n\n\
+             a fg=Red bg=Reset\nb fg=Reset bg=Reset\nc fg=Reset bg=#010203\n"
+        );
+    }
+
+    #[test]
+    fn style_keys_stay_single_characters_past_the_alphabet() {
+        assert_eq!(style_key(0), 'a');
+        assert_eq!(style_key(61), '9');
+        assert_eq!(style_key(62), 'À');
+    }
+
+    #[test]
+    fn version_is_masked_to_its_own_width() {
+        let version = format!("v{}", env!("CARGO_PKG_VERSION"));
+        let masked = mask_version(&format!("|  {version}|"));
+
+        assert_eq!(masked.len(), version.len() + 4);
+        assert!(!masked.contains(&version));
+    }
+}
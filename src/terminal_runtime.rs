@@ -1,6 +1,10 @@
 use std::io;
 
 use crossterm::cursor::{Hide, Show};
+use crossterm::event::{
+    DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture,
+    KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+};
 use crossterm::execute;
 use crossterm::terminal::{
     EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
@@ -8,6 +12,8 @@ use crossterm::terminal::{
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
 
+use crate::platform::Platform;
+
 /// Concrete terminal type used by the runtime.
 pub type AppTerminal = Terminal<CrosstermBackend<io::Stdout>>;
 
@@ -16,24 +22,50 @@ pub type AppTerminal = Terminal<CrosstermBackend<io::Stdout>>;
 /// On drop, this type restores terminal state best-effort.
 pub struct TerminalSession {
     terminal: AppTerminal,
+    /// Whether kitty keyboard enhancement flags were pushed and need popping.
+    keyboard_enhanced: bool,
 }
 
 impl TerminalSession {
     /// Enters raw mode, switches to alternate screen, and creates a ratatui terminal.
-    pub fn enter() -> io::Result<Self> {
+    ///
+    /// Mouse capture is always enabled; focus reporting and the kitty
+    /// keyboard protocol (key repeat and release events) are enabled when
+    /// `platform` detected support for them.
+    pub fn enter(platform: Platform) -> io::Result<Self> {
         enable_raw_mode()?;
 
         let mut stdout = io::stdout();
-        if let Err(error) = execute!(stdout, EnterAlternateScreen, Hide) {
+        if let Err(error) = execute!(stdout, EnterAlternateScreen, Hide, EnableMouseCapture) {
             let _ = disable_raw_mode();
             return Err(error);
         }
+        if platform.focus_events()
+            && let Err(error) = execute!(stdout, EnableFocusChange)
+        {
+            let _ = cleanup_terminal_best_effort(false);
+            return Err(error);
+        }
+        // Enhancement is optional: a terminal that refuses it still plays,
+        // just without hold-to-sprint.
+        let keyboard_enhanced = platform.kitty_keyboard()
+            && execute!(
+                stdout,
+                PushKeyboardEnhancementFlags(
+                    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                        | KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+                )
+            )
+            .is_ok();
 
         let backend = CrosstermBackend::new(stdout);
         match Terminal::new(backend) {
-            Ok(terminal) => Ok(Self { terminal }),
+            Ok(terminal) => Ok(Self {
+                terminal,
+                keyboard_enhanced,
+            }),
             Err(error) => {
-                let _ = cleanup_terminal_best_effort();
+                let _ = cleanup_terminal_best_effort(keyboard_enhanced);
                 Err(error)
             }
         }
@@ -47,12 +79,21 @@ impl TerminalSession {
 
 impl Drop for TerminalSession {
     fn drop(&mut self) {
-        let _ = cleanup_terminal_best_effort();
+        let _ = cleanup_terminal_best_effort(self.keyboard_enhanced);
     }
 }
 
-fn cleanup_terminal_best_effort() -> io::Result<()> {
+fn cleanup_terminal_best_effort(keyboard_enhanced: bool) -> io::Result<()> {
     let _ = disable_raw_mode();
     let mut stdout = io::stdout();
-    execute!(stdout, Show, LeaveAlternateScreen)
+    if keyboard_enhanced {
+        let _ = execute!(stdout, PopKeyboardEnhancementFlags);
+    }
+    execute!(
+        stdout,
+        DisableMouseCapture,
+        DisableFocusChange,
+        Show,
+        LeaveAlternateScreen
+    )
 }
@@ -0,0 +1,627 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use ratatui::style::Color;
+use thiserror::Error;
+
+use super::lint::contrast_ratio;
+use super::{display_name, theme_file_json, user_theme_dir};
+use crate::color::xterm_index_to_rgb;
+use crate::config::{Theme, fallback_theme};
+use crate::platform::Platform;
+
+type Rgb = (u8, u8, u8);
+
+/// ANSI color names in slot order, as used by Alacritty and Windows Terminal.
+const ANSI_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// Terminal color scheme formats that `theme import` understands.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PaletteFormat {
+    /// Alacritty `colors` tables in TOML.
+    Alacritty,
+    /// Alacritty `colors` mappings in the legacy YAML config.
+    AlacrittyYaml,
+    /// kitty `.conf` with `colorN` and `background` settings.
+    Kitty,
+    /// A Windows Terminal color scheme object, or a settings file with `schemes`.
+    WindowsTerminal,
+    /// base16 scheme YAML with `base00`–`base0F`.
+    Base16,
+    /// iTerm2 `.itermcolors` property list.
+    ITerm,
+}
+
+impl PaletteFormat {
+    /// Guesses the format from the file extension and, for YAML, its content.
+    #[must_use]
+    pub fn detect(path: &Path, raw: &str) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "toml" => Some(Self::Alacritty),
+            "yml" | "yaml" if raw.contains("base00") => Some(Self::Base16),
+            "yml" | "yaml" => Some(Self::AlacrittyYaml),
+            "conf" => Some(Self::Kitty),
+            "json" => Some(Self::WindowsTerminal),
+            "itermcolors" => Some(Self::ITerm),
+            _ => None,
+        }
+    }
+}
+
+/// Problems converting a terminal color scheme into a theme.
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    #[error(
+        "unrecognized color scheme format (expected .toml, .yml, .conf, .json or .itermcolors)"
+    )]
+    UnknownFormat,
+    #[error("json parse error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("toml parse error: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("yaml parse error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("plist parse error: {0}")]
+    Plist(#[from] plist::Error),
+    #[error("no background color found in the color scheme")]
+    MissingBackground,
+    #[error("no user theme directory is available on this platform")]
+    NoThemeDir,
+    #[error("{} already exists; pass --force to overwrite it", .0.display())]
+    AlreadyExists(PathBuf),
+}
+
+/// Colors read from a terminal scheme or reported by the running terminal;
+/// unset ANSI slots stay `None`.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TerminalPalette {
+    pub name: Option<String>,
+    pub background: Option<Rgb>,
+    pub foreground: Option<Rgb>,
+    pub cursor: Option<Rgb>,
+    pub selection: Option<Rgb>,
+    pub ansi: [Option<Rgb>; 16],
+}
+
+impl TerminalPalette {
+    /// Builds a palette from the colors the terminal reported at startup.
+    ///
+    /// Returns `None` unless the terminal answered for its background and
+    /// all eight normal ANSI colors.
+    #[must_use]
+    pub fn from_platform(platform: Platform) -> Option<Self> {
+        let ansi = platform.palette();
+        if platform.background().is_none() || ansi[..8].iter().any(Option::is_none) {
+            return None;
+        }
+
+        Some(Self {
+            name: None,
+            background: platform.background(),
+            foreground: platform.foreground(),
+            cursor: None,
+            selection: None,
+            ansi,
+        })
+    }
+
+    /// Returns ANSI slot `index`, falling back to the xterm default.
+    fn slot(&self, index: usize) -> Rgb {
+        self.ansi[index].unwrap_or_else(|| xterm_index_to_rgb(index as u8))
+    }
+}
+
+/// Reads a color scheme from `path`, converts it, and writes it into the
+/// user theme directory. Returns the path written.
+///
+/// The theme id comes from `name` when given, otherwise from the scheme's
+/// own name or the file name.
+pub fn import_theme(path: &Path, name: Option<&str>, force: bool) -> Result<PathBuf, ImportError> {
+    let raw = fs::read_to_string(path)?;
+    let format = PaletteFormat::detect(path, &raw).ok_or(ImportError::UnknownFormat)?;
+    let palette = parse_palette(format, &raw)?;
+
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("imported");
+    let name = name
+        .map(str::to_owned)
+        .or_else(|| palette.name.clone())
+        .unwrap_or_else(|| display_name(stem));
+    let json = theme_json(&name, &palette)?;
+
+    let dir = user_theme_dir().ok_or(ImportError::NoThemeDir)?;
+    let target = dir.join(format!("{}.json", theme_id(&name)));
+    if target.exists() && !force {
+        return Err(ImportError::AlreadyExists(target));
+    }
+    fs::create_dir_all(&dir)?;
+    fs::write(&target, json)?;
+    Ok(target)
+}
+
+/// Parses a color scheme in the given format.
+pub fn parse_palette(format: PaletteFormat, raw: &str) -> Result<TerminalPalette, ImportError> {
+    Ok(match format {
+        PaletteFormat::Alacritty => alacritty_palette(&dotted_pairs(&toml::from_str(raw)?)),
+        PaletteFormat::AlacrittyYaml => {
+            alacritty_palette(&dotted_pairs(&serde_yaml::from_str(raw)?))
+        }
+        PaletteFormat::Kitty => kitty_palette(raw),
+        PaletteFormat::WindowsTerminal => windows_terminal_palette(raw)?,
+        PaletteFormat::Base16 => base16_palette(&dotted_pairs(&serde_yaml::from_str(raw)?)),
+        PaletteFormat::ITerm => iterm_palette(raw)?,
+    })
+}
+
+/// Renders a theme file for `palette`.
+///
+/// Snake and food colors are picked from their normal and bright ANSI
+/// slots, whichever stands out more against the background; UI colors come
+/// from the foreground, bright black and blue.
+pub fn theme_json(name: &str, palette: &TerminalPalette) -> Result<String, ImportError> {
+    let background = palette.background.ok_or(ImportError::MissingBackground)?;
+    let foreground = palette.foreground.unwrap_or_else(|| palette.slot(7));
+    let dark = luminance(background) < 128;
+
+    // Prefer slots the scheme defines; fall back to xterm defaults only
+    // when it defines neither.
+    let most_visible = |slots: [usize; 2]| {
+        let contrast =
+            |rgb: Rgb| contrast_ratio(rgb_color(rgb), rgb_color(background)).unwrap_or(1.0);
+        slots
+            .iter()
+            .filter_map(|&slot| palette.ansi[slot])
+            .max_by(|a, b| contrast(*a).total_cmp(&contrast(*b)))
+            .unwrap_or_else(|| palette.slot(slots[0]))
+    };
+
+    let snake_head = most_visible([10, 2]);
+    let snake_body = palette.slot(2);
+    let ui_bg = palette
+        .selection
+        .unwrap_or_else(|| mix(background, foreground, 0.12));
+    let theme = Theme {
+        name: name.to_owned(),
+        snake_head: rgb_color(snake_head),
+        snake_body: rgb_color(snake_body),
+        snake_tail: rgb_color(mix(snake_body, background, 0.35)),
+        food: rgb_color(most_visible([1, 9])),
+        super_food: rgb_color(most_visible([3, 11])),
+        terminal_bg: rgb_color(background),
+        field_bg: rgb_color(background),
+        ui_bg: rgb_color(ui_bg),
+        ui_text: rgb_color(foreground),
+        ui_accent: rgb_color(palette.cursor.unwrap_or_else(|| most_visible([4, 12]))),
+        ui_muted: rgb_color(palette.slot(8)),
+        ui_bright: rgb_color(if dark {
+            palette.slot(15)
+        } else {
+            palette.slot(0)
+        }),
+        field_bg_alt: rgb_color(mix(background, foreground, 0.04)),
+        ..fallback_theme()
+    };
+    Ok(theme_file_json(&theme)?)
+}
+
+fn alacritty_palette(pairs: &[(String, String)]) -> TerminalPalette {
+    let mut palette = TerminalPalette::default();
+    for (path, value) in pairs {
+        let Some(path) = path.strip_prefix("colors.") else {
+            continue;
+        };
+        let Some(color) = parse_color(value) else {
+            continue;
+        };
+        match path {
+            "primary.background" => palette.background = Some(color),
+            "primary.foreground" => palette.foreground = Some(color),
+            "cursor.cursor" => palette.cursor = Some(color),
+            "selection.background" => palette.selection = Some(color),
+            _ => {
+                let (group, name) = path.split_once('.').unwrap_or_default();
+                let offset = match group {
+                    "normal" => 0,
+                    "bright" => 8,
+                    _ => continue,
+                };
+                if let Some(slot) = ANSI_NAMES.iter().position(|ansi| *ansi == name) {
+                    palette.ansi[offset + slot] = Some(color);
+                }
+            }
+        }
+    }
+    palette
+}
+
+fn kitty_palette(raw: &str) -> TerminalPalette {
+    let mut palette = TerminalPalette::default();
+    for line in raw.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix("## name:") {
+            palette.name = Some(name.trim().to_owned());
+            continue;
+        }
+        let Some((key, value)) = line.split_once(char::is_whitespace) else {
+            continue;
+        };
+        let Some(color) = parse_color(value.trim()) else {
+            continue;
+        };
+        match key {
+            "background" => palette.background = Some(color),
+            "foreground" => palette.foreground = Some(color),
+            "cursor" => palette.cursor = Some(color),
+            "selection_background" => palette.selection = Some(color),
+            _ => {
+                if let Some(slot) = key
+                    .strip_prefix("color")
+                    .and_then(|index| index.parse::<usize>().ok())
+                    .filter(|&index| index < 16)
+                {
+                    palette.ansi[slot] = Some(color);
+                }
+            }
+        }
+    }
+    palette
+}
+
+fn windows_terminal_palette(raw: &str) -> Result<TerminalPalette, ImportError> {
+    let value = serde_json::from_str::<serde_json::Value>(raw)?;
+    // A full settings.json nests schemes in an array; import the first one.
+    let scheme = value
+        .get("schemes")
+        .and_then(|schemes| schemes.get(0))
+        .unwrap_or(&value);
+    let color = |key: &str| {
+        scheme
+            .get(key)
+            .and_then(|v| v.as_str())
+            .and_then(parse_color)
+    };
+
+    let mut palette = TerminalPalette {
+        name: scheme
+            .get("name")
+            .and_then(|v| v.as_str())
+            .map(str::to_owned),
+        background: color("background"),
+        foreground: color("foreground"),
+        cursor: color("cursorColor"),
+        selection: color("selectionBackground"),
+        ..TerminalPalette::default()
+    };
+    for (slot, name) in ANSI_NAMES.iter().enumerate() {
+        palette.ansi[slot] = color(name);
+        let bright = format!("bright{}{}", name[..1].to_ascii_uppercase(), &name[1..]);
+        palette.ansi[slot + 8] = color(&bright);
+    }
+    // Windows Terminal calls magenta "purple".
+    palette.ansi[5] = palette.ansi[5].or_else(|| color("purple"));
+    palette.ansi[13] = palette.ansi[13].or_else(|| color("brightPurple"));
+    Ok(palette)
+}
+
+fn base16_palette(pairs: &[(String, String)]) -> TerminalPalette {
+    let base = |index: usize| {
+        let key = format!("base{index:02X}");
+        pairs
+            .iter()
+            .find(|(path, _)| path.rsplit('.').next() == Some(key.as_str()))
+            .and_then(|(_, value)| parse_color(value))
+    };
+    let name = pairs
+        .iter()
+        .find(|(path, _)| path == "scheme" || path == "name")
+        .map(|(_, value)| value.clone());
+
+    // The conventional base16 shell mapping of scheme slots to ANSI slots.
+    const ANSI_BASES: [usize; 16] = [
+        0x00, 0x08, 0x0B, 0x0A, 0x0D, 0x0E, 0x0C, 0x05, 0x03, 0x08, 0x0B, 0x0A, 0x0D, 0x0E, 0x0C,
+        0x07,
+    ];
+    let mut palette = TerminalPalette {
+        name,
+        background: base(0x00),
+        foreground: base(0x05),
+        cursor: base(0x05),
+        selection: base(0x02),
+        ..TerminalPalette::default()
+    };
+    for (slot, &index) in ANSI_BASES.iter().enumerate() {
+        palette.ansi[slot] = base(index);
+    }
+    palette
+}
+
+fn iterm_palette(raw: &str) -> Result<TerminalPalette, ImportError> {
+    let root = plist::Value::from_reader_xml(raw.as_bytes())?;
+    let mut palette = TerminalPalette::default();
+    let Some(entries) = root.as_dictionary() else {
+        return Ok(palette);
+    };
+    for (name, entry) in entries {
+        let Some(components) = entry.as_dictionary() else {
+            continue;
+        };
+        let channel = |key: &str| {
+            let value = components.get(key)?;
+            let value = value
+                .as_real()
+                .or_else(|| value.as_signed_integer().map(|v| v as f64))?;
+            Some((value.clamp(0.0, 1.0) * 255.0).round() as u8)
+        };
+        let (Some(r), Some(g), Some(b)) = (
+            channel("Red Component"),
+            channel("Green Component"),
+            channel("Blue Component"),
+        ) else {
+            continue;
+        };
+        assign_iterm_color(&mut palette, name, (r, g, b));
+    }
+    Ok(palette)
+}
+
+fn assign_iterm_color(palette: &mut TerminalPalette, name: &str, rgb: Rgb) {
+    match name {
+        "Background Color" => palette.background = Some(rgb),
+        "Foreground Color" => palette.foreground = Some(rgb),
+        "Cursor Color" => palette.cursor = Some(rgb),
+        "Selection Color" => palette.selection = Some(rgb),
+        _ => {
+            if let Some(slot) = name
+                .strip_prefix("Ansi ")
+                .and_then(|rest| rest.strip_suffix(" Color"))
+                .and_then(|index| index.parse::<usize>().ok())
+                .filter(|&index| index < 16)
+            {
+                palette.ansi[slot] = Some(rgb);
+            }
+        }
+    }
+}
+
+/// Flattens nested tables into dotted key paths with their scalar values.
+fn dotted_pairs(value: &serde_json::Value) -> Vec<(String, String)> {
+    fn walk(value: &serde_json::Value, path: &str, pairs: &mut Vec<(String, String)>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                for (key, child) in map {
+                    let path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{path}.{key}")
+                    };
+                    walk(child, &path, pairs);
+                }
+            }
+            serde_json::Value::String(text) => pairs.push((path.to_owned(), text.clone())),
+            serde_json::Value::Number(number) => pairs.push((path.to_owned(), number.to_string())),
+            _ => {}
+        }
+    }
+
+    let mut pairs = Vec::new();
+    walk(value, "", &mut pairs);
+    pairs
+}
+
+/// Parses `#rrggbb`, `0xrrggbb` or bare `rrggbb`.
+fn parse_color(value: &str) -> Option<Rgb> {
+    let hex = value
+        .strip_prefix('#')
+        .or_else(|| value.strip_prefix("0x"))
+        .unwrap_or(value);
+    if hex.len() != 6 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(&hex[range], 16).ok();
+    Some((channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
+
+/// Lowercases `name` and joins its alphanumeric runs with dashes.
+pub(super) fn theme_id(name: &str) -> String {
+    let id = name
+        .split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+    if id.is_empty() {
+        "imported".to_owned()
+    } else {
+        id
+    }
+}
+
+fn mix(from: Rgb, to: Rgb, t: f32) -> Rgb {
+    let lerp = |a: u8, b: u8| (f32::from(a) + (f32::from(b) - f32::from(a)) * t).round() as u8;
+    (lerp(from.0, to.0), lerp(from.1, to.1), lerp(from.2, to.2))
+}
+
+fn luminance((r, g, b): Rgb) -> u32 {
+    (2126 * u32::from(r) + 7152 * u32::from(g) + 722 * u32::from(b)) / 10_000
+}
+
+fn rgb_color((r, g, b): Rgb) -> Color {
+    Color::Rgb(r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{PaletteFormat, parse_palette, theme_id, theme_json};
+    use crate::theme::lint::check_theme;
+
+    fn parse(file: &str, raw: &str) -> super::TerminalPalette {
+        let format = PaletteFormat::detect(Path::new(file), raw).expect("format should be known");
+        parse_palette(format, raw).expect("palette should parse")
+    }
+
+    #[test]
+    fn reads_alacritty_toml_and_yaml() {
+        let toml = r##"
+[colors.primary]
+background = "#1d1f21" # comment
+foreground = '#c5c8c6'
+
+[colors.normal]
+red = "#cc6666"
+
+[colors.bright]
+green = "0xb5bd68"
+"##;
+        let yaml = r##"
+colors:
+  primary:
+    background: '#1d1f21'
+    foreground: '#c5c8c6'
+  normal:
+    red: '#cc6666'
+  bright:
+    green: '0xb5bd68'
+"##;
+        for (file, raw) in [("a.toml", toml), ("a.yml", yaml)] {
+            let palette = parse(file, raw);
+            assert_eq!(palette.background, Some((0x1d, 0x1f, 0x21)), "{file}");
+            assert_eq!(palette.foreground, Some((0xc5, 0xc8, 0xc6)), "{file}");
+            assert_eq!(palette.ansi[1], Some((0xcc, 0x66, 0x66)), "{file}");
+            assert_eq!(palette.ansi[10], Some((0xb5, 0xbd, 0x68)), "{file}");
+        }
+    }
+
+    #[test]
+    fn reads_inline_tables_and_flow_mappings() {
+        let toml = r##"
+[colors]
+primary = { background = "#1d1f21", foreground = "#c5c8c6" }
+normal = { black = "#1d1f21", red = "#cc6666" }
+"##;
+        let yaml = r##"
+colors:
+  primary: { background: '#1d1f21', foreground: '#c5c8c6' }
+  normal: { black: '#1d1f21', red: '#cc6666' }
+"##;
+        for (file, raw) in [("a.toml", toml), ("a.yml", yaml)] {
+            let palette = parse(file, raw);
+            assert_eq!(palette.background, Some((0x1d, 0x1f, 0x21)), "{file}");
+            assert_eq!(palette.ansi[0], Some((0x1d, 0x1f, 0x21)), "{file}");
+            assert_eq!(palette.ansi[1], Some((0xcc, 0x66, 0x66)), "{file}");
+        }
+    }
+
+    #[test]
+    fn malformed_schemes_are_errors() {
+        for (file, raw) in [
+            ("a.toml", "[colors.primary\nbackground = \"#1d1f21\"\n"),
+            ("a.yml", "colors: {primary: [\n"),
+            ("a.itermcolors", "<plist><dict><key>Ansi 0 Color</key>"),
+        ] {
+            let format =
+                PaletteFormat::detect(Path::new(file), raw).expect("format should be known");
+            assert!(parse_palette(format, raw).is_err(), "{file}");
+        }
+    }
+
+    #[test]
+    fn reads_kitty_conf() {
+        let palette = parse(
+            "theme.conf",
+            "## name: Tokyo Night\nbackground #1a1b26\ncolor1     #f7768e\ncolor12 #7aa2f7\n",
+        );
+        assert_eq!(palette.name.as_deref(), Some("Tokyo Night"));
+        assert_eq!(palette.background, Some((0x1a, 0x1b, 0x26)));
+        assert_eq!(palette.ansi[1], Some((0xf7, 0x76, 0x8e)));
+        assert_eq!(palette.ansi[12], Some((0x7a, 0xa2, 0xf7)));
+    }
+
+    #[test]
+    fn reads_windows_terminal_scheme_from_settings() {
+        let palette = parse(
+            "settings.json",
+            r##"{ "schemes": [ { "name": "Campbell", "background": "#0C0C0C",
+                "purple": "#881798", "brightGreen": "#16C60C" } ] }"##,
+        );
+        assert_eq!(palette.name.as_deref(), Some("Campbell"));
+        assert_eq!(palette.background, Some((0x0c, 0x0c, 0x0c)));
+        assert_eq!(palette.ansi[5], Some((0x88, 0x17, 0x98)));
+        assert_eq!(palette.ansi[10], Some((0x16, 0xc6, 0x0c)));
+    }
+
+    #[test]
+    fn reads_base16_yaml() {
+        let palette = parse(
+            "ocean.yaml",
+            "scheme: \"Ocean\"\nbase00: \"2b303b\"\nbase05: \"c0c5ce\"\nbase08: \"bf616a\"\n",
+        );
+        assert_eq!(palette.name.as_deref(), Some("Ocean"));
+        assert_eq!(palette.background, Some((0x2b, 0x30, 0x3b)));
+        assert_eq!(palette.foreground, Some((0xc0, 0xc5, 0xce)));
+        assert_eq!(palette.ansi[1], Some((0xbf, 0x61, 0x6a)));
+        assert_eq!(palette.ansi[9], Some((0xbf, 0x61, 0x6a)));
+    }
+
+    #[test]
+    fn reads_itermcolors_plist() {
+        let raw = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+	<key>Ansi 0 Color</key>
+	<dict/>
+	<key>Ansi 1 Color</key>
+	<dict>
+		<key>Blue Component</key>
+		<real>0.0</real>
+		<key>Green Component</key>
+		<real>0.5</real>
+		<key>Red Component</key>
+		<integer>1</integer>
+	</dict>
+	<key>Background Color</key>
+	<dict>
+		<key>Blue Component</key>
+		<real>0.2</real>
+		<key>Green Component</key>
+		<real>0.1</real>
+		<key>Red Component</key>
+		<real>0.1</real>
+	</dict>
+</dict>
+</plist>"#;
+        let palette = parse("Dracula.itermcolors", raw);
+        assert_eq!(palette.ansi[0], None);
+        assert_eq!(palette.ansi[1], Some((255, 128, 0)));
+        assert_eq!(palette.background, Some((26, 26, 51)));
+    }
+
+    #[test]
+    fn generated_theme_passes_theme_check() {
+        let palette = parse(
+            "theme.conf",
+            "background #1a1b26\nforeground #c0caf5\ncolor1 #f7768e\ncolor2 #9ece6a\n\
+             color3 #e0af68\ncolor8 #414868\ncolor10 #9ece6a\n",
+        );
+        let json = theme_json("Tokyo Night", &palette).expect("palette has a background");
+        let report = check_theme("tokyo-night", &json, &Default::default());
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(json.contains("\"field_bg\": \"#1A1B26\""));
+        assert!(json.contains("\"food\": \"#F7768E\""));
+    }
+
+    #[test]
+    fn theme_ids_are_slugged() {
+        assert_eq!(theme_id("Tokyo Night (Storm)"), "tokyo-night-storm");
+        assert_eq!(theme_id("!!"), "imported");
+    }
+}
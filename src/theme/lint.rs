@@ -0,0 +1,494 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use ratatui::style::Color;
+use serde_json::Value;
+
+use super::{
+    Appearance, BackgroundFile, ColorValue, THEME_KEYS, ThemeFile, ThemeParseError,
+    background_image_path, flatten_theme_file, parse_hex_color, parse_named_ansi_color,
+    parse_theme_with_sources,
+};
+use crate::background::AsciiArt;
+use crate::color::color_to_rgb;
+
+/// Keys recognized at the top level of a theme file.
+const TOP_LEVEL_KEYS: [&str; 6] = [
+    "name",
+    "extends",
+    "defs",
+    "theme",
+    "snake_skin",
+    "background",
+];
+
+/// Foreground/background pairs checked for contrast, with the minimum WCAG
+/// ratio each must reach. Play-field entities use the 3:1 threshold for
+/// graphical objects; UI text uses the 4.5:1 threshold for body text.
+const CONTRAST_PAIRS: [(&str, &str, f64); 4] = [
+    ("snake_head", "field_bg", 3.0),
+    ("food", "field_bg", 3.0),
+    ("super_food", "field_bg", 3.0),
+    ("ui_text", "ui_bg", 4.5),
+];
+
+/// Result of checking one theme file.
+#[derive(Debug, Default)]
+pub struct ThemeReport {
+    pub errors: Vec<ThemeParseError>,
+    pub warnings: Vec<ContrastWarning>,
+}
+
+impl ThemeReport {
+    /// Returns true when the theme has neither errors nor warnings.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+}
+
+/// A color pair whose contrast falls below its required minimum.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContrastWarning {
+    pub appearance: Appearance,
+    pub foreground: &'static str,
+    pub background: &'static str,
+    pub ratio: f64,
+    pub minimum: f64,
+}
+
+impl fmt::Display for ContrastWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let appearance = match self.appearance {
+            Appearance::Dark => "dark",
+            Appearance::Light => "light",
+        };
+        write!(
+            f,
+            "theme.{} on theme.{} ({appearance}): contrast {:.2}:1 is below {}:1",
+            self.foreground, self.background, self.ratio, self.minimum
+        )
+    }
+}
+
+/// Checks a theme file for structural problems and low-contrast color pairs.
+///
+/// `id` supplies the display name used while resolving colors, and
+/// `parents` the raw JSON of themes the file may name in `extends`.
+#[must_use]
+pub fn check_theme(id: &str, raw: &str, parents: &HashMap<String, String>) -> ThemeReport {
+    let mut report = ThemeReport::default();
+    let parsed = serde_json::from_str::<Value>(raw).and_then(|value| {
+        serde_json::from_value::<ThemeFile>(value.clone()).map(|file| (value, file))
+    });
+    let (value, mut file) = match parsed {
+        Ok(pair) => pair,
+        Err(error) => {
+            report.errors.push(error.into());
+            return report;
+        }
+    };
+
+    // Resolve references against inherited entries too; with a broken
+    // chain, fall back to the file's own entries.
+    match flatten_theme_file(id, raw, parents) {
+        Ok(flattened) => file = flattened,
+        Err(error) => report.errors.push(error),
+    }
+
+    if let Value::Object(top) = &value {
+        for key in top.keys() {
+            if !TOP_LEVEL_KEYS.contains(&key.as_str()) {
+                report
+                    .errors
+                    .push(ThemeParseError::UnknownKey { path: key.clone() });
+            }
+        }
+    }
+
+    for section in ["defs", "theme"] {
+        let Some(Value::Object(entries)) = value.get(section) else {
+            continue;
+        };
+        for (key, entry) in entries {
+            let path = format!("{section}.{key}");
+            if section == "theme" && !THEME_KEYS.contains(&key.as_str()) {
+                report
+                    .errors
+                    .push(ThemeParseError::UnknownKey { path: path.clone() });
+            }
+            check_value(&file, &path, entry, &mut report.errors);
+            if let Some(chain) = reference_cycle(&file, section, key) {
+                report
+                    .errors
+                    .push(ThemeParseError::ReferenceCycle { path, chain });
+            }
+        }
+    }
+
+    if let Some(Value::Array(colors)) = value.get("snake_skin").and_then(|skin| skin.get("colors"))
+    {
+        for (idx, entry) in colors.iter().enumerate() {
+            check_value(
+                &file,
+                &format!("snake_skin.colors[{idx}]"),
+                entry,
+                &mut report.errors,
+            );
+        }
+    }
+
+    if let Some(BackgroundFile::Image { file: image }) = &file.background
+        && let Err(error) = AsciiArt::load(&background_image_path(image))
+    {
+        report.errors.push(ThemeParseError::UnreadableImage {
+            file: image.clone(),
+            reason: error.to_string(),
+        });
+    }
+
+    let skin_colors = file
+        .snake_skin
+        .as_ref()
+        .map_or(&[][..], |skin| skin.colors());
+    let has_variants = file
+        .defs
+        .values()
+        .chain(file.theme.values())
+        .chain(skin_colors)
+        .any(|value| matches!(value, ColorValue::Variant { .. }));
+    let appearances: &[Appearance] = if has_variants {
+        &[Appearance::Dark, Appearance::Light]
+    } else {
+        &[Appearance::Dark]
+    };
+    for &appearance in appearances {
+        let Ok(theme) = parse_theme_with_sources(id, raw, parents, appearance) else {
+            continue;
+        };
+        let color_of = |key: &str| match key {
+            "snake_head" => theme.snake_head,
+            "food" => theme.food,
+            "super_food" => theme.super_food,
+            "field_bg" => theme.field_bg,
+            "ui_text" => theme.ui_text,
+            "ui_bg" => theme.ui_bg,
+            _ => Color::Reset,
+        };
+        for (foreground, background, minimum) in CONTRAST_PAIRS {
+            let Some(ratio) = contrast_ratio(color_of(foreground), color_of(background)) else {
+                continue;
+            };
+            if ratio < minimum {
+                report.warnings.push(ContrastWarning {
+                    appearance,
+                    foreground,
+                    background,
+                    ratio,
+                    minimum,
+                });
+            }
+        }
+    }
+
+    report
+}
+
+/// Returns the WCAG 2 contrast ratio between two colors, from 1.0 to 21.0.
+///
+/// Returns `None` when either color is the terminal default, whose actual
+/// value is unknown.
+#[must_use]
+pub fn contrast_ratio(a: Color, b: Color) -> Option<f64> {
+    let (la, lb) = (
+        relative_luminance(color_to_rgb(a)?),
+        relative_luminance(color_to_rgb(b)?),
+    );
+    let (lighter, darker) = if la >= lb { (la, lb) } else { (lb, la) };
+    Some((lighter + 0.05) / (darker + 0.05))
+}
+
+fn relative_luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    let linear = |channel: u8| {
+        let c = f64::from(channel) / 255.0;
+        if c <= 0.040_45 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+fn check_value(file: &ThemeFile, path: &str, value: &Value, errors: &mut Vec<ThemeParseError>) {
+    match value {
+        Value::String(text) => check_color_string(file, path, text, errors),
+        Value::Object(variants) => {
+            for (key, variant) in variants {
+                let path = format!("{path}.{key}");
+                if matches!(key.as_str(), "dark" | "light") {
+                    check_value(file, &path, variant, errors);
+                } else {
+                    errors.push(ThemeParseError::UnknownKey { path });
+                }
+            }
+        }
+        // Numbers outside 0–255 and other JSON types fail deserialization.
+        _ => {}
+    }
+}
+
+fn check_color_string(
+    file: &ThemeFile,
+    path: &str,
+    value: &str,
+    errors: &mut Vec<ThemeParseError>,
+) {
+    if is_literal_color(value) {
+        return;
+    }
+
+    if value.starts_with('#') {
+        errors.push(ThemeParseError::InvalidHex {
+            path: path.to_owned(),
+            value: value.to_owned(),
+        });
+    } else if lookup(file, value).is_none() {
+        errors.push(ThemeParseError::UnresolvedReference {
+            path: path.to_owned(),
+            name: value.to_owned(),
+        });
+    }
+}
+
+fn is_literal_color(value: &str) -> bool {
+    value.eq_ignore_ascii_case("none")
+        || value.eq_ignore_ascii_case("reset")
+        || parse_hex_color(value).is_some()
+        || parse_named_ansi_color(value).is_some()
+}
+
+/// Resolves a reference name the way the loader does: `defs` first, then
+/// `theme`.
+fn lookup<'a>(file: &'a ThemeFile, name: &str) -> Option<(&'static str, &'a ColorValue)> {
+    file.defs
+        .get(name)
+        .map(|value| ("defs", value))
+        .or_else(|| file.theme.get(name).map(|value| ("theme", value)))
+}
+
+/// Returns the chain of names leading from `section.key` back to itself,
+/// if its references form a cycle.
+///
+/// Keys shadowed by a `defs` entry of the same name cannot be referenced and
+/// so cannot be part of a cycle.
+fn reference_cycle(file: &ThemeFile, section: &str, key: &str) -> Option<Vec<String>> {
+    if lookup(file, key).map(|(found, _)| found) != Some(section) {
+        return None;
+    }
+
+    let mut chain = vec![key.to_owned()];
+    let mut visited = HashSet::new();
+    find_path_back(file, key, &mut chain, &mut visited).then_some(chain)
+}
+
+fn find_path_back<'a>(
+    file: &'a ThemeFile,
+    target: &str,
+    chain: &mut Vec<String>,
+    visited: &mut HashSet<&'a str>,
+) -> bool {
+    let current = chain.last().cloned().unwrap_or_default();
+    let Some((_, value)) = lookup(file, &current) else {
+        return false;
+    };
+
+    for name in references(value) {
+        chain.push(name.to_owned());
+        if name == target {
+            return true;
+        }
+        if visited.insert(name) && find_path_back(file, target, chain, visited) {
+            return true;
+        }
+        chain.pop();
+    }
+
+    false
+}
+
+/// Lists the names a value refers to across both variants.
+fn references(value: &ColorValue) -> Vec<&str> {
+    match value {
+        ColorValue::String(text) if !is_literal_color(text) => vec![text.as_str()],
+        ColorValue::String(_) | ColorValue::Ansi(_) => Vec::new(),
+        ColorValue::Variant { dark, light } => dark
+            .iter()
+            .chain(light.iter())
+            .flat_map(|value| references(value))
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ratatui::style::Color;
+
+    use super::{check_theme, contrast_ratio};
+    use crate::theme::{Appearance, BUILTIN_THEMES, ThemeParseError};
+
+    const BASE_THEME: &str = r##""snake_body": "#00CC00", "snake_tail": "#00CC00",
+        "super_food": "#FFFF00", "terminal_bg": "#000000", "field_bg": "#000000",
+        "ui_bg": "#000000", "ui_text": "#FFFFFF", "ui_accent": "#00CC00",
+        "ui_muted": "#888888""##;
+
+    fn messages(raw: &str) -> Vec<String> {
+        check_theme("test", raw, &HashMap::new())
+            .errors
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn contrast_ratio_matches_wcag_extremes() {
+        let ratio = contrast_ratio(Color::Rgb(0, 0, 0), Color::Rgb(255, 255, 255))
+            .expect("rgb colors have a contrast ratio");
+        assert!((ratio - 21.0).abs() < 0.01);
+        assert_eq!(contrast_ratio(Color::Reset, Color::White), None);
+    }
+
+    #[test]
+    fn reports_unknown_keys_with_paths() {
+        let raw = format!(
+            r##"{{ "colour": 1, "theme": {{ {BASE_THEME}, "snake_head": {{ "drak": "#00CC00" }},
+            "food": "#FF0000", "snek": "#FF0000" }} }}"##
+        );
+        assert_eq!(
+            messages(&raw),
+            vec![
+                "colour: unknown key",
+                "theme.snake_head.drak: unknown key",
+                "theme.snek: unknown key",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_bad_hex_and_unresolved_references() {
+        let raw = format!(
+            r##"{{ "defs": {{ "red": "#FF00GG" }},
+            "theme": {{ {BASE_THEME}, "snake_head": "grene", "food": "red" }} }}"##
+        );
+        assert_eq!(
+            messages(&raw),
+            vec![
+                "defs.red: invalid hex color '#FF00GG'",
+                "theme.snake_head: 'grene' is not a color, a defs entry or a theme key",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_snake_skin_colors() {
+        let raw = format!(
+            r##"{{ "theme": {{ {BASE_THEME}, "snake_head": "#00CC00", "food": "#FF0000" }},
+            "snake_skin": {{ "style": "gradient", "colors": ["snake_head", "#12", "lime"] }} }}"##
+        );
+        assert_eq!(
+            messages(&raw),
+            vec![
+                "snake_skin.colors[1]: invalid hex color '#12'",
+                "snake_skin.colors[2]: 'lime' is not a color, a defs entry or a theme key",
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_unreadable_background_image() {
+        let raw = format!(
+            r##"{{ "theme": {{ {BASE_THEME}, "snake_head": "#00CC00", "food": "#FF0000" }},
+            "background": {{ "pattern": "image", "file": "/nonexistent/snake-art.txt" }} }}"##
+        );
+        let messages = messages(&raw);
+        assert_eq!(messages.len(), 1);
+        assert!(
+            messages[0].starts_with("background.file: cannot read '/nonexistent/snake-art.txt'")
+        );
+    }
+
+    #[test]
+    fn reports_reference_cycles() {
+        let raw = format!(
+            r##"{{ "defs": {{ "a": "b", "b": {{ "dark": "a", "light": "#FFFFFF" }} }},
+            "theme": {{ {BASE_THEME}, "snake_head": "a", "food": "food" }} }}"##
+        );
+        let report = check_theme("test", &raw, &HashMap::new());
+        let cycles: Vec<String> = report
+            .errors
+            .iter()
+            .filter(|error| matches!(error, ThemeParseError::ReferenceCycle { .. }))
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            cycles,
+            vec![
+                "defs.a: reference cycle a -> b -> a",
+                "defs.b: reference cycle b -> a -> b",
+                "theme.food: reference cycle food -> food",
+            ]
+        );
+    }
+
+    #[test]
+    fn warns_about_low_contrast_in_each_appearance() {
+        let raw = format!(
+            r##"{{ "theme": {{ {BASE_THEME}, "snake_head": "#00CC00",
+            "food": {{ "dark": "#0A0A0A", "light": "#FF0000" }} }} }}"##
+        );
+        let report = check_theme("test", &raw, &HashMap::new());
+        assert!(report.errors.is_empty());
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.warnings[0].appearance, Appearance::Dark);
+        assert_eq!(report.warnings[0].foreground, "food");
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn built_in_themes_have_no_errors() {
+        for &(id, raw) in BUILTIN_THEMES {
+            let report = check_theme(id, raw, &HashMap::new());
+            let errors: Vec<String> = report.errors.iter().map(ToString::to_string).collect();
+            assert!(errors.is_empty(), "{id}: {errors:?}");
+        }
+    }
+
+    #[test]
+    fn references_may_use_inherited_defs() {
+        let parents = HashMap::from([(
+            "base".to_owned(),
+            format!(r##"{{ "defs": {{ "leaf": "#00CC00" }}, "theme": {{ {BASE_THEME} }} }}"##),
+        )]);
+        let raw =
+            r##"{ "extends": "base", "theme": { "snake_head": "leaf", "food": "#FF0000" } }"##;
+        assert!(check_theme("child", raw, &parents).errors.is_empty());
+
+        let report = check_theme("child", r#"{ "extends": "nope" }"#, &parents);
+        let errors: Vec<String> = report.errors.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            errors,
+            vec!["extends: 'child' extends 'nope', which is not a built-in or user theme"]
+        );
+    }
+
+    #[test]
+    fn invalid_json_is_the_only_error() {
+        let report = check_theme("test", "{ \"theme\": ", &HashMap::new());
+        assert!(matches!(
+            report.errors.as_slice(),
+            [ThemeParseError::Json(_)]
+        ));
+    }
+}
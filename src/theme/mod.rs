@@ -0,0 +1,1888 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use ratatui::style::Color;
+use serde::ser::{SerializeMap, Serializer};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use self::import::{TerminalPalette, theme_id, theme_json};
+use crate::background::{AsciiArt, BackgroundPattern};
+use crate::color::color_to_rgb;
+use crate::config::{SnakeSkin, Theme, fallback_theme};
+use crate::platform::Platform;
+
+pub mod import;
+pub mod lint;
+
+const USER_THEME_APP_DIR: &str = "terminal-snake";
+
+/// Id and name of the theme derived from the live terminal palette.
+const TERMINAL_THEME_ID: &str = "terminal";
+const TERMINAL_THEME_NAME: &str = "Terminal Palette";
+
+/// Keys recognized in a theme file's `theme` object, in file order.
+pub const THEME_KEYS: [&str; 13] = [
+    "snake_head",
+    "snake_body",
+    "snake_tail",
+    "food",
+    "super_food",
+    "terminal_bg",
+    "field_bg",
+    "ui_bg",
+    "ui_text",
+    "ui_accent",
+    "ui_muted",
+    "ui_bright",
+    "field_bg_alt",
+];
+
+include!(concat!(env!("OUT_DIR"), "/builtin_themes.rs"));
+
+/// Which side of a theme's `{ "dark": ..., "light": ... }` variants to use.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Appearance {
+    Dark,
+    Light,
+}
+
+impl Appearance {
+    /// Detects the terminal appearance from the OSC 11 background reply,
+    /// then `COLORFGBG`. Returns `None` when neither source is available.
+    #[must_use]
+    pub fn detect(platform: Platform) -> Option<Self> {
+        platform
+            .background()
+            .map(Self::from_background)
+            .or_else(|| {
+                std::env::var("COLORFGBG")
+                    .ok()
+                    .and_then(|value| Self::from_colorfgbg(&value))
+            })
+    }
+
+    fn from_background((r, g, b): (u8, u8, u8)) -> Self {
+        // Rec. 709 relative luminance on gamma-encoded channels is close
+        // enough for a light/dark split.
+        let luma = 0.2126 * f32::from(r) + 0.7152 * f32::from(g) + 0.0722 * f32::from(b);
+        if luma < 128.0 {
+            Self::Dark
+        } else {
+            Self::Light
+        }
+    }
+
+    /// Parses rxvt-style `fg;bg` (or `fg;default;bg`) ANSI indices.
+    fn from_colorfgbg(value: &str) -> Option<Self> {
+        let bg = value.rsplit(';').next()?.parse::<u8>().ok()?;
+        match bg {
+            0..=6 | 8 => Some(Self::Dark),
+            7 | 9..=15 => Some(Self::Light),
+            _ => None,
+        }
+    }
+}
+
+/// Appearance setting chosen by the user; `Auto` follows the terminal.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum AppearancePreference {
+    #[default]
+    Auto,
+    Dark,
+    Light,
+}
+
+impl AppearancePreference {
+    /// Resolves the preference, falling back to dark when auto-detection failed.
+    #[must_use]
+    pub fn resolve(self, detected: Option<Appearance>) -> Appearance {
+        match self {
+            Self::Auto => detected.unwrap_or(Appearance::Dark),
+            Self::Dark => Appearance::Dark,
+            Self::Light => Appearance::Light,
+        }
+    }
+
+    /// Returns the next preference in settings-menu order.
+    #[must_use]
+    pub fn next(self) -> Self {
+        match self {
+            Self::Auto => Self::Dark,
+            Self::Dark => Self::Light,
+            Self::Light => Self::Auto,
+        }
+    }
+
+    #[must_use]
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Auto => "Auto",
+            Self::Dark => "Dark",
+            Self::Light => "Light",
+        }
+    }
+}
+
+impl std::str::FromStr for AppearancePreference {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            value if value.eq_ignore_ascii_case("auto") => Ok(Self::Auto),
+            value if value.eq_ignore_ascii_case("dark") => Ok(Self::Dark),
+            value if value.eq_ignore_ascii_case("light") => Ok(Self::Light),
+            other => Err(format!(
+                "unknown appearance '{other}' (expected auto, dark or light)"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ThemeItem {
+    pub id: String,
+    pub theme: Theme,
+}
+
+#[derive(Debug, Clone)]
+pub struct ThemeCatalog {
+    themes: Vec<ThemeItem>,
+    selected_idx: usize,
+    appearance: Appearance,
+    /// Raw JSON per theme id, kept so variants can be re-resolved.
+    sources: HashMap<String, String>,
+    /// User theme directory watched for changes, if one exists.
+    user_dir: Option<PathBuf>,
+    /// Modification time and size of each user theme file at the last scan.
+    user_files: BTreeMap<PathBuf, (SystemTime, u64)>,
+    /// Load problems not yet shown to the player.
+    warnings: Vec<String>,
+    /// Theme JSON derived from the live terminal palette, if any.
+    terminal_source: Option<String>,
+}
+
+impl ThemeCatalog {
+    /// Loads embedded bundled themes, then overlays user-provided themes,
+    /// resolving light/dark variants for `appearance`.
+    #[must_use]
+    pub fn load(appearance: Appearance) -> Self {
+        Self::load_with_user_dir(appearance, user_theme_dir())
+    }
+
+    /// Loads only the embedded bundled themes, ignoring the user directory.
+    #[must_use]
+    pub fn builtin(appearance: Appearance) -> Self {
+        Self::load_with_user_dir(appearance, None)
+    }
+
+    fn load_with_user_dir(appearance: Appearance, user_dir: Option<PathBuf>) -> Self {
+        let user_files = user_dir.as_deref().map(scan_theme_dir).unwrap_or_default();
+        let mut catalog = Self {
+            themes: Vec::new(),
+            selected_idx: 0,
+            appearance,
+            sources: HashMap::new(),
+            user_dir,
+            user_files,
+            warnings: Vec::new(),
+            terminal_source: None,
+        };
+        catalog.rebuild();
+        let _ = catalog.select_by_id("ember");
+        catalog
+    }
+
+    /// Re-parses the user theme directory when any file in it was added,
+    /// removed or modified since the last scan. Returns true on reload.
+    ///
+    /// The selection stays on the same theme id. A theme whose file no
+    /// longer parses keeps its last good version, and the parse error is
+    /// queued for [`Self::take_warnings`].
+    pub fn reload_if_changed(&mut self) -> bool {
+        let Some(dir) = self.user_dir.as_deref() else {
+            return false;
+        };
+        let snapshot = scan_theme_dir(dir);
+        if snapshot == self.user_files {
+            return false;
+        }
+
+        self.user_files = snapshot;
+        let selected_id = self.current_id().to_owned();
+        let selected_idx = self.selected_idx;
+        self.rebuild();
+        if !self.select_by_id(&selected_id) {
+            // The selected file was deleted; stay near its old position.
+            self.selected_idx = selected_idx.min(self.themes.len() - 1);
+        }
+        true
+    }
+
+    /// Adds a `terminal` theme built from the palette the terminal reported,
+    /// keeping the current selection. Returns false if `palette` has no
+    /// background to build from.
+    ///
+    /// The theme sits after the built-ins, so user themes can extend or
+    /// override it like any other.
+    pub fn add_terminal_theme(&mut self, palette: &TerminalPalette) -> bool {
+        let Ok(source) = theme_json(TERMINAL_THEME_NAME, palette) else {
+            return false;
+        };
+
+        let selected_id = self.current_id().to_owned();
+        self.terminal_source = Some(source);
+        self.rebuild();
+        let _ = self.select_by_id(&selected_id);
+        true
+    }
+
+    /// Writes `theme` as a JSON file into the user theme directory and
+    /// selects it. Returns the new theme's id, derived from its name.
+    ///
+    /// Colors are saved as resolved for the current appearance, so the file
+    /// has no light/dark variants. A user theme with the same id is
+    /// replaced.
+    pub fn save_user_theme(&mut self, theme: &Theme) -> io::Result<String> {
+        let Some(dir) = self.user_dir.clone() else {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "no user theme directory is available",
+            ));
+        };
+
+        let id = theme_id(&theme.name);
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(format!("{id}.json")), theme_file_json(theme)?)?;
+
+        self.user_files = scan_theme_dir(&dir);
+        self.rebuild();
+        let _ = self.select_by_id(&id);
+        Ok(id)
+    }
+
+    /// Returns the raw JSON of every loaded theme, keyed by id.
+    #[must_use]
+    pub fn sources(&self) -> &HashMap<String, String> {
+        &self.sources
+    }
+
+    /// Returns and clears load problems collected since the last call.
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    fn rebuild(&mut self) {
+        let mut order = Vec::<String>::new();
+        let mut by_id = HashMap::<String, Theme>::new();
+        let mut sources = HashMap::<String, String>::new();
+        let mut issues = Vec::<ThemeLoadIssue>::new();
+
+        merge_embedded_themes(
+            self.appearance,
+            &mut order,
+            &mut by_id,
+            &mut sources,
+            &mut issues,
+        );
+
+        if let Some(content) = &self.terminal_source {
+            let entry = SourceEntry {
+                id: TERMINAL_THEME_ID.to_owned(),
+                label: "terminal palette theme".to_owned(),
+                content: content.clone(),
+            };
+            merge_sources(
+                vec![entry],
+                self.appearance,
+                &mut order,
+                &mut by_id,
+                &mut sources,
+                &mut issues,
+            );
+        }
+
+        if let Some(path) = self.user_dir.as_deref() {
+            merge_theme_dir(
+                path,
+                self.appearance,
+                &mut order,
+                &mut by_id,
+                &mut sources,
+                &mut issues,
+            );
+        }
+
+        for issue in issues {
+            // Keep the last good version of a theme that is mid-edit.
+            if let Some(previous) = self.themes.iter().find(|item| item.id == issue.id)
+                && let Some(raw) = self.sources.get(&issue.id)
+            {
+                sources.insert(issue.id.clone(), raw.clone());
+                insert_theme(
+                    &mut order,
+                    &mut by_id,
+                    issue.id.clone(),
+                    previous.theme.clone(),
+                );
+            }
+            self.warnings.push(issue.message);
+        }
+
+        if by_id.is_empty() {
+            insert_theme(
+                &mut order,
+                &mut by_id,
+                "fallback".to_owned(),
+                fallback_theme(),
+            );
+        }
+
+        let mut themes = Vec::with_capacity(order.len());
+        for id in order {
+            if let Some(theme) = by_id.remove(&id) {
+                themes.push(ThemeItem { id, theme });
+            }
+        }
+
+        self.themes = themes;
+        self.sources = sources;
+        self.selected_idx = 0;
+    }
+
+    #[must_use]
+    pub fn appearance(&self) -> Appearance {
+        self.appearance
+    }
+
+    /// Re-resolves every theme's variants for `appearance`, keeping the
+    /// current selection.
+    pub fn set_appearance(&mut self, appearance: Appearance) {
+        if appearance == self.appearance {
+            return;
+        }
+
+        self.appearance = appearance;
+        for item in &mut self.themes {
+            let Some(raw) = self.sources.get(&item.id) else {
+                continue;
+            };
+            if let Ok(theme) = parse_theme_with_sources(&item.id, raw, &self.sources, appearance) {
+                item.theme = theme;
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.themes.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.themes.is_empty()
+    }
+
+    #[must_use]
+    pub fn current_theme(&self) -> &Theme {
+        &self.themes[self.selected_idx].theme
+    }
+
+    #[must_use]
+    pub fn current_index(&self) -> usize {
+        self.selected_idx
+    }
+
+    #[must_use]
+    pub fn current_id(&self) -> &str {
+        &self.themes[self.selected_idx].id
+    }
+
+    #[must_use]
+    pub fn items(&self) -> &[ThemeItem] {
+        &self.themes
+    }
+
+    #[must_use]
+    pub fn theme_at(&self, idx: usize) -> Option<&Theme> {
+        self.themes.get(idx).map(|item| &item.theme)
+    }
+
+    #[must_use]
+    pub fn id_at(&self, idx: usize) -> Option<&str> {
+        self.themes.get(idx).map(|item| item.id.as_str())
+    }
+
+    pub fn select_index(&mut self, idx: usize) -> bool {
+        if idx < self.themes.len() {
+            self.selected_idx = idx;
+            return true;
+        }
+
+        false
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected_idx = (self.selected_idx + 1) % self.themes.len();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected_idx = if self.selected_idx == 0 {
+            self.themes.len() - 1
+        } else {
+            self.selected_idx - 1
+        };
+    }
+
+    #[must_use]
+    pub fn select_by_id(&mut self, id: &str) -> bool {
+        if let Some(idx) = self.themes.iter().position(|theme| theme.id == id) {
+            self.selected_idx = idx;
+            return true;
+        }
+
+        false
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ThemeFile {
+    #[serde(default)]
+    name: Option<String>,
+    /// Id of a theme whose `defs` and `theme` entries this file inherits.
+    #[serde(default)]
+    extends: Option<String>,
+    #[serde(default)]
+    defs: HashMap<String, ColorValue>,
+    #[serde(default)]
+    theme: HashMap<String, ColorValue>,
+    #[serde(default)]
+    snake_skin: Option<SkinFile>,
+    #[serde(default)]
+    background: Option<BackgroundFile>,
+}
+
+/// The `snake_skin` section of a theme file, tagged by `style`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "style", rename_all = "snake_case", deny_unknown_fields)]
+enum SkinFile {
+    Banded,
+    Gradient {
+        colors: Vec<ColorValue>,
+    },
+    Stripes {
+        colors: Vec<ColorValue>,
+        #[serde(default = "default_stripe_width")]
+        width: usize,
+    },
+    Length {
+        colors: Vec<ColorValue>,
+        #[serde(default = "default_skin_length")]
+        length: usize,
+    },
+}
+
+impl SkinFile {
+    fn colors(&self) -> &[ColorValue] {
+        match self {
+            Self::Banded => &[],
+            Self::Gradient { colors }
+            | Self::Stripes { colors, .. }
+            | Self::Length { colors, .. } => colors,
+        }
+    }
+}
+
+/// The `background` section of a theme file, tagged by `pattern`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "pattern", rename_all = "snake_case", deny_unknown_fields)]
+enum BackgroundFile {
+    Plain,
+    Checkerboard,
+    HorizontalStripes,
+    VerticalStripes,
+    DotGrid,
+    Vignette,
+    Gradient,
+    /// ASCII-art text file; relative paths start in the user theme directory.
+    Image {
+        file: String,
+    },
+}
+
+fn default_stripe_width() -> usize {
+    2
+}
+
+fn default_skin_length() -> usize {
+    50
+}
+
+impl ThemeFile {
+    /// Fills in every `defs` and `theme` entry this file does not set itself.
+    fn inherit(&mut self, parent: ThemeFile) {
+        for (key, value) in parent.defs {
+            self.defs.entry(key).or_insert(value);
+        }
+        for (key, value) in parent.theme {
+            self.theme.entry(key).or_insert(value);
+        }
+        if self.snake_skin.is_none() {
+            self.snake_skin = parent.snake_skin;
+        }
+        if self.background.is_none() {
+            self.background = parent.background;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+enum ColorValue {
+    String(String),
+    Ansi(u8),
+    Variant {
+        #[serde(default)]
+        dark: Option<Box<ColorValue>>,
+        #[serde(default)]
+        light: Option<Box<ColorValue>>,
+    },
+}
+
+/// A theme that failed to load, with a message suitable for the player.
+#[derive(Debug)]
+struct ThemeLoadIssue {
+    id: String,
+    message: String,
+}
+
+fn insert_theme(
+    order: &mut Vec<String>,
+    by_id: &mut HashMap<String, Theme>,
+    id: String,
+    theme: Theme,
+) {
+    if !by_id.contains_key(&id) {
+        order.push(id.clone());
+    }
+    by_id.insert(id, theme);
+}
+
+fn merge_theme_dir(
+    path: &Path,
+    appearance: Appearance,
+    order: &mut Vec<String>,
+    by_id: &mut HashMap<String, Theme>,
+    sources: &mut HashMap<String, String>,
+    issues: &mut Vec<ThemeLoadIssue>,
+) {
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    let mut theme_paths: Vec<PathBuf> = Vec::new();
+    for entry_result in entries {
+        let entry = match entry_result {
+            Ok(entry) => entry,
+            Err(_) => continue,
+        };
+
+        let file_path = entry.path();
+        if is_json_file(&file_path) {
+            theme_paths.push(file_path);
+        }
+    }
+
+    theme_paths.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+
+    let mut batch = Vec::with_capacity(theme_paths.len());
+    for file_path in theme_paths {
+        let Some(id) = file_path
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .map(str::to_owned)
+        else {
+            continue;
+        };
+
+        match fs::read_to_string(&file_path) {
+            Ok(content) => batch.push(SourceEntry {
+                label: format!("theme file {}", file_path.display()),
+                id,
+                content,
+            }),
+            Err(error) => issues.push(ThemeLoadIssue {
+                message: format!("Failed to read theme file {}: {error}", file_path.display()),
+                id,
+            }),
+        }
+    }
+
+    merge_sources(batch, appearance, order, by_id, sources, issues);
+}
+
+fn merge_embedded_themes(
+    appearance: Appearance,
+    order: &mut Vec<String>,
+    by_id: &mut HashMap<String, Theme>,
+    sources: &mut HashMap<String, String>,
+    issues: &mut Vec<ThemeLoadIssue>,
+) {
+    let batch = BUILTIN_THEMES
+        .iter()
+        .map(|&(id, content)| SourceEntry {
+            id: id.to_owned(),
+            label: format!("built-in theme '{id}'"),
+            content: content.to_owned(),
+        })
+        .collect();
+
+    merge_sources(batch, appearance, order, by_id, sources, issues);
+}
+
+/// One theme source waiting to be resolved, with a label for messages.
+struct SourceEntry {
+    id: String,
+    label: String,
+    content: String,
+}
+
+/// Resolves a batch of theme sources.
+///
+/// Every entry is registered in `sources` before any is resolved, so a
+/// theme may extend one that sorts after it. Entries that fail to resolve
+/// are removed again, restoring any source they had replaced.
+fn merge_sources(
+    batch: Vec<SourceEntry>,
+    appearance: Appearance,
+    order: &mut Vec<String>,
+    by_id: &mut HashMap<String, Theme>,
+    sources: &mut HashMap<String, String>,
+    issues: &mut Vec<ThemeLoadIssue>,
+) {
+    let mut replaced = Vec::with_capacity(batch.len());
+    for entry in &batch {
+        replaced.push(sources.insert(entry.id.clone(), entry.content.clone()));
+    }
+
+    for (entry, previous) in batch.into_iter().zip(replaced) {
+        match parse_theme_with_sources(&entry.id, &entry.content, sources, appearance) {
+            Ok(theme) => insert_theme(order, by_id, entry.id, theme),
+            Err(error) => {
+                match previous {
+                    Some(previous) => sources.insert(entry.id.clone(), previous),
+                    None => sources.remove(&entry.id),
+                };
+                issues.push(ThemeLoadIssue {
+                    message: format!("Invalid {}: {error}", entry.label),
+                    id: entry.id,
+                });
+            }
+        }
+    }
+}
+
+/// A problem in a theme file.
+///
+/// Loading rejects invalid JSON and broken `extends` chains; the other
+/// problems fall back to default colors at runtime and are reported by
+/// `theme check`.
+#[derive(Debug, Error)]
+pub enum ThemeParseError {
+    #[error("json parse error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("{path}: unknown key")]
+    UnknownKey { path: String },
+    #[error("{path}: '{name}' is not a color, a defs entry or a theme key")]
+    UnresolvedReference { path: String, name: String },
+    #[error("{path}: reference cycle {}", .chain.join(" -> "))]
+    ReferenceCycle { path: String, chain: Vec<String> },
+    #[error("{path}: invalid hex color '{value}'")]
+    InvalidHex { path: String, value: String },
+    #[error("extends: '{id}' extends '{parent}', which is not a built-in or user theme")]
+    MissingParent { id: String, parent: String },
+    #[error("extends: inheritance cycle {}", .chain.join(" -> "))]
+    InheritanceCycle { chain: Vec<String> },
+    #[error("background.file: cannot read '{file}': {reason}")]
+    UnreadableImage { file: String, reason: String },
+}
+
+/// Parses a theme, looking up any `extends` ancestors in `sources`.
+fn parse_theme_with_sources(
+    id: &str,
+    raw: &str,
+    sources: &HashMap<String, String>,
+    appearance: Appearance,
+) -> Result<Theme, ThemeParseError> {
+    let parsed = flatten_theme_file(id, raw, sources)?;
+    Ok(resolve_theme(id, &parsed, appearance))
+}
+
+/// Parses `raw` and folds in every ancestor named through `extends`; the
+/// nearest file wins for each `defs` and `theme` entry. The name is never
+/// inherited.
+fn flatten_theme_file(
+    id: &str,
+    raw: &str,
+    sources: &HashMap<String, String>,
+) -> Result<ThemeFile, ThemeParseError> {
+    let mut file = serde_json::from_str::<ThemeFile>(raw)?;
+    let mut chain = vec![id.to_owned()];
+    let mut next = file.extends.clone();
+
+    while let Some(parent) = next {
+        if chain.contains(&parent) {
+            chain.push(parent);
+            return Err(ThemeParseError::InheritanceCycle { chain });
+        }
+        let Some(parent_raw) = sources.get(&parent) else {
+            return Err(ThemeParseError::MissingParent {
+                id: chain.pop().unwrap_or_default(),
+                parent,
+            });
+        };
+
+        let parent_file = serde_json::from_str::<ThemeFile>(parent_raw)?;
+        next = parent_file.extends.clone();
+        file.inherit(parent_file);
+        chain.push(parent);
+    }
+
+    Ok(file)
+}
+
+fn resolve_theme(id: &str, parsed: &ThemeFile, appearance: Appearance) -> Theme {
+    let dark = appearance == Appearance::Dark;
+    let fallback = fallback_theme();
+    let mut stack = Vec::new();
+    let ui_muted = resolve_token(parsed, "ui_muted", dark, &mut stack).unwrap_or(fallback.ui_muted);
+    let ui_bright_default = brighten_30_percent(ui_muted);
+
+    let field_bg = resolve_token(parsed, "field_bg", dark, &mut stack).unwrap_or(fallback.field_bg);
+    let field_bg_alt_default = match appearance {
+        Appearance::Dark => lighten_color(field_bg, 4),
+        Appearance::Light => darken_color(field_bg, 4),
+    };
+
+    Theme {
+        name: parsed.name.clone().unwrap_or_else(|| display_name(id)),
+        snake_head: resolve_token(parsed, "snake_head", dark, &mut stack)
+            .unwrap_or(fallback.snake_head),
+        snake_body: resolve_token(parsed, "snake_body", dark, &mut stack)
+            .unwrap_or(fallback.snake_body),
+        snake_tail: resolve_token(parsed, "snake_tail", dark, &mut stack)
+            .unwrap_or(fallback.snake_tail),
+        food: resolve_token(parsed, "food", dark, &mut stack).unwrap_or(fallback.food),
+        super_food: resolve_token(parsed, "super_food", dark, &mut stack)
+            .unwrap_or(fallback.super_food),
+        terminal_bg: resolve_token(parsed, "terminal_bg", dark, &mut stack)
+            .unwrap_or(fallback.terminal_bg),
+        field_bg,
+        ui_bg: resolve_token(parsed, "ui_bg", dark, &mut stack).unwrap_or(fallback.ui_bg),
+        ui_text: resolve_token(parsed, "ui_text", dark, &mut stack).unwrap_or(fallback.ui_text),
+        ui_accent: resolve_token(parsed, "ui_accent", dark, &mut stack)
+            .unwrap_or(fallback.ui_accent),
+        ui_muted,
+        ui_bright: resolve_token(parsed, "ui_bright", dark, &mut stack)
+            .unwrap_or(ui_bright_default),
+        field_bg_alt: resolve_token(parsed, "field_bg_alt", dark, &mut stack)
+            .unwrap_or(field_bg_alt_default),
+        snake_skin: resolve_skin(parsed, dark, &mut stack),
+        background: resolve_background(parsed),
+    }
+}
+
+/// Builds the background pattern. An image that cannot be read falls back to
+/// the checkerboard; `theme check` reports it.
+fn resolve_background(parsed: &ThemeFile) -> BackgroundPattern {
+    match &parsed.background {
+        None | Some(BackgroundFile::Checkerboard) => BackgroundPattern::Checkerboard,
+        Some(BackgroundFile::Plain) => BackgroundPattern::Plain,
+        Some(BackgroundFile::HorizontalStripes) => BackgroundPattern::HorizontalStripes,
+        Some(BackgroundFile::VerticalStripes) => BackgroundPattern::VerticalStripes,
+        Some(BackgroundFile::DotGrid) => BackgroundPattern::DotGrid,
+        Some(BackgroundFile::Vignette) => BackgroundPattern::Vignette,
+        Some(BackgroundFile::Gradient) => BackgroundPattern::Gradient,
+        Some(BackgroundFile::Image { file }) => {
+            let path = background_image_path(file);
+            match AsciiArt::load(&path) {
+                Ok(art) => BackgroundPattern::Image { path, art },
+                Err(_) => BackgroundPattern::Checkerboard,
+            }
+        }
+    }
+}
+
+/// Expands `~/` and places relative image paths in the user theme directory.
+fn background_image_path(file: &str) -> PathBuf {
+    if let Some(rest) = file.strip_prefix("~/")
+        && let Some(home) = dirs::home_dir()
+    {
+        return home.join(rest);
+    }
+    let path = PathBuf::from(file);
+    match user_theme_dir() {
+        Some(dir) if path.is_relative() => dir.join(path),
+        _ => path,
+    }
+}
+
+/// Resolves the skin's color stops, dropping any that do not resolve. A skin
+/// left without colors falls back to the default banding.
+fn resolve_skin(parsed: &ThemeFile, prefer_dark: bool, stack: &mut Vec<String>) -> SnakeSkin {
+    let Some(skin) = &parsed.snake_skin else {
+        return SnakeSkin::Banded;
+    };
+    let colors = skin
+        .colors()
+        .iter()
+        .filter_map(|value| resolve_value(parsed, value, prefer_dark, stack))
+        .collect::<Vec<_>>();
+    if colors.is_empty() {
+        return SnakeSkin::Banded;
+    }
+
+    match skin {
+        SkinFile::Banded => SnakeSkin::Banded,
+        SkinFile::Gradient { .. } => SnakeSkin::Gradient(colors),
+        SkinFile::Stripes { width, .. } => SnakeSkin::Stripes {
+            colors,
+            width: (*width).max(1),
+        },
+        SkinFile::Length { length, .. } => SnakeSkin::Length {
+            colors,
+            length: (*length).max(1),
+        },
+    }
+}
+
+/// Moves each RGB channel toward 255 by the given percentage (0–100).
+/// For named ANSI colors, applies a simple lightening mapping.
+fn lighten_color(color: Color, percent: u16) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => Color::Rgb(
+            lighten_channel(r, percent),
+            lighten_channel(g, percent),
+            lighten_channel(b, percent),
+        ),
+        // Named ANSI colors lack fine granularity — return unchanged so
+        // the checkerboard stays invisible rather than overly contrasty.
+        other => other,
+    }
+}
+
+fn lighten_channel(channel: u8, percent: u16) -> u8 {
+    let remaining = 255u16.saturating_sub(u16::from(channel));
+    let increase = (remaining * percent + 50) / 100;
+    (u16::from(channel) + increase).min(255) as u8
+}
+
+/// Moves each RGB channel toward 0 by the given percentage (0–100).
+/// Named ANSI colors are returned unchanged, as in [`lighten_color`].
+fn darken_color(color: Color, percent: u16) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => {
+            let darken = |channel: u8| (u16::from(channel) * (100 - percent.min(100)) / 100) as u8;
+            Color::Rgb(darken(r), darken(g), darken(b))
+        }
+        other => other,
+    }
+}
+
+fn brighten_30_percent(color: Color) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => Color::Rgb(
+            brighten_channel_30_percent(r),
+            brighten_channel_30_percent(g),
+            brighten_channel_30_percent(b),
+        ),
+        Color::Black => Color::DarkGray,
+        Color::DarkGray => Color::Gray,
+        Color::Gray => Color::White,
+        Color::White => Color::White,
+        other => other,
+    }
+}
+
+fn brighten_channel_30_percent(channel: u8) -> u8 {
+    let remaining = 255u16.saturating_sub(u16::from(channel));
+    let increase = (remaining * 30 + 50) / 100;
+    (u16::from(channel) + increase).min(255) as u8
+}
+
+fn resolve_token(
+    file: &ThemeFile,
+    token: &str,
+    prefer_dark: bool,
+    stack: &mut Vec<String>,
+) -> Option<Color> {
+    let value = file.theme.get(token)?;
+    resolve_value(file, value, prefer_dark, stack)
+}
+
+fn resolve_value(
+    file: &ThemeFile,
+    value: &ColorValue,
+    prefer_dark: bool,
+    stack: &mut Vec<String>,
+) -> Option<Color> {
+    match value {
+        ColorValue::String(s) => parse_color_string(file, s, prefer_dark, stack),
+        ColorValue::Ansi(code) => Some(Color::Indexed(*code)),
+        ColorValue::Variant { dark, light } => {
+            let preferred = if prefer_dark {
+                dark.as_deref()
+            } else {
+                light.as_deref()
+            };
+            let fallback = if prefer_dark {
+                light.as_deref()
+            } else {
+                dark.as_deref()
+            };
+
+            preferred
+                .and_then(|value| resolve_value(file, value, prefer_dark, stack))
+                .or_else(|| {
+                    fallback.and_then(|value| resolve_value(file, value, prefer_dark, stack))
+                })
+        }
+    }
+}
+
+fn parse_color_string(
+    file: &ThemeFile,
+    value: &str,
+    prefer_dark: bool,
+    stack: &mut Vec<String>,
+) -> Option<Color> {
+    if value.eq_ignore_ascii_case("none") || value.eq_ignore_ascii_case("reset") {
+        return Some(Color::Reset);
+    }
+
+    if let Some(color) = parse_hex_color(value) {
+        return Some(color);
+    }
+
+    if let Some(color) = parse_named_ansi_color(value) {
+        return Some(color);
+    }
+
+    if stack.iter().any(|seen| seen == value) {
+        return None;
+    }
+
+    let referenced = file.defs.get(value).or_else(|| file.theme.get(value))?;
+
+    stack.push(value.to_owned());
+    let resolved = resolve_value(file, referenced, prefer_dark, stack);
+    let _ = stack.pop();
+    resolved
+}
+
+/// Parses a single color as written in a theme file: a hex string, a named
+/// ANSI color, or `reset`. References to `defs` are not resolved.
+#[must_use]
+pub fn parse_color_value(value: &str) -> Option<Color> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("none") || value.eq_ignore_ascii_case("reset") {
+        return Some(Color::Reset);
+    }
+
+    parse_hex_color(value).or_else(|| parse_named_ansi_color(value))
+}
+
+/// Returns the color stored under `key`, one of [`THEME_KEYS`].
+#[must_use]
+pub fn theme_color(theme: &Theme, key: &str) -> Option<Color> {
+    Some(match key {
+        "snake_head" => theme.snake_head,
+        "snake_body" => theme.snake_body,
+        "snake_tail" => theme.snake_tail,
+        "food" => theme.food,
+        "super_food" => theme.super_food,
+        "terminal_bg" => theme.terminal_bg,
+        "field_bg" => theme.field_bg,
+        "ui_bg" => theme.ui_bg,
+        "ui_text" => theme.ui_text,
+        "ui_accent" => theme.ui_accent,
+        "ui_muted" => theme.ui_muted,
+        "ui_bright" => theme.ui_bright,
+        "field_bg_alt" => theme.field_bg_alt,
+        _ => return None,
+    })
+}
+
+/// Returns the theme field stored under `key`, one of [`THEME_KEYS`].
+pub fn theme_color_mut<'a>(theme: &'a mut Theme, key: &str) -> Option<&'a mut Color> {
+    Some(match key {
+        "snake_head" => &mut theme.snake_head,
+        "snake_body" => &mut theme.snake_body,
+        "snake_tail" => &mut theme.snake_tail,
+        "food" => &mut theme.food,
+        "super_food" => &mut theme.super_food,
+        "terminal_bg" => &mut theme.terminal_bg,
+        "field_bg" => &mut theme.field_bg,
+        "ui_bg" => &mut theme.ui_bg,
+        "ui_text" => &mut theme.ui_text,
+        "ui_accent" => &mut theme.ui_accent,
+        "ui_muted" => &mut theme.ui_muted,
+        "ui_bright" => &mut theme.ui_bright,
+        "field_bg_alt" => &mut theme.field_bg_alt,
+        _ => return None,
+    })
+}
+
+/// Formats `color` the way a theme file spells it.
+#[must_use]
+pub fn color_value(color: Color) -> String {
+    let name = match color {
+        Color::Reset => "reset",
+        Color::Black => "black",
+        Color::Red => "red",
+        Color::Green => "green",
+        Color::Yellow => "yellow",
+        Color::Blue => "blue",
+        Color::Magenta => "magenta",
+        Color::Cyan => "cyan",
+        Color::Gray => "gray",
+        Color::DarkGray => "darkgray",
+        Color::LightRed => "lightred",
+        Color::LightGreen => "lightgreen",
+        Color::LightYellow => "lightyellow",
+        Color::LightBlue => "lightblue",
+        Color::LightMagenta => "lightmagenta",
+        Color::LightCyan => "lightcyan",
+        Color::White => "white",
+        other => {
+            let (r, g, b) = color_to_rgb(other).unwrap_or_default();
+            return format!("#{r:02X}{g:02X}{b:02X}");
+        }
+    };
+    name.to_owned()
+}
+
+/// A theme file as written by [`theme_file_json`].
+#[derive(Serialize)]
+struct ThemeFileOut<'a> {
+    name: &'a str,
+    theme: ThemeColorsOut<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snake_skin: Option<SkinFile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    background: Option<BackgroundFile>,
+}
+
+/// The `theme` section with every key, in [`THEME_KEYS`] order.
+struct ThemeColorsOut<'a>(&'a Theme);
+
+impl Serialize for ThemeColorsOut<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(THEME_KEYS.len()))?;
+        for key in THEME_KEYS {
+            let color = theme_color(self.0, key).unwrap_or(Color::Reset);
+            map.serialize_entry(key, &color_value(color))?;
+        }
+        map.end()
+    }
+}
+
+/// Renders `theme` as a theme file with every key spelled out.
+pub(super) fn theme_file_json(theme: &Theme) -> serde_json::Result<String> {
+    let file = ThemeFileOut {
+        name: &theme.name,
+        theme: ThemeColorsOut(theme),
+        snake_skin: skin_file(&theme.snake_skin),
+        background: background_file(&theme.background),
+    };
+    let mut json = serde_json::to_string_pretty(&file)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Returns the `snake_skin` section for a non-default skin.
+fn skin_file(skin: &SnakeSkin) -> Option<SkinFile> {
+    let values = |colors: &[Color]| {
+        colors
+            .iter()
+            .map(|color| ColorValue::String(color_value(*color)))
+            .collect()
+    };
+    match skin {
+        SnakeSkin::Banded => None,
+        SnakeSkin::Gradient(colors) => Some(SkinFile::Gradient {
+            colors: values(colors),
+        }),
+        SnakeSkin::Stripes { colors, width } => Some(SkinFile::Stripes {
+            colors: values(colors),
+            width: *width,
+        }),
+        SnakeSkin::Length { colors, length } => Some(SkinFile::Length {
+            colors: values(colors),
+            length: *length,
+        }),
+    }
+}
+
+/// Returns the `background` section for a non-default background.
+fn background_file(background: &BackgroundPattern) -> Option<BackgroundFile> {
+    Some(match background {
+        BackgroundPattern::Checkerboard => return None,
+        BackgroundPattern::Plain => BackgroundFile::Plain,
+        BackgroundPattern::HorizontalStripes => BackgroundFile::HorizontalStripes,
+        BackgroundPattern::VerticalStripes => BackgroundFile::VerticalStripes,
+        BackgroundPattern::DotGrid => BackgroundFile::DotGrid,
+        BackgroundPattern::Vignette => BackgroundFile::Vignette,
+        BackgroundPattern::Gradient => BackgroundFile::Gradient,
+        BackgroundPattern::Image { path, .. } => BackgroundFile::Image {
+            file: path.to_string_lossy().into_owned(),
+        },
+    })
+}
+
+fn parse_named_ansi_color(value: &str) -> Option<Color> {
+    match value {
+        value if value.eq_ignore_ascii_case("black") => Some(Color::Black),
+        value if value.eq_ignore_ascii_case("red") => Some(Color::Red),
+        value if value.eq_ignore_ascii_case("green") => Some(Color::Green),
+        value if value.eq_ignore_ascii_case("yellow") => Some(Color::Yellow),
+        value if value.eq_ignore_ascii_case("blue") => Some(Color::Blue),
+        value if value.eq_ignore_ascii_case("magenta") => Some(Color::Magenta),
+        value if value.eq_ignore_ascii_case("cyan") => Some(Color::Cyan),
+        value if value.eq_ignore_ascii_case("white") => Some(Color::White),
+        value if value.eq_ignore_ascii_case("gray") || value.eq_ignore_ascii_case("grey") => {
+            Some(Color::Gray)
+        }
+        value
+            if value.eq_ignore_ascii_case("darkgray")
+                || value.eq_ignore_ascii_case("darkgrey")
+                || value.eq_ignore_ascii_case("dark_gray")
+                || value.eq_ignore_ascii_case("dark_grey")
+                || value.eq_ignore_ascii_case("dark-gray")
+                || value.eq_ignore_ascii_case("dark-grey") =>
+        {
+            Some(Color::DarkGray)
+        }
+        value
+            if value.eq_ignore_ascii_case("lightred")
+                || value.eq_ignore_ascii_case("light_red")
+                || value.eq_ignore_ascii_case("light-red") =>
+        {
+            Some(Color::LightRed)
+        }
+        value
+            if value.eq_ignore_ascii_case("lightgreen")
+                || value.eq_ignore_ascii_case("light_green")
+                || value.eq_ignore_ascii_case("light-green") =>
+        {
+            Some(Color::LightGreen)
+        }
+        value
+            if value.eq_ignore_ascii_case("lightyellow")
+                || value.eq_ignore_ascii_case("light_yellow")
+                || value.eq_ignore_ascii_case("light-yellow") =>
+        {
+            Some(Color::LightYellow)
+        }
+        value
+            if value.eq_ignore_ascii_case("lightblue")
+                || value.eq_ignore_ascii_case("light_blue")
+                || value.eq_ignore_ascii_case("light-blue") =>
+        {
+            Some(Color::LightBlue)
+        }
+        value
+            if value.eq_ignore_ascii_case("lightmagenta")
+                || value.eq_ignore_ascii_case("light_magenta")
+                || value.eq_ignore_ascii_case("light-magenta") =>
+        {
+            Some(Color::LightMagenta)
+        }
+        value
+            if value.eq_ignore_ascii_case("lightcyan")
+                || value.eq_ignore_ascii_case("light_cyan")
+                || value.eq_ignore_ascii_case("light-cyan") =>
+        {
+            Some(Color::LightCyan)
+        }
+        value
+            if value.eq_ignore_ascii_case("lightwhite")
+                || value.eq_ignore_ascii_case("light_white")
+                || value.eq_ignore_ascii_case("light-white") =>
+        {
+            Some(Color::White)
+        }
+        _ => None,
+    }
+}
+
+fn parse_hex_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+
+    let red = u8::from_str_radix(&hex[0..2], 16).ok()?;
+    let green = u8::from_str_radix(&hex[2..4], 16).ok()?;
+    let blue = u8::from_str_radix(&hex[4..6], 16).ok()?;
+
+    Some(Color::Rgb(red, green, blue))
+}
+
+/// Records the modification time and size of every JSON file in `path`.
+///
+/// Size is tracked alongside mtime so that rewrites within the filesystem's
+/// timestamp granularity are still noticed in most cases.
+fn scan_theme_dir(path: &Path) -> BTreeMap<PathBuf, (SystemTime, u64)> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return BTreeMap::new();
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|file_path| is_json_file(file_path))
+        .filter_map(|file_path| {
+            let metadata = fs::metadata(&file_path).ok()?;
+            let modified = metadata.modified().ok()?;
+            Some((file_path, (modified, metadata.len())))
+        })
+        .collect()
+}
+
+fn is_json_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+fn display_name(id: &str) -> String {
+    let mut output = String::new();
+    for (idx, part) in id.split(['-', '_']).enumerate() {
+        if idx > 0 {
+            output.push(' ');
+        }
+
+        let mut chars = part.chars();
+        if let Some(first) = chars.next() {
+            output.push(first.to_ascii_uppercase());
+            output.push_str(chars.as_str());
+        }
+    }
+    output
+}
+
+fn user_theme_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|config_dir| config_dir.join(USER_THEME_APP_DIR).join("themes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use ratatui::style::Color;
+
+    use super::{
+        Appearance, AppearancePreference, TerminalPalette, ThemeCatalog, ThemeParseError,
+        merge_theme_dir, parse_hex_color, parse_theme_with_sources,
+    };
+    use crate::background::{AsciiArt, BackgroundPattern};
+    use crate::config::{SnakeSkin, Theme};
+
+    fn parse_theme_from_str_result(
+        id: &str,
+        raw: &str,
+        appearance: Appearance,
+    ) -> Result<Theme, ThemeParseError> {
+        parse_theme_with_sources(id, raw, &HashMap::new(), appearance)
+    }
+
+    #[test]
+    fn parses_hex_color() {
+        assert_eq!(parse_hex_color("#AABBCC"), Some(Color::Rgb(170, 187, 204)));
+        assert_eq!(parse_hex_color("invalid"), None);
+    }
+
+    #[test]
+    fn resolves_defs_and_variants() {
+        let json = r##"
+        {
+          "defs": {
+            "bg": "#111111",
+            "panel": { "dark": "#222222", "light": "#eeeeee" },
+            "accent_ref": "#AA00AA"
+          },
+          "theme": {
+            "snake_head":  "accent_ref",
+            "snake_body":  "accent_ref",
+            "snake_tail":  "accent_ref",
+            "food":        "#FF0000",
+            "field_bg":    "bg",
+            "ui_bg":       "panel",
+            "ui_text":     "#00FF00",
+            "ui_accent":   "accent_ref",
+            "ui_muted":    "#888888"
+          }
+        }
+        "##;
+
+        let theme = parse_theme_from_str_result("custom", json, Appearance::Dark)
+            .expect("theme should parse");
+        assert_eq!(theme.field_bg, Color::Rgb(17, 17, 17));
+        assert_eq!(theme.ui_bg, Color::Rgb(34, 34, 34));
+        assert_eq!(theme.ui_accent, Color::Rgb(170, 0, 170));
+        assert_eq!(theme.ui_bright, Color::Rgb(172, 172, 172));
+    }
+
+    #[test]
+    fn light_appearance_selects_light_variants() {
+        let json = r##"
+        {
+          "defs": { "panel": { "dark": "#222222", "light": "#eeeeee" } },
+          "theme": {
+            "ui_bg":    "panel",
+            "field_bg": { "dark": "#000000", "light": "#ffffff" },
+            "food":     { "dark": "#ff0000" }
+          }
+        }
+        "##;
+
+        let theme = parse_theme_from_str_result("custom", json, Appearance::Light)
+            .expect("theme should parse");
+        assert_eq!(theme.ui_bg, Color::Rgb(238, 238, 238));
+        assert_eq!(theme.field_bg, Color::Rgb(255, 255, 255));
+        // A missing light variant falls back to the dark one.
+        assert_eq!(theme.food, Color::Rgb(255, 0, 0));
+    }
+
+    #[test]
+    fn catalog_re_resolves_variants_and_keeps_selection() {
+        let mut catalog = ThemeCatalog::load(Appearance::Dark);
+        assert!(catalog.select_by_id("gruvbox"));
+        let dark_bg = catalog.current_theme().terminal_bg;
+
+        catalog.set_appearance(Appearance::Light);
+        assert_eq!(catalog.appearance(), Appearance::Light);
+        assert_eq!(catalog.current_id(), "gruvbox");
+        assert_eq!(
+            catalog.current_theme().terminal_bg,
+            Color::Rgb(251, 241, 199)
+        );
+
+        catalog.set_appearance(Appearance::Dark);
+        assert_eq!(catalog.current_theme().terminal_bg, dark_bg);
+    }
+
+    #[test]
+    fn appearance_detection_sources_are_parsed() {
+        assert_eq!(
+            Appearance::from_background((250, 250, 245)),
+            Appearance::Light
+        );
+        assert_eq!(Appearance::from_background((30, 30, 46)), Appearance::Dark);
+        assert_eq!(Appearance::from_colorfgbg("15;0"), Some(Appearance::Dark));
+        assert_eq!(
+            Appearance::from_colorfgbg("0;default;15"),
+            Some(Appearance::Light)
+        );
+        assert_eq!(Appearance::from_colorfgbg("garbage"), None);
+        assert_eq!(AppearancePreference::Auto.resolve(None), Appearance::Dark);
+        assert_eq!(
+            AppearancePreference::Light.resolve(Some(Appearance::Dark)),
+            Appearance::Light
+        );
+    }
+
+    #[test]
+    fn none_maps_to_terminal_default() {
+        let json = r##"
+        {
+          "theme": {
+            "snake_head":  "#00CC00",
+            "snake_body":  "#00CC00",
+            "snake_tail":  "#00CC00",
+            "food":        "#FF0000",
+            "field_bg":    "none",
+            "ui_bg":       "none",
+            "ui_text":     "#00FF00",
+            "ui_accent":   "#00CC00",
+            "ui_muted":    "#777777"
+          }
+        }
+        "##;
+
+        let theme = parse_theme_from_str_result("system", json, Appearance::Dark)
+            .expect("theme should parse");
+        assert_eq!(theme.field_bg, Color::Reset);
+        assert_eq!(theme.ui_bg, Color::Reset);
+    }
+
+    #[test]
+    fn reset_alias_maps_to_terminal_default() {
+        let json = r##"
+        {
+          "theme": {
+            "snake_head":  "#00CC00",
+            "snake_body":  "#00CC00",
+            "snake_tail":  "#00CC00",
+            "food":        "#FF0000",
+            "field_bg":    "reset",
+            "ui_bg":       "reset",
+            "ui_text":     "#00FF00",
+            "ui_accent":   "#00CC00",
+            "ui_muted":    "#777777"
+          }
+        }
+        "##;
+
+        let theme = parse_theme_from_str_result("system", json, Appearance::Dark)
+            .expect("theme should parse");
+        assert_eq!(theme.field_bg, Color::Reset);
+        assert_eq!(theme.ui_bg, Color::Reset);
+    }
+
+    #[test]
+    fn named_ansi_colors_are_supported() {
+        let json = r##"
+        {
+          "theme": {
+            "snake_head":  "red",
+            "snake_body":  "light_green",
+            "snake_tail":  "dark-gray",
+            "food":        "yellow",
+            "field_bg":    "black",
+            "ui_bg":       "blue",
+            "ui_text":     "white",
+            "ui_accent":   "magenta",
+            "ui_muted":    "cyan"
+          }
+        }
+        "##;
+
+        let theme = parse_theme_from_str_result("named", json, Appearance::Dark)
+            .expect("theme should parse");
+        assert_eq!(theme.snake_head, Color::Red);
+        assert_eq!(theme.snake_body, Color::LightGreen);
+        assert_eq!(theme.snake_tail, Color::DarkGray);
+        assert_eq!(theme.food, Color::Yellow);
+        assert_eq!(theme.field_bg, Color::Black);
+        assert_eq!(theme.ui_bg, Color::Blue);
+        assert_eq!(theme.ui_text, Color::White);
+        assert_eq!(theme.ui_accent, Color::Magenta);
+        assert_eq!(theme.ui_muted, Color::Cyan);
+    }
+
+    #[test]
+    fn explicit_ui_bright_overrides_default() {
+        let json = r##"
+        {
+          "theme": {
+            "snake_head":  "#00CC00",
+            "snake_body":  "#00CC00",
+            "snake_tail":  "#00CC00",
+            "food":        "#FF0000",
+            "field_bg":    "#000000",
+            "ui_bg":       "#111111",
+            "ui_text":     "#00FF00",
+            "ui_accent":   "#00CC00",
+            "ui_muted":    "#202020",
+            "ui_bright":   "#123456"
+          }
+        }
+        "##;
+
+        let theme = parse_theme_from_str_result("custom", json, Appearance::Dark)
+            .expect("theme should parse");
+        assert_eq!(theme.ui_bright, Color::Rgb(18, 52, 86));
+    }
+
+    #[test]
+    fn merge_theme_dir_sorts_by_filename() {
+        let dir = unique_test_dir("sorted");
+        fs::create_dir_all(&dir).expect("test directory should be creatable");
+
+        write_theme_file(&dir.join("z-last.json"), "#00AA00");
+        write_theme_file(&dir.join("a-first.json"), "#AA0000");
+
+        let mut order = Vec::new();
+        let mut by_id = HashMap::new();
+        merge_theme_dir(
+            &dir,
+            Appearance::Dark,
+            &mut order,
+            &mut by_id,
+            &mut HashMap::new(),
+            &mut Vec::new(),
+        );
+
+        assert_eq!(order, vec!["a-first", "z-last"]);
+        cleanup_test_dir(&dir);
+    }
+
+    #[test]
+    fn later_directory_overrides_same_theme_id() {
+        let low_dir = unique_test_dir("low");
+        let high_dir = unique_test_dir("high");
+        fs::create_dir_all(&low_dir).expect("low-priority dir should be creatable");
+        fs::create_dir_all(&high_dir).expect("high-priority dir should be creatable");
+
+        write_theme_file(&low_dir.join("dup.json"), "#112233");
+        write_theme_file(&high_dir.join("dup.json"), "#AABBCC");
+
+        let mut order = Vec::new();
+        let mut by_id = HashMap::new();
+        merge_theme_dir(
+            &low_dir,
+            Appearance::Dark,
+            &mut order,
+            &mut by_id,
+            &mut HashMap::new(),
+            &mut Vec::new(),
+        );
+        merge_theme_dir(
+            &high_dir,
+            Appearance::Dark,
+            &mut order,
+            &mut by_id,
+            &mut HashMap::new(),
+            &mut Vec::new(),
+        );
+
+        assert_eq!(order, vec!["dup"]);
+        let theme = by_id
+            .get("dup")
+            .expect("theme should be present after override");
+        assert_eq!(theme.snake_head, Color::Rgb(170, 187, 204));
+
+        cleanup_test_dir(&low_dir);
+        cleanup_test_dir(&high_dir);
+    }
+
+    #[test]
+    fn reload_picks_up_edits_and_keeps_selection() {
+        let dir = unique_test_dir("reload");
+        fs::create_dir_all(&dir).expect("test directory should be creatable");
+        write_theme_file(&dir.join("live.json"), "#112233");
+
+        let mut catalog = ThemeCatalog::load_with_user_dir(Appearance::Dark, Some(dir.clone()));
+        assert!(catalog.select_by_id("live"));
+        assert!(!catalog.reload_if_changed());
+
+        // A longer file guarantees the change is seen even on coarse mtimes.
+        write_theme_file(&dir.join("live.json"), "#AABBCC");
+        let mut raw = fs::read_to_string(dir.join("live.json")).expect("theme file should exist");
+        raw.push('\n');
+        fs::write(dir.join("live.json"), raw).expect("theme file should be writable");
+        write_theme_file(&dir.join("a-new.json"), "#445566");
+        assert!(catalog.reload_if_changed());
+        assert_eq!(catalog.current_id(), "live");
+        assert_eq!(
+            catalog.current_theme().snake_head,
+            Color::Rgb(170, 187, 204)
+        );
+        assert!(catalog.select_by_id("a-new"));
+        assert!(catalog.select_by_id("live"));
+
+        fs::write(dir.join("live.json"), "{ broken").expect("theme file should be writable");
+        assert!(catalog.reload_if_changed());
+        assert_eq!(catalog.current_id(), "live");
+        assert_eq!(
+            catalog.current_theme().snake_head,
+            Color::Rgb(170, 187, 204)
+        );
+        let warnings = catalog.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("live.json"));
+        assert!(catalog.take_warnings().is_empty());
+
+        cleanup_test_dir(&dir);
+    }
+
+    #[test]
+    fn saved_theme_is_written_and_selected() {
+        let dir = unique_test_dir("save");
+        let mut catalog = ThemeCatalog::load_with_user_dir(Appearance::Dark, Some(dir.clone()));
+        let mut theme = catalog.current_theme().clone();
+        theme.name = "My Ember".to_owned();
+        theme.snake_head = Color::Rgb(1, 2, 3);
+        theme.terminal_bg = Color::Reset;
+        theme.ui_muted = Color::DarkGray;
+        theme.snake_skin = SnakeSkin::Stripes {
+            colors: vec![Color::Rgb(9, 9, 9), Color::Red],
+            width: 3,
+        };
+        theme.background = BackgroundPattern::DotGrid;
+
+        let id = catalog
+            .save_user_theme(&theme)
+            .expect("theme should be saved");
+        assert_eq!(id, "my-ember");
+        assert!(dir.join("my-ember.json").is_file());
+        assert_eq!(catalog.current_id(), "my-ember");
+        assert_eq!(catalog.current_theme(), &theme);
+        assert!(!catalog.reload_if_changed());
+        assert!(catalog.take_warnings().is_empty());
+
+        cleanup_test_dir(&dir);
+    }
+
+    #[test]
+    fn extends_inherits_defs_and_tokens_from_parent() {
+        let sources = HashMap::from([
+            (
+                "base".to_owned(),
+                r##"{ "name": "Base", "defs": { "leaf": "#00CC00" },
+                   "theme": { "snake_head": "leaf", "food": "#FF0000" } }"##
+                    .to_owned(),
+            ),
+            (
+                "middle".to_owned(),
+                r##"{ "extends": "base", "theme": { "food": "#0000FF" } }"##.to_owned(),
+            ),
+        ]);
+        let child = r##"{ "extends": "middle", "defs": { "leaf": "#123456" } }"##;
+
+        let theme = parse_theme_with_sources("my-child", child, &sources, Appearance::Dark)
+            .expect("inherited theme should parse");
+        assert_eq!(theme.name, "My Child");
+        assert_eq!(theme.snake_head, Color::Rgb(18, 52, 86));
+        assert_eq!(theme.food, Color::Rgb(0, 0, 255));
+    }
+
+    #[test]
+    fn extends_reports_missing_parents_and_cycles() {
+        let sources = HashMap::from([
+            ("a".to_owned(), r#"{ "extends": "b" }"#.to_owned()),
+            ("b".to_owned(), r#"{ "extends": "a" }"#.to_owned()),
+        ]);
+
+        let missing = parse_theme_with_sources(
+            "child",
+            r#"{ "extends": "gone" }"#,
+            &sources,
+            Appearance::Dark,
+        );
+        assert!(matches!(
+            missing,
+            Err(ThemeParseError::MissingParent { ref id, ref parent }) if id == "child" && parent == "gone"
+        ));
+
+        let cycle = parse_theme_with_sources("a", &sources["a"], &sources, Appearance::Dark);
+        assert!(matches!(
+            cycle,
+            Err(ThemeParseError::InheritanceCycle { ref chain }) if chain == &["a", "b", "a"]
+        ));
+    }
+
+    #[test]
+    fn snake_skin_styles_resolve_references_and_defaults() {
+        let theme = parse_theme_from_str_result(
+            "skin",
+            r##"{ "defs": { "moss": { "dark": "#00AA00", "light": "#004400" } },
+                 "theme": { "snake_head": "#FFFFFF" },
+                 "snake_skin": { "style": "gradient", "colors": ["snake_head", "moss", "oops"] } }"##,
+            Appearance::Light,
+        )
+        .expect("theme should parse");
+        assert_eq!(
+            theme.snake_skin,
+            SnakeSkin::Gradient(vec![Color::Rgb(255, 255, 255), Color::Rgb(0, 68, 0)])
+        );
+
+        let theme = parse_theme_from_str_result(
+            "skin",
+            r##"{ "snake_skin": { "style": "stripes", "colors": ["red", "#000000"] } }"##,
+            Appearance::Dark,
+        )
+        .expect("theme should parse");
+        assert_eq!(
+            theme.snake_skin,
+            SnakeSkin::Stripes {
+                colors: vec![Color::Red, Color::Rgb(0, 0, 0)],
+                width: 2,
+            }
+        );
+
+        let theme = parse_theme_from_str_result(
+            "skin",
+            r##"{ "snake_skin": { "style": "length", "colors": [], "length": 10 } }"##,
+            Appearance::Dark,
+        )
+        .expect("theme should parse");
+        assert_eq!(theme.snake_skin, SnakeSkin::Banded);
+
+        assert!(
+            parse_theme_from_str_result(
+                "skin",
+                r#"{ "snake_skin": { "style": "plaid", "colors": [] } }"#,
+                Appearance::Dark,
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn background_patterns_parse_and_inherit() {
+        let sources = HashMap::from([(
+            "base".to_owned(),
+            r#"{ "background": { "pattern": "vertical_stripes" } }"#.to_owned(),
+        )]);
+        let parse = |raw: &str| parse_theme_with_sources("bg", raw, &sources, Appearance::Dark);
+
+        let theme =
+            parse(r#"{ "background": { "pattern": "dot_grid" } }"#).expect("theme should parse");
+        assert_eq!(theme.background, BackgroundPattern::DotGrid);
+        let theme = parse("{}").expect("theme should parse");
+        assert_eq!(theme.background, BackgroundPattern::Checkerboard);
+        let theme = parse(r#"{ "extends": "base" }"#).expect("theme should parse");
+        assert_eq!(theme.background, BackgroundPattern::VerticalStripes);
+        assert!(parse(r#"{ "background": { "pattern": "moire" } }"#).is_err());
+    }
+
+    #[test]
+    fn background_image_loads_art_or_falls_back_to_checkerboard() {
+        let dir = unique_test_dir("background-image");
+        fs::create_dir_all(&dir).expect("test dir should be created");
+        let art_path = dir.join("art.txt");
+        fs::write(&art_path, "@.\n .\n").expect("art should be written");
+
+        let raw = format!(
+            r#"{{ "background": {{ "pattern": "image", "file": {} }} }}"#,
+            serde_json::to_string(&art_path.to_string_lossy()).expect("path should encode")
+        );
+        let theme =
+            parse_theme_from_str_result("art", &raw, Appearance::Dark).expect("theme should parse");
+        assert_eq!(
+            theme.background,
+            BackgroundPattern::Image {
+                path: art_path.clone(),
+                art: AsciiArt::parse("@.\n .\n"),
+            }
+        );
+
+        let missing = raw.replace("art.txt", "missing.txt");
+        let theme = parse_theme_from_str_result("art", &missing, Appearance::Dark)
+            .expect("theme should parse");
+        assert_eq!(theme.background, BackgroundPattern::Checkerboard);
+
+        cleanup_test_dir(&dir);
+    }
+
+    #[test]
+    fn snake_skin_is_inherited_through_extends() {
+        let sources = HashMap::from([(
+            "base".to_owned(),
+            r##"{ "snake_skin": { "style": "length", "colors": ["#000000", "#FFFFFF"] } }"##
+                .to_owned(),
+        )]);
+        let theme = parse_theme_with_sources(
+            "child",
+            r#"{ "extends": "base" }"#,
+            &sources,
+            Appearance::Dark,
+        )
+        .expect("theme should parse");
+        assert_eq!(
+            theme.snake_skin,
+            SnakeSkin::Length {
+                colors: vec![Color::Rgb(0, 0, 0), Color::Rgb(255, 255, 255)],
+                length: 50,
+            }
+        );
+    }
+
+    #[test]
+    fn user_theme_can_extend_builtin_and_later_user_theme() {
+        let dir = unique_test_dir("extends");
+        fs::create_dir_all(&dir).expect("test directory should be creatable");
+        fs::write(
+            dir.join("a-tweak.json"),
+            r##"{ "extends": "z-base", "theme": { "food": "#0000FF" } }"##,
+        )
+        .expect("theme file should be writable");
+        fs::write(
+            dir.join("z-base.json"),
+            r##"{ "extends": "nord", "theme": { "snake_head": "#123456" } }"##,
+        )
+        .expect("theme file should be writable");
+        fs::write(dir.join("orphan.json"), r#"{ "extends": "missing" }"#)
+            .expect("theme file should be writable");
+
+        let mut catalog = ThemeCatalog::load_with_user_dir(Appearance::Dark, Some(dir.clone()));
+        let nord_field_bg = catalog
+            .items()
+            .iter()
+            .find(|item| item.id == "nord")
+            .map(|item| item.theme.field_bg)
+            .expect("nord is built in");
+
+        assert!(catalog.select_by_id("a-tweak"));
+        assert_eq!(catalog.current_theme().food, Color::Rgb(0, 0, 255));
+        assert_eq!(catalog.current_theme().snake_head, Color::Rgb(18, 52, 86));
+        assert_eq!(catalog.current_theme().field_bg, nord_field_bg);
+        assert!(!catalog.select_by_id("orphan"));
+        let warnings = catalog.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("'missing'"));
+
+        cleanup_test_dir(&dir);
+    }
+
+    #[test]
+    fn terminal_palette_becomes_rgb_theme() {
+        let mut ansi = [None; 16];
+        for (slot, color) in ansi.iter_mut().enumerate().take(8) {
+            *color = Some((slot as u8 * 30, 100, 200));
+        }
+        let palette = TerminalPalette {
+            background: Some((0x10, 0x10, 0x18)),
+            foreground: Some((0xE0, 0xE0, 0xE0)),
+            ansi,
+            ..TerminalPalette::default()
+        };
+
+        let mut catalog = ThemeCatalog::load_with_user_dir(Appearance::Dark, None);
+        let selected = catalog.current_id().to_owned();
+        assert!(catalog.add_terminal_theme(&palette));
+        assert_eq!(catalog.current_id(), selected);
+
+        assert!(catalog.select_by_id("terminal"));
+        let theme = catalog.current_theme();
+        assert_eq!(theme.name, "Terminal Palette");
+        assert_eq!(theme.field_bg, Color::Rgb(0x10, 0x10, 0x18));
+        assert_eq!(theme.food, Color::Rgb(30, 100, 200));
+        assert!(matches!(theme.field_bg_alt, Color::Rgb(..)));
+        assert_ne!(theme.field_bg_alt, theme.field_bg);
+
+        assert!(!catalog.add_terminal_theme(&TerminalPalette::default()));
+    }
+
+    fn write_theme_file(path: &PathBuf, color: &str) {
+        let raw = format!(
+            "{{\"theme\":{{\"snake_head\":\"{color}\",\"snake_body\":\"{color}\",\"snake_tail\":\"{color}\",\"food\":\"#FF0000\",\"terminal_bg\":\"#000000\",\"field_bg\":\"#000000\",\"ui_bg\":\"#111111\",\"ui_text\":\"#FFFFFF\",\"ui_accent\":\"{color}\",\"ui_muted\":\"#777777\"}}}}"
+        );
+        fs::write(path, raw).expect("theme file should be writable");
+    }
+
+    fn unique_test_dir(label: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after epoch")
+            .as_nanos();
+
+        std::env::temp_dir()
+            .join("snake-theme-tests")
+            .join(format!("{label}-{nanos}"))
+    }
+
+    fn cleanup_test_dir(path: &PathBuf) {
+        let _ = fs::remove_dir_all(path);
+    }
+}
@@ -1,14 +1,14 @@
 use std::time::{Duration, Instant};
 
+use ratatui::buffer::Buffer;
 use ratatui::layout::{Alignment, Constraint, Layout, Rect};
 use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::Paragraph;
-use ratatui::Frame;
+use ratatui::widgets::{Paragraph, Widget};
 
-use crate::config::{glyphs, Theme, GLYPH_MARKER_SQUARE, HUD_BOTTOM_MARGIN_Y, PLAY_AREA_MARGIN_X};
+use crate::color::color_to_rgb;
+use crate::config::{GLYPH_MARKER_SQUARE, HUD_BOTTOM_MARGIN_Y, PLAY_AREA_MARGIN_X, Theme, glyphs};
 use crate::game::GameState;
-use crate::platform::Platform;
 
 const HUD_INNER_MARGIN_X: u16 = 1;
 const VALUE_FLASH_HOLD_DURATION: Duration = Duration::from_secs(1);
@@ -16,6 +16,31 @@ const VALUE_FLASH_FADE_DURATION: Duration = Duration::from_secs(2);
 const VALUE_FLASH_DURATION: Duration =
     Duration::from_secs(VALUE_FLASH_HOLD_DURATION.as_secs() + VALUE_FLASH_FADE_DURATION.as_secs());
 
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+/// Transient message shown across the top edge of the play area.
+#[derive(Debug, Clone)]
+pub struct HudToast {
+    pub message: String,
+    pub shown_at: Instant,
+}
+
+impl HudToast {
+    #[must_use]
+    pub fn new(message: impl Into<String>, now: Instant) -> Self {
+        Self {
+            message: message.into(),
+            shown_at: now,
+        }
+    }
+
+    /// Returns true once the toast has been visible for its full duration.
+    #[must_use]
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.shown_at) >= TOAST_DURATION
+    }
+}
+
 /// Per-value flash timestamps for HUD value transitions.
 #[derive(Debug, Clone, Copy, Default)]
 pub struct HudValueFlash {
@@ -30,6 +55,27 @@ pub struct HudValueFlash {
     pub coverage_changed_at: Option<Instant>,
 }
 
+impl HudValueFlash {
+    /// Returns true while any value is still flashing and needs redraws.
+    #[must_use]
+    pub fn is_active(&self, now: Instant) -> bool {
+        [
+            self.length_changed_at,
+            self.level_changed_at,
+            self.score_changed_at,
+            self.high_score_changed_at,
+            self.dimensions_changed_at,
+            self.food_count_changed_at,
+            self.next_points_changed_at,
+            self.bonus_multiplier_changed_at,
+            self.coverage_changed_at,
+        ]
+        .into_iter()
+        .flatten()
+        .any(|changed_at| now.saturating_duration_since(changed_at) < VALUE_FLASH_DURATION)
+    }
+}
+
 /// Supplemental values displayed by the HUD rows.
 #[derive(Debug, Clone)]
 pub struct HudInfo<'a> {
@@ -44,19 +90,21 @@ pub struct HudInfo<'a> {
     pub now: Instant,
     /// Last-change timestamps for HUD values.
     pub value_flash: HudValueFlash,
+    /// Active toast, if any; expired toasts are not drawn.
+    pub toast: Option<&'a HudToast>,
 }
 
 /// Renders the two-line HUD and returns the remaining play area above it.
 #[must_use]
-pub fn render_hud(
-    frame: &mut Frame<'_>,
-    area: Rect,
-    state: &GameState,
-    _platform: Platform,
-    info: &HudInfo<'_>,
-) -> Rect {
+pub fn render_hud(buf: &mut Buffer, area: Rect, state: &GameState, info: &HudInfo<'_>) -> Rect {
     let debug_height = u16::from(info.debug);
-    let [play_area, score_area, status_area, debug_area, bottom_margin] = Layout::vertical([
+    let [
+        play_area,
+        score_area,
+        status_area,
+        debug_area,
+        bottom_margin,
+    ] = Layout::vertical([
         Constraint::Min(0),
         Constraint::Length(1),
         Constraint::Length(1),
@@ -78,53 +126,49 @@ pub fn render_hud(
     // paragraph that does not set an explicit bg, and the unused left quarter
     // of the status row would remain terminal_bg.
     let hud_bg = Style::default().bg(info.theme.field_bg);
-    frame.render_widget(Paragraph::new("").style(hud_bg), score_band);
-    frame.render_widget(Paragraph::new("").style(hud_bg), status_band);
+    Paragraph::new("").style(hud_bg).render(score_band, buf);
+    Paragraph::new("").style(hud_bg).render(status_band, buf);
 
     // Top status line: Length | Level | Score | Hi
-    frame.render_widget(
-        Paragraph::new(top_info_line(
-            state.snake.len(),
-            state.speed_level,
-            state.score,
-            info.high_score,
-            info.game_over_reference_high_score,
-            usize::from(score_area.width),
-            info.theme.ui_accent,
-            info.theme.ui_bright,
-            info.theme.ui_muted,
-            info.value_flash,
-            info.now,
-        ))
-        .alignment(Alignment::Right)
-        .style(Style::default().fg(Color::DarkGray)),
-        score_area,
-    );
+    Paragraph::new(top_info_line(
+        state.snake.len(),
+        state.speed_level,
+        state.score,
+        info.high_score,
+        info.game_over_reference_high_score,
+        usize::from(score_area.width),
+        info.theme.ui_accent,
+        info.theme.ui_bright,
+        info.theme.ui_muted,
+        info.value_flash,
+        info.now,
+    ))
+    .alignment(Alignment::Right)
+    .style(Style::default().fg(Color::DarkGray))
+    .render(score_area, buf);
 
     // Bottom status line: dimensions, food count, next points, bonus multiplier
     let dimensions_text = format!("{}x{}", state.bounds().width, state.bounds().height);
     let food_count_text = state.calculated_food_count().to_string();
     let next_food_points_text = state.ordinary_food_projected_points().to_string();
     let bonus_multiplier_text = format!("{:.2}x", state.ordinary_food_projected_multiplier());
-    frame.render_widget(
-        Paragraph::new(bottom_info_line(
-            dimensions_text.as_str(),
-            food_count_text.as_str(),
-            next_food_points_text.as_str(),
-            bonus_multiplier_text.as_str(),
-            info.theme.food,
-            info.theme.ui_muted,
-            info.theme.ui_accent,
-            info.value_flash,
-            info.now,
-        ))
-        .alignment(Alignment::Right)
-        .style(Style::default().fg(Color::DarkGray)),
-        status_area,
-    );
+    Paragraph::new(bottom_info_line(
+        dimensions_text.as_str(),
+        food_count_text.as_str(),
+        next_food_points_text.as_str(),
+        bonus_multiplier_text.as_str(),
+        info.theme.food,
+        info.theme.ui_muted,
+        info.theme.ui_accent,
+        info.value_flash,
+        info.now,
+    ))
+    .alignment(Alignment::Right)
+    .style(Style::default().fg(Color::DarkGray))
+    .render(status_area, buf);
 
     if info.debug {
-        frame.render_widget(Paragraph::new("").style(hud_bg), debug_band);
+        Paragraph::new("").style(hud_bg).render(debug_band, buf);
         let debug_width = bottom_info_width(
             dimensions_text.as_str(),
             food_count_text.as_str(),
@@ -136,31 +180,27 @@ pub fn render_hud(
             Layout::horizontal([Constraint::Min(0), Constraint::Length(debug_width)])
                 .areas(debug_area);
 
-        frame.render_widget(
-            Paragraph::new(Line::from(info.debug_line.as_str()))
-                .alignment(Alignment::Left)
-                .style(Style::default().fg(Color::DarkGray)),
-            debug_left,
-        );
-        frame.render_widget(
-            Paragraph::new(bottom_info_line(
-                dimensions_text.as_str(),
-                food_count_text.as_str(),
-                next_food_points_text.as_str(),
-                bonus_multiplier_text.as_str(),
-                info.theme.food,
-                info.theme.ui_muted,
-                info.theme.ui_accent,
-                info.value_flash,
-                info.now,
-            ))
-            .alignment(Alignment::Right)
-            .style(Style::default().fg(Color::DarkGray)),
-            debug_right,
-        );
+        Paragraph::new(Line::from(info.debug_line.as_str()))
+            .alignment(Alignment::Left)
+            .style(Style::default().fg(Color::DarkGray))
+            .render(debug_left, buf);
+        Paragraph::new(bottom_info_line(
+            dimensions_text.as_str(),
+            food_count_text.as_str(),
+            next_food_points_text.as_str(),
+            bonus_multiplier_text.as_str(),
+            info.theme.food,
+            info.theme.ui_muted,
+            info.theme.ui_accent,
+            info.value_flash,
+            info.now,
+        ))
+        .alignment(Alignment::Right)
+        .style(Style::default().fg(Color::DarkGray))
+        .render(debug_right, buf);
     }
 
-    render_hud_bottom_margin(frame, bottom_margin, info.theme);
+    render_hud_bottom_margin(buf, bottom_margin, info.theme);
 
     play_area
 }
@@ -175,19 +215,19 @@ fn inset_horizontal(area: Rect, margin: u16) -> Rect {
     }
 }
 
-fn render_hud_bottom_margin(frame: &mut Frame<'_>, bottom_margin: Rect, theme: &Theme) {
+fn render_hud_bottom_margin(buf: &mut Buffer, bottom_margin: Rect, theme: &Theme) {
     let margin_band = inset_horizontal(bottom_margin, PLAY_AREA_MARGIN_X);
     let style = Style::default().fg(theme.field_bg).bg(theme.terminal_bg);
     let half_upper = glyphs().half_upper;
-    let buffer = frame.buffer_mut();
 
     for y in margin_band.y..margin_band.bottom() {
         for x in margin_band.x..margin_band.right() {
-            buffer.set_string(x, y, half_upper, style);
+            buf.set_string(x, y, half_upper, style);
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn top_info_line(
     length: usize,
     level: u32,
@@ -296,6 +336,7 @@ fn top_info_width(
     width + format!("{high_score_label}: {high_score}").chars().count()
 }
 
+#[allow(clippy::too_many_arguments)]
 fn bottom_info_line<'a>(
     dimensions: &'a str,
     food_count: &'a str,
@@ -364,6 +405,31 @@ fn flash_color(base: Color, accent: Color, changed_at: Option<Instant>, now: Ins
     blend_color(accent, base, ease_out_cubic(t))
 }
 
+/// Draws `toast` centered on the top row of `area`, truncated to fit.
+pub fn render_toast(buf: &mut Buffer, area: Rect, toast: &HudToast, theme: &Theme, now: Instant) {
+    if toast.is_expired(now) || area.height == 0 || area.width < 4 {
+        return;
+    }
+
+    let max_chars = usize::from(area.width - 2);
+    let mut text: String = toast.message.chars().take(max_chars).collect();
+    if toast.message.chars().count() > max_chars {
+        text.pop();
+        text.push('…');
+    }
+    let width = text.chars().count() as u16 + 2;
+    let row = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y,
+        width,
+        height: 1,
+    };
+
+    Paragraph::new(format!(" {text} "))
+        .style(Style::default().fg(theme.ui_bright).bg(theme.ui_bg))
+        .render(row, buf);
+}
+
 fn ease_out_cubic(t: f32) -> f32 {
     let t = t.clamp(0.0, 1.0);
     1.0 - (1.0 - t).powi(3)
@@ -389,29 +455,6 @@ fn lerp_u8(from: u8, to: u8, t: f32) -> u8 {
     ((from as f32) + ((to as f32) - (from as f32)) * t).round() as u8
 }
 
-fn color_to_rgb(color: Color) -> Option<(u8, u8, u8)> {
-    match color {
-        Color::Black => Some((0, 0, 0)),
-        Color::Red => Some((205, 49, 49)),
-        Color::Green => Some((13, 188, 121)),
-        Color::Yellow => Some((229, 229, 16)),
-        Color::Blue => Some((36, 114, 200)),
-        Color::Magenta => Some((188, 63, 188)),
-        Color::Cyan => Some((17, 168, 205)),
-        Color::Gray => Some((229, 229, 229)),
-        Color::DarkGray => Some((102, 102, 102)),
-        Color::LightRed => Some((241, 76, 76)),
-        Color::LightGreen => Some((35, 209, 139)),
-        Color::LightYellow => Some((245, 245, 67)),
-        Color::LightBlue => Some((59, 142, 234)),
-        Color::LightMagenta => Some((214, 112, 214)),
-        Color::LightCyan => Some((41, 184, 219)),
-        Color::White => Some((255, 255, 255)),
-        Color::Rgb(r, g, b) => Some((r, g, b)),
-        _ => None,
-    }
-}
-
 fn food_count_marker() -> &'static str {
     if glyphs().solid == "#" {
         "#"
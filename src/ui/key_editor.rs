@@ -0,0 +1,194 @@
+use crate::input::{Direction, GameInput};
+use crate::keymap::{Action, KeyBindings, KeyPreset};
+use crate::ui::theme_editor::EditorAction;
+
+/// Row choosing the preset; the action rows follow it in [`Action::ALL`] order.
+pub const PRESET_ROW: usize = 0;
+pub const SAVE_ROW: usize = Action::ALL.len() + 1;
+pub const CANCEL_ROW: usize = Action::ALL.len() + 2;
+pub const ROW_COUNT: usize = Action::ALL.len() + 3;
+
+/// Working copy of the key bindings being edited from the settings menu.
+#[derive(Debug, Clone)]
+pub struct KeyEditor {
+    preset: KeyPreset,
+    bindings: KeyBindings,
+    selected_row: usize,
+    /// Whether the next key press rebinds the selected action.
+    capturing: bool,
+    error: Option<String>,
+}
+
+impl KeyEditor {
+    #[must_use]
+    pub fn new(preset: KeyPreset, bindings: &KeyBindings) -> Self {
+        Self {
+            preset,
+            bindings: bindings.clone(),
+            selected_row: 1,
+            capturing: false,
+            error: None,
+        }
+    }
+
+    #[must_use]
+    pub fn preset(&self) -> KeyPreset {
+        self.preset
+    }
+
+    #[must_use]
+    pub fn bindings(&self) -> &KeyBindings {
+        &self.bindings
+    }
+
+    #[must_use]
+    pub fn selected_row(&self) -> usize {
+        self.selected_row
+    }
+
+    /// Whether keys should arrive raw, to be bound to the selected action.
+    #[must_use]
+    pub fn is_capturing(&self) -> bool {
+        self.capturing
+    }
+
+    /// Returns the last problem to show the player, if any.
+    #[must_use]
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Returns the action edited by `row`, or `None` for the preset, save
+    /// and cancel rows.
+    #[must_use]
+    pub fn row_action(row: usize) -> Option<Action> {
+        row.checked_sub(1)
+            .and_then(|idx| Action::ALL.get(idx))
+            .copied()
+    }
+
+    pub fn handle_input(&mut self, input: GameInput) -> EditorAction {
+        if self.capturing {
+            self.handle_capture_input(input);
+            return EditorAction::None;
+        }
+
+        match input {
+            GameInput::Direction(Direction::Up) => {
+                self.selected_row = (self.selected_row + ROW_COUNT - 1) % ROW_COUNT;
+            }
+            GameInput::Direction(Direction::Down) => {
+                self.selected_row = (self.selected_row + 1) % ROW_COUNT;
+            }
+            GameInput::Confirm | GameInput::Direction(Direction::Right) => {
+                match self.selected_row {
+                    SAVE_ROW => return EditorAction::Save,
+                    CANCEL_ROW => return EditorAction::Close,
+                    PRESET_ROW => {
+                        self.preset = self.preset.next();
+                        self.bindings = KeyBindings::preset(self.preset);
+                        self.error = None;
+                    }
+                    _ => {
+                        self.error = None;
+                        self.capturing = true;
+                    }
+                }
+            }
+            GameInput::Pause | GameInput::Direction(Direction::Left) => {
+                return EditorAction::Close;
+            }
+            _ => {}
+        }
+
+        EditorAction::None
+    }
+
+    fn handle_capture_input(&mut self, input: GameInput) {
+        let Some(action) = Self::row_action(self.selected_row) else {
+            self.capturing = false;
+            return;
+        };
+        match input {
+            GameInput::Bind(key) => {
+                self.error =
+                    self.bindings.rebind(action, key).err().map(|other| {
+                        format!("{} is already bound to {}", key.label(), other.label())
+                    });
+                self.capturing = false;
+            }
+            GameInput::Pause => self.capturing = false,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CANCEL_ROW, KeyEditor, PRESET_ROW, ROW_COUNT, SAVE_ROW};
+    use crate::input::{Direction, GameInput};
+    use crate::keymap::{Action, Key, KeyBindings, KeyPreset};
+    use crate::ui::theme_editor::EditorAction;
+
+    fn editor() -> KeyEditor {
+        KeyEditor::new(KeyPreset::Default, &KeyBindings::default())
+    }
+
+    fn select(editor: &mut KeyEditor, row: usize) {
+        while editor.selected_row() != row {
+            editor.handle_input(GameInput::Direction(Direction::Down));
+        }
+    }
+
+    #[test]
+    fn captured_key_rebinds_the_selected_action() {
+        let mut editor = editor();
+        assert_eq!(
+            KeyEditor::row_action(editor.selected_row()),
+            Some(Action::Up)
+        );
+
+        editor.handle_input(GameInput::Confirm);
+        assert!(editor.is_capturing());
+        editor.handle_input(GameInput::Bind(Key::Char('i')));
+
+        assert!(!editor.is_capturing());
+        assert_eq!(editor.error(), None);
+        assert_eq!(editor.bindings().keys(Action::Up), [Key::Char('i')]);
+    }
+
+    #[test]
+    fn conflicting_key_is_rejected_with_a_message() {
+        let mut editor = editor();
+        editor.handle_input(GameInput::Confirm);
+        editor.handle_input(GameInput::Bind(Key::Char('t')));
+
+        assert_eq!(editor.error(), Some("T is already bound to Next theme"));
+        assert_eq!(editor.bindings(), &KeyBindings::default());
+    }
+
+    #[test]
+    fn escape_cancels_capture_and_preset_row_cycles_layouts() {
+        let mut editor = editor();
+        editor.handle_input(GameInput::Confirm);
+        editor.handle_input(GameInput::Pause);
+        assert!(!editor.is_capturing());
+        assert_eq!(editor.bindings(), &KeyBindings::default());
+
+        select(&mut editor, PRESET_ROW);
+        editor.handle_input(GameInput::Confirm);
+        assert_eq!(editor.preset(), KeyPreset::Vim);
+        assert_eq!(editor.bindings(), &KeyBindings::preset(KeyPreset::Vim));
+    }
+
+    #[test]
+    fn save_and_cancel_rows_report_actions() {
+        let mut editor = editor();
+        select(&mut editor, SAVE_ROW);
+        assert_eq!(editor.handle_input(GameInput::Confirm), EditorAction::Save);
+        select(&mut editor, CANCEL_ROW);
+        assert_eq!(editor.handle_input(GameInput::Confirm), EditorAction::Close);
+        assert_eq!(editor.handle_input(GameInput::Pause), EditorAction::Close);
+        assert_eq!(ROW_COUNT, Action::ALL.len() + 3);
+    }
+}
@@ -1,21 +1,93 @@
-use ratatui::Frame;
+use ratatui::buffer::Buffer;
 use ratatui::layout::{Alignment, Constraint, Layout, Rect};
 use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Clear, Paragraph};
+use ratatui::widgets::{Clear, Paragraph, Widget};
 use std::time::Duration;
 
+use crate::background::BackgroundChoice;
 use crate::block_font::{FONT_HEIGHT, render_text, text_width};
 use crate::config::{
     GLYPH_INDICATOR_DOWN, GLYPH_INDICATOR_UP, MAX_START_SPEED_LEVEL, MIN_START_SPEED_LEVEL, Theme,
     glyphs,
 };
 use crate::game::DeathReason;
-use crate::theme::ThemeItem;
+use crate::keymap::{Action, KeyBindings, KeyPreset};
+use crate::renderer::render_preview_board;
+use crate::theme::{AppearancePreference, ThemeItem, color_value};
+use crate::ui::key_editor::{self, KeyEditor};
+use crate::ui::theme_editor::{EditorMode, Hsl, HslChannel, NAME_ROW, ROW_COUNT, ThemeEditor};
+
+/// Column width settings labels are padded to, sized for the longest label.
+const VALUE_LABEL_WIDTH: usize = 10;
 
 pub struct ThemeSelectView<'a> {
     pub selected_idx: usize,
     pub themes: &'a [ThemeItem],
+    /// Animation frame of the mock game next to the list.
+    pub preview_step: u64,
+    pub bindings: &'a KeyBindings,
+}
+
+/// Menu entry a mouse click landed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuTarget {
+    StartItem(usize),
+    SettingsItem(usize),
+    PauseItem(usize),
+    GameOverItem(usize),
+    /// Index into the theme list, not the visible row.
+    Theme(usize),
+}
+
+/// Screen rows of the menu entries drawn in the last frame, recorded with the
+/// same `Rect`s the entries were rendered into.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MenuHits {
+    regions: Vec<(Rect, MenuTarget)>,
+}
+
+impl MenuHits {
+    /// Returns the entry under the given terminal cell. Regions drawn later
+    /// sit on top, so they win.
+    #[must_use]
+    pub fn target_at(&self, column: u16, row: u16) -> Option<MenuTarget> {
+        self.regions
+            .iter()
+            .rev()
+            .find(|(rect, _)| {
+                column >= rect.x && column < rect.right() && row >= rect.y && row < rect.bottom()
+            })
+            .map(|(_, target)| *target)
+    }
+
+    /// Records one single-row region per target, starting `first_line` rows
+    /// into `area` and clipped to it.
+    fn push_rows(
+        &mut self,
+        area: Rect,
+        first_line: usize,
+        targets: impl IntoIterator<Item = MenuTarget>,
+    ) {
+        for (line, target) in targets.into_iter().enumerate() {
+            let Ok(offset) = u16::try_from(first_line + line) else {
+                break;
+            };
+            if offset >= area.height {
+                break;
+            }
+            let row = Rect {
+                y: area.y + offset,
+                height: 1,
+                ..area
+            };
+            self.regions.push((row, target));
+        }
+    }
+
+    fn extend(&mut self, other: Self) {
+        self.regions.extend(other.regions);
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -36,7 +108,7 @@ enum GameOverTitleMode {
 /// Draws the start screen as a centered popup.
 #[allow(clippy::too_many_arguments)]
 pub fn render_start_menu(
-    frame: &mut Frame<'_>,
+    buf: &mut Buffer,
     area: Rect,
     _high_score: u32,
     theme: &Theme,
@@ -46,10 +118,15 @@ pub fn render_start_menu(
     settings_selected_idx: usize,
     start_speed_level: u32,
     speed_adjust_mode: bool,
-    checkerboard_enabled: bool,
+    appearance: AppearancePreference,
+    background: BackgroundChoice,
     game_border_enabled: bool,
+    shape_coding: bool,
+    key_preset: KeyPreset,
+    bindings: &KeyBindings,
+    mouse_steering: bool,
     theme_select: Option<ThemeSelectView<'_>>,
-) {
+) -> MenuHits {
     // Breakpoints:
     // 1) Full block-font "terminal   snake".
     // 2) Plain "TERMINAL" + block-font "snake".
@@ -78,28 +155,58 @@ pub fn render_start_menu(
                 theme,
             ),
             menu_option_value_line(
-                "Grid",
-                if checkerboard_enabled { "On" } else { "Off" }.to_string(),
+                "Appearance",
+                appearance.label().to_string(),
                 settings_selected_idx == 2,
                 false,
                 theme,
             ),
+            menu_option_value_line(
+                "Background",
+                background.label().to_string(),
+                settings_selected_idx == 3,
+                false,
+                theme,
+            ),
             menu_option_value_line(
                 "Border",
                 if game_border_enabled { "On" } else { "Off" }.to_string(),
-                settings_selected_idx == 3,
+                settings_selected_idx == 4,
+                false,
+                theme,
+            ),
+            menu_option_value_line(
+                "Shapes",
+                if shape_coding { "On" } else { "Off" }.to_string(),
+                settings_selected_idx == 5,
+                false,
+                theme,
+            ),
+            menu_option_value_line(
+                "Keys",
+                keys_label(key_preset, bindings).to_string(),
+                settings_selected_idx == 6,
+                false,
+                theme,
+            ),
+            menu_option_value_line(
+                "Steering",
+                if mouse_steering { "On" } else { "Off" }.to_string(),
+                settings_selected_idx == 7,
                 false,
                 theme,
             ),
-            menu_option_line("Back", settings_selected_idx == 4, theme),
+            menu_option_line("Back", settings_selected_idx == 8, theme),
         ]
     } else {
         vec![
-            menu_option_line("Start", selected_idx == 0, theme),
-            menu_option_line("Settings", selected_idx == 1, theme),
-            menu_option_line("Quit", selected_idx == 2, theme),
+            menu_option_line("Start", selected_idx == START_MENU_START_IDX, theme),
+            menu_option_line("Settings", selected_idx == START_MENU_SETTINGS_IDX, theme),
+            menu_option_line("Quit", selected_idx == START_MENU_QUIT_IDX, theme),
         ]
     };
+    let item_count = body.len();
+    let mut warning_line_count = 0;
 
     if play_area_too_small {
         let mut warning_lines = Vec::new();
@@ -119,6 +226,7 @@ pub fn render_start_menu(
             warning_lines.push(Line::from(line));
         }
         warning_lines.push(Line::from(""));
+        warning_line_count = warning_lines.len();
         warning_lines.append(&mut body);
         body = warning_lines;
     }
@@ -143,8 +251,8 @@ pub fn render_start_menu(
     let logo_to_menu_gap = MENU_MARGIN_ROWS.saturating_sub(1);
     let popup_height = menu_popup_height(title_row_height, menu_height).saturating_add(4);
     let popup = centered_popup_with_height(area, 76, popup_height);
-    frame.render_widget(Clear, popup);
-    render_menu_panel(frame, popup, theme);
+    Clear.render(popup, buf);
+    render_menu_panel(buf, popup, theme);
 
     let [_, title_row, _, body_row, _, hint_row, copyright_row, _] = Layout::vertical([
         Constraint::Length(MENU_MARGIN_ROWS),
@@ -168,12 +276,10 @@ pub fn render_start_menu(
         .areas(title_row);
 
         let title_lines = start_screen_title_lines(theme);
-        frame.render_widget(
-            Paragraph::new(title_lines)
-                .alignment(Alignment::Center)
-                .style(Style::default().bg(theme.ui_bg)),
-            title_font_row,
-        );
+        Paragraph::new(title_lines)
+            .alignment(Alignment::Center)
+            .style(Style::default().bg(theme.ui_bg))
+            .render(title_font_row, buf);
 
         // Right-align the version with the right edge of the block-font title.
         // The title ("terminal" + 3-space gap + "snake") is center-aligned in the
@@ -183,12 +289,10 @@ pub fn render_start_menu(
         let version_text = format!("v{}", env!("CARGO_PKG_VERSION"));
         let pad_to = title_right_col.max(version_text.chars().count());
         let padded_version = format!("{:>width$}", version_text, width = pad_to);
-        frame.render_widget(
-            Paragraph::new(Line::from(padded_version))
-                .alignment(Alignment::Left)
-                .style(Style::default().fg(theme.ui_bright).bg(theme.ui_bg)),
-            title_version_row,
-        );
+        Paragraph::new(Line::from(padded_version))
+            .alignment(Alignment::Left)
+            .style(Style::default().fg(theme.ui_bright).bg(theme.ui_bg))
+            .render(title_version_row, buf);
     } else {
         let snake_width = if matches!(
             title_mode,
@@ -215,35 +319,29 @@ pub fn render_start_menu(
             .areas(title_row);
 
             let snake_lines = snake_only_title_lines(theme);
-            frame.render_widget(
-                Paragraph::new(snake_lines)
-                    .alignment(Alignment::Center)
-                    .style(Style::default().bg(theme.ui_bg)),
-                title_font_row,
-            );
+            Paragraph::new(snake_lines)
+                .alignment(Alignment::Center)
+                .style(Style::default().bg(theme.ui_bg))
+                .render(title_font_row, buf);
 
             // Overwrite SNAKE row 0 (blank) with TERMINAL.
             let overlay_row = Rect {
                 height: 1,
                 ..title_font_row
             };
-            frame.render_widget(
-                Paragraph::new(Line::from(padded_terminal))
-                    .alignment(Alignment::Left)
-                    .style(terminal_style),
-                overlay_row,
-            );
+            Paragraph::new(Line::from(padded_terminal))
+                .alignment(Alignment::Left)
+                .style(terminal_style)
+                .render(overlay_row, buf);
 
             let title_right_col = (popup_width + snake_width) / 2;
             let version_text = format!("v{}", env!("CARGO_PKG_VERSION"));
             let pad_to = title_right_col.max(version_text.chars().count());
             let padded_version = format!("{:>width$}", version_text, width = pad_to);
-            frame.render_widget(
-                Paragraph::new(Line::from(padded_version))
-                    .alignment(Alignment::Left)
-                    .style(Style::default().fg(theme.ui_bright).bg(theme.ui_bg)),
-                title_version_row,
-            );
+            Paragraph::new(Line::from(padded_version))
+                .alignment(Alignment::Left)
+                .style(Style::default().fg(theme.ui_bright).bg(theme.ui_bg))
+                .render(title_version_row, buf);
         } else if title_mode == StartTitleMode::MixedStacked {
             // Can't overlap — TERMINAL gets its own row above SNAKE.
             // Layout: [1(plain), FONT_HEIGHT(4), 1(version)] = 6 rows total.
@@ -254,31 +352,25 @@ pub fn render_start_menu(
             ])
             .areas(title_row);
 
-            frame.render_widget(
-                Paragraph::new(Line::from(padded_terminal))
-                    .alignment(Alignment::Left)
-                    .style(terminal_style),
-                title_plain_row,
-            );
+            Paragraph::new(Line::from(padded_terminal))
+                .alignment(Alignment::Left)
+                .style(terminal_style)
+                .render(title_plain_row, buf);
 
             let snake_lines = snake_only_title_lines(theme);
-            frame.render_widget(
-                Paragraph::new(snake_lines)
-                    .alignment(Alignment::Center)
-                    .style(Style::default().bg(theme.ui_bg)),
-                title_font_row,
-            );
+            Paragraph::new(snake_lines)
+                .alignment(Alignment::Center)
+                .style(Style::default().bg(theme.ui_bg))
+                .render(title_font_row, buf);
 
             let title_right_col = (popup_width + snake_width) / 2;
             let version_text = format!("v{}", env!("CARGO_PKG_VERSION"));
             let pad_to = title_right_col.max(version_text.chars().count());
             let padded_version = format!("{:>width$}", version_text, width = pad_to);
-            frame.render_widget(
-                Paragraph::new(Line::from(padded_version))
-                    .alignment(Alignment::Left)
-                    .style(Style::default().fg(theme.ui_bright).bg(theme.ui_bg)),
-                title_version_row,
-            );
+            Paragraph::new(Line::from(padded_version))
+                .alignment(Alignment::Left)
+                .style(Style::default().fg(theme.ui_bright).bg(theme.ui_bg))
+                .render(title_version_row, buf);
         } else {
             // Extra narrow: both words are plain uppercase.
             // Layout: [1(TERMINAL), 1(SNAKE), 1(version)] = 3 rows total.
@@ -289,43 +381,41 @@ pub fn render_start_menu(
             ])
             .areas(title_row);
 
-            frame.render_widget(
-                Paragraph::new(Line::from("TERMINAL"))
-                    .alignment(Alignment::Center)
-                    .style(terminal_style),
-                title_terminal_row,
-            );
+            Paragraph::new(Line::from("TERMINAL"))
+                .alignment(Alignment::Center)
+                .style(terminal_style)
+                .render(title_terminal_row, buf);
 
-            frame.render_widget(
-                Paragraph::new(Line::from("SNAKE"))
-                    .alignment(Alignment::Center)
-                    .style(
-                        Style::default()
-                            .fg(theme.ui_text)
-                            .add_modifier(Modifier::BOLD)
-                            .bg(theme.ui_bg),
-                    ),
-                title_snake_row,
-            );
+            Paragraph::new(Line::from("SNAKE"))
+                .alignment(Alignment::Center)
+                .style(
+                    Style::default()
+                        .fg(theme.ui_text)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme.ui_bg),
+                )
+                .render(title_snake_row, buf);
 
             let title_right_col = (popup_width + snake_plain_width) / 2;
             let version_text = format!("v{}", env!("CARGO_PKG_VERSION"));
             let pad_to = title_right_col.max(version_text.chars().count());
             let padded_version = format!("{:>width$}", version_text, width = pad_to);
-            frame.render_widget(
-                Paragraph::new(Line::from(padded_version))
-                    .alignment(Alignment::Left)
-                    .style(Style::default().fg(theme.ui_bright).bg(theme.ui_bg)),
-                title_version_row,
-            );
+            Paragraph::new(Line::from(padded_version))
+                .alignment(Alignment::Left)
+                .style(Style::default().fg(theme.ui_bright).bg(theme.ui_bg))
+                .render(title_version_row, buf);
         }
     }
 
     let menu_width = start_menu_content_width(
         theme,
         start_speed_level,
-        checkerboard_enabled,
+        appearance,
+        background,
         game_border_enabled,
+        shape_coding,
+        keys_label(key_preset, bindings),
+        mouse_steering,
         settings_open,
     )
     .saturating_add(2);
@@ -334,12 +424,10 @@ pub fn render_start_menu(
     } else {
         centered_rect_with_max_width(body_row, menu_width)
     };
-    frame.render_widget(
-        Paragraph::new(body)
-            .alignment(Alignment::Left)
-            .style(menu_body_style(theme)),
-        menu_area,
-    );
+    Paragraph::new(body)
+        .alignment(Alignment::Left)
+        .style(menu_body_style(theme))
+        .render(menu_area, buf);
 
     // Overlay configured up/down indicator glyphs around speed value.
     // Written directly to the buffer after the paragraph so they sit on top of
@@ -347,15 +435,15 @@ pub fn render_start_menu(
     // entire frame every tick the source data is never mutated — the overlay is
     // ephemeral and non-destructive.
     if speed_adjust_mode && settings_open {
-        // "> Speed:  " = 2 (prefix) + 5 (label) + 3 (":  ") = 10 chars before value.
-        let value_x = menu_area.x.saturating_add(10);
+        // Prefix "> " plus the padded label and ": " puts the indicator just
+        // ahead of the value column.
+        let value_x = menu_area.x.saturating_add(2 + VALUE_LABEL_WIDTH as u16 + 2);
         // In settings submenu, Speed is body line index 0.
         let speed_y = menu_area.y;
         let indicator_style = Style::default()
             .fg(theme.ui_accent)
             .bg(theme.ui_bg)
             .add_modifier(Modifier::BOLD);
-        let buf = frame.buffer_mut();
         if value_x < menu_area.right() {
             if start_speed_level < MAX_START_SPEED_LEVEL {
                 buf.set_string(
@@ -376,45 +464,65 @@ pub fn render_start_menu(
         }
     }
 
+    let up_down = bindings.pair_label(Action::Up, Action::Down);
+    let confirm = bindings.label(Action::Confirm);
     let hint_text = if speed_adjust_mode {
-        "↑↓ adjusts speed   Enter/Esc to confirm"
+        format!(
+            "{up_down} adjusts speed   {confirm}/{} to confirm",
+            bindings.label(Action::Pause)
+        )
     } else if theme_editing {
-        "↑↓ cycles theme   Enter/Esc to confirm"
+        format!(
+            "{up_down} cycles theme   {confirm}/{} to confirm",
+            bindings.label(Action::Pause)
+        )
     } else if settings_open {
-        "↑↓ navigate   Enter/→ select   Esc/← back"
+        format!(
+            "{up_down} navigate   {confirm}/{} select   {} back",
+            bindings.label(Action::Right),
+            bindings.pair_label(Action::Pause, Action::Left)
+        )
     } else {
-        "↑↓ navigate   Enter/→ select"
+        format!(
+            "{up_down} navigate   {confirm}/{} select",
+            bindings.label(Action::Right)
+        )
     };
-    frame.render_widget(
-        Paragraph::new(Line::from(hint_text))
-            .alignment(Alignment::Center)
-            .style(Style::default().fg(theme.ui_muted).bg(theme.ui_bg)),
-        hint_row,
-    );
+    Paragraph::new(Line::from(hint_text))
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(theme.ui_muted).bg(theme.ui_bg))
+        .render(hint_row, buf);
 
-    frame.render_widget(
-        Paragraph::new(Line::from("Copyright (c) 2026 Thomas Malt"))
-            .alignment(Alignment::Center)
-            .style(Style::default().fg(theme.ui_muted).bg(theme.ui_bg)),
-        copyright_row,
-    );
+    Paragraph::new(Line::from("Copyright (c) 2026 Thomas Malt"))
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(theme.ui_muted).bg(theme.ui_bg))
+        .render(copyright_row, buf);
 
-    render_menu_bottom_margin(frame, popup, theme);
+    render_menu_bottom_margin(buf, popup, theme);
 
+    let mut hits = MenuHits::default();
     if let Some(select_view) = theme_select {
-        render_theme_select_list(frame, area, theme, &select_view);
+        hits.extend(render_theme_select_list(buf, area, theme, &select_view));
+    } else {
+        let target = if settings_open {
+            MenuTarget::SettingsItem
+        } else {
+            MenuTarget::StartItem
+        };
+        hits.push_rows(menu_area, warning_line_count, (0..item_count).map(target));
     }
+    hits
 }
 
 /// Draws the pause screen as a centered popup.
 pub fn render_pause_menu(
-    frame: &mut Frame<'_>,
+    buf: &mut Buffer,
     area: Rect,
     theme: &Theme,
     pause_resize_too_small: bool,
     selected_idx: usize,
     theme_select: Option<ThemeSelectView<'_>>,
-) {
+) -> MenuHits {
     let popup_for_measure = centered_popup_with_height(area, 60, 1);
     let warning_wrap_width = usize::from(popup_for_measure.width.saturating_sub(2)).max(1);
     let mut body = Vec::new();
@@ -438,19 +546,28 @@ pub fn render_pause_menu(
 
         body.push(Line::from(""));
     }
-    body.push(menu_option_line("Resume", selected_idx == 0, theme));
+    let warning_line_count = body.len();
+    body.push(menu_option_line(
+        "Resume",
+        selected_idx == PAUSE_MENU_RESUME_IDX,
+        theme,
+    ));
     body.push(menu_option_line(
         format!("Theme:  {}", theme.name),
-        selected_idx == 1,
+        selected_idx == PAUSE_MENU_THEME_IDX,
+        theme,
+    ));
+    body.push(menu_option_line(
+        "Quit",
+        selected_idx == PAUSE_MENU_QUIT_IDX,
         theme,
     ));
-    body.push(menu_option_line("Quit", selected_idx == 2, theme));
     let menu_height = u16::try_from(body.len()).unwrap_or(u16::MAX);
     let title_height: u16 = 1;
     let popup_height = menu_popup_height(title_height, menu_height);
     let popup = centered_popup_with_height(area, 60, popup_height);
-    frame.render_widget(Clear, popup);
-    render_menu_panel(frame, popup, theme);
+    Clear.render(popup, buf);
+    render_menu_panel(buf, popup, theme);
 
     let [_, title_row, _, body_row, _] = Layout::vertical([
         Constraint::Length(MENU_MARGIN_ROWS),
@@ -461,12 +578,10 @@ pub fn render_pause_menu(
     ])
     .areas(popup);
 
-    frame.render_widget(
-        Paragraph::new(Line::from("PAUSED"))
-            .alignment(Alignment::Center)
-            .style(Style::default().fg(theme.ui_accent).bg(theme.ui_bg)),
-        title_row,
-    );
+    Paragraph::new(Line::from("PAUSED"))
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(theme.ui_accent).bg(theme.ui_bg))
+        .render(title_row, buf);
 
     let menu_width = pause_menu_content_width(theme).saturating_add(2);
     let menu_area = if pause_resize_too_small {
@@ -474,24 +589,30 @@ pub fn render_pause_menu(
     } else {
         centered_rect_with_max_width(body_row, menu_width)
     };
-    frame.render_widget(
-        Paragraph::new(body)
-            .alignment(Alignment::Left)
-            .style(menu_body_style(theme)),
-        menu_area,
-    );
+    Paragraph::new(body)
+        .alignment(Alignment::Left)
+        .style(menu_body_style(theme))
+        .render(menu_area, buf);
 
-    render_menu_bottom_margin(frame, popup, theme);
+    render_menu_bottom_margin(buf, popup, theme);
 
+    let mut hits = MenuHits::default();
     if let Some(select_view) = theme_select {
-        render_theme_select_list(frame, area, theme, &select_view);
+        hits.extend(render_theme_select_list(buf, area, theme, &select_view));
+    } else {
+        hits.push_rows(
+            menu_area,
+            warning_line_count,
+            (0..PAUSE_MENU_ITEM_COUNT).map(MenuTarget::PauseItem),
+        );
     }
+    hits
 }
 
 /// Draws the game-over screen as a centered popup.
 #[allow(clippy::too_many_arguments)]
 pub fn render_game_over_menu(
-    frame: &mut Frame<'_>,
+    buf: &mut Buffer,
     area: Rect,
     score: u32,
     high_score: u32,
@@ -499,9 +620,11 @@ pub fn render_game_over_menu(
     coverage_percent: f64,
     death_reason: Option<DeathReason>,
     game_length: Duration,
+    sprint_time: Duration,
     theme: &Theme,
     selected_idx: usize,
-) {
+    bindings: &KeyBindings,
+) -> MenuHits {
     let is_new_high = score > high_score;
 
     let shown_high_score = if is_new_high { score } else { high_score };
@@ -523,6 +646,7 @@ pub fn render_game_over_menu(
         None => "-",
     };
     let game_length_str = format_game_length(game_length);
+    let sprint_str = format_game_length(sprint_time);
     let foods_str = format!("{foods_per_minute:.1}");
 
     let value_col_width = [
@@ -531,6 +655,7 @@ pub fn render_game_over_menu(
         &high_score_str,
         cause_str,
         &game_length_str,
+        &sprint_str,
         &foods_str,
         &length_str,
         &coverage_str,
@@ -546,6 +671,7 @@ pub fn render_game_over_menu(
         table_row("High score", &high_score_str, value_col_width, theme),
         table_row("Cause", cause_str, value_col_width, theme),
         table_row("Game length", &game_length_str, value_col_width, theme),
+        table_row("Sprint", &sprint_str, value_col_width, theme),
         table_row("Food/min", &foods_str, value_col_width, theme),
         table_row("Length", &length_str, value_col_width, theme),
         table_row("Coverage", &coverage_str, value_col_width, theme),
@@ -557,8 +683,17 @@ pub fn render_game_over_menu(
         body.push(Line::from(""));
     }
 
-    body.push(menu_option_line("Play Again", selected_idx == 0, theme));
-    body.push(menu_option_line("Quit", selected_idx == 1, theme));
+    let options_line = body.len();
+    body.push(menu_option_line(
+        "Play Again",
+        selected_idx == GAME_OVER_MENU_PLAY_AGAIN_IDX,
+        theme,
+    ));
+    body.push(menu_option_line(
+        "Quit",
+        selected_idx == GAME_OVER_MENU_QUIT_IDX,
+        theme,
+    ));
 
     let menu_height = u16::try_from(body.len()).unwrap_or(u16::MAX);
     let popup_for_measure = centered_popup_with_height(area, 70, area.height.max(1));
@@ -574,8 +709,8 @@ pub fn render_game_over_menu(
     };
     let popup_height = menu_popup_height(title_height, menu_height).saturating_add(2);
     let popup = centered_popup_with_height(area, 70, popup_height);
-    frame.render_widget(Clear, popup);
-    render_menu_panel(frame, popup, theme);
+    Clear.render(popup, buf);
+    render_menu_panel(buf, popup, theme);
 
     let [_, title_row, _, body_row, _, footer_hint_row] = Layout::vertical([
         Constraint::Length(MENU_MARGIN_ROWS),
@@ -589,12 +724,10 @@ pub fn render_game_over_menu(
 
     match title_mode {
         GameOverTitleMode::FullBlock => {
-            frame.render_widget(
-                Paragraph::new(game_over_block_title_lines(theme))
-                    .alignment(Alignment::Center)
-                    .style(Style::default().bg(theme.ui_bg)),
-                title_row,
-            );
+            Paragraph::new(game_over_block_title_lines(theme))
+                .alignment(Alignment::Center)
+                .style(Style::default().bg(theme.ui_bg))
+                .render(title_row, buf);
         }
         GameOverTitleMode::MixedNarrow => {
             let popup_width = usize::from(popup.width);
@@ -608,37 +741,31 @@ pub fn render_game_over_menu(
             ])
             .areas(title_row);
 
-            frame.render_widget(
-                Paragraph::new(Line::from(padded_game))
-                    .alignment(Alignment::Left)
-                    .style(
-                        Style::default()
-                            .fg(theme.ui_accent)
-                            .add_modifier(Modifier::BOLD)
-                            .bg(theme.ui_bg),
-                    ),
-                game_row,
-            );
+            Paragraph::new(Line::from(padded_game))
+                .alignment(Alignment::Left)
+                .style(
+                    Style::default()
+                        .fg(theme.ui_accent)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme.ui_bg),
+                )
+                .render(game_row, buf);
 
-            frame.render_widget(
-                Paragraph::new(over_only_title_lines(theme))
-                    .alignment(Alignment::Center)
-                    .style(Style::default().bg(theme.ui_bg)),
-                over_row,
-            );
+            Paragraph::new(over_only_title_lines(theme))
+                .alignment(Alignment::Center)
+                .style(Style::default().bg(theme.ui_bg))
+                .render(over_row, buf);
         }
         GameOverTitleMode::Plain => {
-            frame.render_widget(
-                Paragraph::new(Line::from("Game Over"))
-                    .alignment(Alignment::Center)
-                    .style(
-                        Style::default()
-                            .fg(theme.ui_text)
-                            .add_modifier(Modifier::BOLD)
-                            .bg(theme.ui_bg),
-                    ),
-                title_row,
-            );
+            Paragraph::new(Line::from("Game Over"))
+                .alignment(Alignment::Center)
+                .style(
+                    Style::default()
+                        .fg(theme.ui_text)
+                        .add_modifier(Modifier::BOLD)
+                        .bg(theme.ui_bg),
+                )
+                .render(title_row, buf);
         }
     }
 
@@ -646,30 +773,37 @@ pub fn render_game_over_menu(
     // = 19 + value_col_width.
     let table_width = u16::try_from(19 + value_col_width).unwrap_or(u16::MAX);
     let centered_body = centered_rect_with_max_width(body_row, table_width);
-    frame.render_widget(
-        Paragraph::new(body)
-            .alignment(Alignment::Left)
-            .style(menu_body_style(theme)),
-        centered_body,
-    );
+    Paragraph::new(body)
+        .alignment(Alignment::Left)
+        .style(menu_body_style(theme))
+        .render(centered_body, buf);
 
-    let table_rows: u16 = 8;
+    let table_rows: u16 = 9;
     let table_area = Rect {
         x: centered_body.x,
         y: centered_body.y,
         width: centered_body.width,
         height: table_rows.min(centered_body.height),
     };
-    render_outer_table_border(frame, table_area, theme);
+    render_outer_table_border(buf, table_area, theme);
 
-    frame.render_widget(
-        Paragraph::new(Line::from("Use arrows/WASD to move"))
-            .alignment(Alignment::Center)
-            .style(Style::default().fg(theme.ui_muted).bg(theme.ui_bg)),
-        footer_hint_row,
-    );
+    Paragraph::new(Line::from(format!(
+        "Use {} to move",
+        bindings.movement_label()
+    )))
+    .alignment(Alignment::Center)
+    .style(Style::default().fg(theme.ui_muted).bg(theme.ui_bg))
+    .render(footer_hint_row, buf);
+
+    render_menu_bottom_margin(buf, popup, theme);
 
-    render_menu_bottom_margin(frame, popup, theme);
+    let mut hits = MenuHits::default();
+    hits.push_rows(
+        centered_body,
+        options_line,
+        (0..GAME_OVER_MENU_ITEM_COUNT).map(MenuTarget::GameOverItem),
+    );
+    hits
 }
 
 fn choose_game_over_title_mode(
@@ -734,11 +868,11 @@ fn centered_popup_with_height(area: Rect, width_percent: u16, desired_height: u1
 }
 
 fn render_theme_select_list(
-    frame: &mut Frame<'_>,
+    buf: &mut Buffer,
     area: Rect,
     active_theme: &Theme,
     select_view: &ThemeSelectView<'_>,
-) {
+) -> MenuHits {
     let desired_list_height = u16::try_from(select_view.themes.len().max(1)).unwrap_or(u16::MAX);
     let desired_popup_height = desired_list_height;
     let base_popup = centered_popup(area, 52, 40);
@@ -749,8 +883,8 @@ fn render_theme_select_list(
         desired_popup_width,
         desired_popup_height,
     );
-    frame.render_widget(Clear, popup);
-    render_menu_panel(frame, popup, active_theme);
+    Clear.render(popup, buf);
+    render_menu_panel(buf, popup, active_theme);
     let inner = popup;
 
     let list_height = desired_list_height.min(inner.height.max(1));
@@ -763,18 +897,44 @@ fn render_theme_select_list(
         usize::from(list_height),
         active_theme,
     );
-    frame.render_widget(
-        Paragraph::new(items)
-            .alignment(Alignment::Left)
-            .style(theme_select_list_style(active_theme)),
-        list_row,
-    );
+    Paragraph::new(items)
+        .alignment(Alignment::Left)
+        .style(theme_select_list_style(active_theme))
+        .render(list_row, buf);
 
-    render_menu_bottom_margin(frame, popup, active_theme);
+    render_menu_bottom_margin(buf, popup, active_theme);
 
     if let Some(preview_area) = right_preview_area(area, popup) {
-        render_theme_preview_palette(frame, preview_area, active_theme);
+        render_theme_preview(
+            buf,
+            preview_area,
+            active_theme,
+            select_view.preview_step,
+            select_view.bindings,
+        );
     }
+
+    let mut hits = MenuHits::default();
+    if !select_view.themes.is_empty() {
+        let show_count = usize::from(list_height).min(select_view.themes.len());
+        let start = theme_list_start(
+            select_view.themes.len(),
+            select_view.selected_idx,
+            show_count,
+        );
+        // The bottom margin is drawn over the list's last row.
+        let clickable = Rect {
+            height: list_row.height.min(popup.height.saturating_sub(1)),
+            ..list_row
+        };
+        hits.push_rows(
+            clickable,
+            0,
+            (0..show_count)
+                .map(|offset| MenuTarget::Theme((start + offset) % select_view.themes.len())),
+        );
+    }
+    hits
 }
 
 fn right_preview_area(container: Rect, anchor: Rect) -> Option<Rect> {
@@ -788,53 +948,331 @@ fn right_preview_area(container: Rect, anchor: Rect) -> Option<Rect> {
         return None;
     }
 
+    // Short theme lists still get a board tall enough for the snake loop.
     Some(Rect {
         x,
         y: anchor.y,
         width: available_width.min(30),
-        height: anchor.height,
+        height: anchor
+            .height
+            .max(PREVIEW_MIN_HEIGHT)
+            .min(container.bottom().saturating_sub(anchor.y)),
     })
 }
 
-fn render_theme_preview_palette(frame: &mut Frame<'_>, area: Rect, theme: &Theme) {
-    frame.render_widget(Clear, area);
-    render_menu_panel(frame, area, theme);
+/// Draws a mock game in `theme` next to the theme list: a HUD line above a
+/// small animated board, with the editor hint below.
+fn render_theme_preview(
+    buf: &mut Buffer,
+    area: Rect,
+    theme: &Theme,
+    step: u64,
+    bindings: &KeyBindings,
+) {
+    Clear.render(area, buf);
+    render_menu_panel(buf, area, theme);
 
-    let [_, content] = Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).areas(area);
+    let [_, hud_row, board_row, hint_row, _] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Min(1),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .areas(area);
+    let [_, hud_row, _] = Layout::horizontal([
+        Constraint::Length(1),
+        Constraint::Min(1),
+        Constraint::Length(1),
+    ])
+    .areas(hud_row);
+    let [_, board_row, _] = Layout::horizontal([
+        Constraint::Length(1),
+        Constraint::Min(1),
+        Constraint::Length(1),
+    ])
+    .areas(board_row);
 
-    frame.render_widget(
-        Paragraph::new(theme_preview_lines(theme))
-            .alignment(Alignment::Left)
-            .style(menu_body_style(theme)),
-        content,
-    );
+    Paragraph::new(preview_hud_line(theme))
+        .alignment(Alignment::Right)
+        .style(
+            Style::default()
+                .fg(ratatui::style::Color::DarkGray)
+                .bg(theme.field_bg),
+        )
+        .render(hud_row, buf);
+    render_preview_board(buf, board_row, theme, step);
+    Paragraph::new(Line::from(format!(
+        " {}: edit a copy",
+        bindings.label(Action::EditTheme)
+    )))
+    .style(Style::default().fg(theme.ui_muted).bg(theme.ui_bg))
+    .render(hint_row, buf);
+}
 
-    render_menu_bottom_margin(frame, area, theme);
+/// Compact HUD status line in the theme's HUD colors, as during a game that
+/// is setting a new high score.
+fn preview_hud_line(theme: &Theme) -> Line<'static> {
+    let sep = format!(" {} ", glyphs().table_separator);
+    Line::from(vec![
+        Span::raw("L: "),
+        Span::styled("8", Style::default().fg(theme.ui_bright)),
+        Span::raw(sep.clone()),
+        Span::raw("V: "),
+        Span::styled("3", Style::default().fg(theme.ui_bright)),
+        Span::raw(sep),
+        Span::raw("H: "),
+        Span::styled("42", Style::default().fg(theme.ui_accent)),
+        Span::raw(" "),
+    ])
 }
 
-fn theme_preview_lines(theme: &Theme) -> Vec<Line<'static>> {
-    vec![
-        Line::from("Preview"),
+/// Minimum height of the theme preview panel, in terminal rows.
+const PREVIEW_MIN_HEIGHT: u16 = 12;
+
+/// Width of each HSL slider bar in the theme editor, in terminal cells.
+const HSL_SLIDER_WIDTH: u16 = 18;
+
+/// Draws the theme editor as a panel on the left of `area`, leaving the play
+/// field visible beside it so edits preview live.
+pub fn render_theme_editor(
+    buf: &mut Buffer,
+    area: Rect,
+    editor: &ThemeEditor,
+    bindings: &KeyBindings,
+) {
+    let theme = editor.theme();
+    let mut lines = vec![
+        Line::from(" Edit theme").style(
+            Style::default()
+                .fg(theme.ui_accent)
+                .add_modifier(Modifier::BOLD),
+        ),
         Line::from(""),
-        swatch_line("Head", theme.snake_head, "snake head"),
-        swatch_line("Body", theme.snake_body, "snake body"),
-        swatch_line("Tail", theme.snake_tail, "snake tail"),
-        swatch_line("Food", theme.food, "food"),
-        swatch_line("Term bg", theme.terminal_bg, "terminal_bg"),
-        swatch_line("Field", theme.field_bg, "field_bg"),
-        swatch_line("UI bg", theme.ui_bg, "ui_bg"),
-        swatch_line("UI text", theme.ui_text, "ui_text"),
-    ]
+    ];
+    lines.extend((0..ROW_COUNT).map(|row| theme_editor_row_line(editor, row)));
+    lines.push(Line::from(""));
+    if let EditorMode::Hsl { channel, hsl, .. } = editor.mode() {
+        for slider in [
+            HslChannel::Hue,
+            HslChannel::Saturation,
+            HslChannel::Lightness,
+        ] {
+            lines.push(hsl_slider_line(slider, *channel == slider, *hsl, theme));
+        }
+        lines.push(Line::from(""));
+    }
+    if let Some(error) = editor.error() {
+        lines.push(Line::from(format!(" {error}")).style(Style::default().fg(theme.ui_accent)));
+    }
+    let hint = match editor.mode() {
+        EditorMode::Browse => format!(
+            " {}: type value  {}: HSL  {}: close",
+            bindings.label(Action::Confirm),
+            bindings.label(Action::Right),
+            bindings.label(Action::Pause)
+        ),
+        // Text entry keys are fixed so every printable key can be typed.
+        EditorMode::Text { .. } => " Enter: apply  Esc: cancel".to_owned(),
+        EditorMode::Hsl { .. } => format!(
+            " {}: slider  {}: adjust",
+            bindings.pair_label(Action::Up, Action::Down),
+            bindings.pair_label(Action::Left, Action::Right)
+        ),
+    };
+    let hint_width = hint.chars().count();
+    lines.push(Line::from(hint).style(Style::default().fg(theme.ui_muted)));
+
+    // One row of panel margin above and below the content.
+    let height = u16::try_from(lines.len() + 2).unwrap_or(u16::MAX);
+    let width = u16::try_from(hint_width + 2)
+        .unwrap_or(u16::MAX)
+        .max(HSL_SLIDER_WIDTH + 16);
+    let popup = left_anchored_popup_with_size(area, area.x.saturating_add(2), width, height);
+    Clear.render(popup, buf);
+    render_menu_panel(buf, popup, theme);
+
+    let [_, content, _] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(1),
+        Constraint::Length(1),
+    ])
+    .areas(popup);
+    Paragraph::new(lines)
+        .alignment(Alignment::Left)
+        .style(menu_body_style(theme))
+        .render(content, buf);
+}
+
+/// Draws the key binding editor as a panel on the left of `area`.
+pub fn render_key_editor(buf: &mut Buffer, area: Rect, editor: &KeyEditor, theme: &Theme) {
+    let bindings = editor.bindings();
+    let mut lines = vec![
+        Line::from(" Key bindings").style(
+            Style::default()
+                .fg(theme.ui_accent)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Line::from(""),
+    ];
+    lines.extend((0..key_editor::ROW_COUNT).map(|row| key_editor_row_line(editor, row, theme)));
+    lines.push(Line::from(""));
+    if let Some(error) = editor.error() {
+        lines.push(Line::from(format!(" {error}")).style(Style::default().fg(theme.ui_accent)));
+    }
+    let hint = match KeyEditor::row_action(editor.selected_row()) {
+        Some(action) if editor.is_capturing() => {
+            format!(" Press a key for {}  Esc: cancel", action.label())
+        }
+        _ => format!(
+            " {}: change  {}: close",
+            bindings.label(Action::Confirm),
+            bindings.label(Action::Pause)
+        ),
+    };
+    let hint_width = hint.chars().count();
+    lines.push(Line::from(hint).style(Style::default().fg(theme.ui_muted)));
+
+    let content_width = lines.iter().map(Line::width).max().unwrap_or(0);
+    let height = u16::try_from(lines.len() + 2).unwrap_or(u16::MAX);
+    let width = u16::try_from(content_width.max(hint_width) + 2).unwrap_or(u16::MAX);
+    let popup = left_anchored_popup_with_size(area, area.x.saturating_add(2), width, height);
+    Clear.render(popup, buf);
+    render_menu_panel(buf, popup, theme);
+
+    let [_, content, _] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(1),
+        Constraint::Length(1),
+    ])
+    .areas(popup);
+    Paragraph::new(lines)
+        .alignment(Alignment::Left)
+        .style(menu_body_style(theme))
+        .render(content, buf);
 }
 
-fn swatch_line(label: &str, color: ratatui::style::Color, usage: &str) -> Line<'static> {
+fn key_editor_row_line(editor: &KeyEditor, row: usize, theme: &Theme) -> Line<'static> {
+    let selected = editor.selected_row() == row;
+    let prefix = if selected { "> " } else { "  " };
+    let label_style = if selected {
+        Style::default()
+            .fg(theme.ui_accent)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    let (label, value) = match row {
+        key_editor::PRESET_ROW => ("Preset", editor.preset().label().to_owned()),
+        key_editor::SAVE_ROW => ("Save", String::new()),
+        key_editor::CANCEL_ROW => ("Cancel", String::new()),
+        _ => match KeyEditor::row_action(row) {
+            Some(action) => (action.label(), editor.bindings().labels(action)),
+            None => ("", String::new()),
+        },
+    };
+    if value.is_empty() {
+        return Line::from(Span::styled(format!("{prefix}{label}"), label_style));
+    }
+
+    let value = if selected && editor.is_capturing() {
+        Span::styled(
+            "press a key".to_owned(),
+            Style::default().fg(theme.ui_bg).bg(theme.ui_accent),
+        )
+    } else {
+        Span::raw(value)
+    };
     Line::from(vec![
-        Span::raw(format!("{label:<7} ")),
-        Span::styled("   ", Style::default().bg(color)),
-        Span::raw(format!(" {usage}")),
+        Span::styled(format!("{prefix}{label:<11} "), label_style),
+        value,
     ])
 }
 
+fn theme_editor_row_line(editor: &ThemeEditor, row: usize) -> Line<'static> {
+    let theme = editor.theme();
+    let selected = editor.selected_row() == row;
+    let prefix = if selected { "> " } else { "  " };
+    let label_style = if selected {
+        Style::default()
+            .fg(theme.ui_accent)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    let label = format!("{prefix}{:<11} ", ThemeEditor::row_label(row));
+
+    let color = editor.row_color(row);
+    if color.is_none() && row != NAME_ROW {
+        return Line::from(Span::styled(label, label_style));
+    }
+
+    let mut spans = vec![Span::styled(label, label_style)];
+    if let Some(color) = color {
+        spans.push(Span::styled("   ", Style::default().bg(color)));
+        spans.push(Span::raw(" "));
+    }
+    match editor.mode() {
+        EditorMode::Text { buffer, .. } if selected => spans.push(Span::styled(
+            format!("{buffer}_"),
+            Style::default().fg(theme.ui_bg).bg(theme.ui_accent),
+        )),
+        _ => spans.push(Span::raw(
+            color.map_or_else(|| theme.name.clone(), color_value),
+        )),
+    }
+    Line::from(spans)
+}
+
+/// Draws one HSL slider as a gradient of what the color would become at
+/// each position, with a marker at the current value.
+fn hsl_slider_line(channel: HslChannel, active: bool, hsl: Hsl, theme: &Theme) -> Line<'static> {
+    let (label, value, max) = match channel {
+        HslChannel::Hue => ("H", format!("{:>3}°", hsl.hue), 359),
+        HslChannel::Saturation => ("S", format!("{:>3}%", hsl.saturation), 100),
+        HslChannel::Lightness => ("L", format!("{:>3}%", hsl.lightness), 100),
+    };
+    let current = match channel {
+        HslChannel::Hue => hsl.hue,
+        HslChannel::Saturation => u16::from(hsl.saturation),
+        HslChannel::Lightness => u16::from(hsl.lightness),
+    };
+    let marker = current * (HSL_SLIDER_WIDTH - 1) / max;
+
+    let label_style = if active {
+        Style::default()
+            .fg(theme.ui_accent)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default()
+    };
+    let mut spans = vec![Span::styled(
+        format!("{} {label} ", if active { ">" } else { " " }),
+        label_style,
+    )];
+    for cell in 0..HSL_SLIDER_WIDTH {
+        let position = cell * max / (HSL_SLIDER_WIDTH - 1);
+        let mut sample = hsl;
+        match channel {
+            HslChannel::Hue => sample.hue = position,
+            HslChannel::Saturation => sample.saturation = position as u8,
+            HslChannel::Lightness => sample.lightness = position as u8,
+        }
+        let marker_fg = if sample.lightness > 50 {
+            ratatui::style::Color::Black
+        } else {
+            ratatui::style::Color::White
+        };
+        let symbol = if cell == marker { "|" } else { " " };
+        spans.push(Span::styled(
+            symbol,
+            Style::default().fg(marker_fg).bg(sample.color()),
+        ));
+    }
+    spans.push(Span::styled(format!(" {value}"), label_style));
+    Line::from(spans)
+}
+
 fn left_anchored_popup_with_size(area: Rect, x: u16, width: u16, height: u16) -> Rect {
     let left = x.clamp(area.x, area.right().saturating_sub(1));
     let max_width = area.right().saturating_sub(left);
@@ -851,6 +1289,12 @@ fn left_anchored_popup_with_size(area: Rect, x: u16, width: u16, height: u16) ->
     }
 }
 
+/// Index of the theme on the first visible row, keeping the selection
+/// centered and wrapping around the list.
+fn theme_list_start(theme_count: usize, selected_idx: usize, show_count: usize) -> usize {
+    (selected_idx + theme_count - show_count / 2) % theme_count
+}
+
 fn visible_theme_lines(
     themes: &[ThemeItem],
     selected_idx: usize,
@@ -868,8 +1312,7 @@ fn visible_theme_lines(
     }
 
     let show_count = count.min(themes.len());
-    let center = show_count / 2;
-    let start = (selected_idx + themes.len() - center) % themes.len();
+    let start = theme_list_start(themes.len(), selected_idx, show_count);
 
     let mut lines = Vec::with_capacity(show_count);
     for offset in 0..show_count {
@@ -917,7 +1360,6 @@ fn menu_option_value_line(
     editing: bool,
     theme: &Theme,
 ) -> Line<'static> {
-    const VALUE_LABEL_WIDTH: usize = 6;
     let prefix = if selected { "> " } else { "  " };
     let padded_label = format!("{label:<VALUE_LABEL_WIDTH$}");
     if editing {
@@ -1015,28 +1457,48 @@ fn over_only_title_lines(theme: &Theme) -> Vec<Line<'static>> {
         .collect()
 }
 
+#[allow(clippy::too_many_arguments)]
 fn start_menu_content_width(
     theme: &Theme,
     start_speed_level: u32,
-    checkerboard_enabled: bool,
+    appearance: AppearancePreference,
+    background: BackgroundChoice,
     game_border_enabled: bool,
+    shape_coding: bool,
+    keys_label: &str,
+    mouse_steering: bool,
     settings_open: bool,
 ) -> u16 {
-    const VALUE_LABEL_WIDTH: usize = 6;
     let labels = if settings_open {
         [
             format!("{:<VALUE_LABEL_WIDTH$}:  {start_speed_level}", "Speed"),
             format!("{:<VALUE_LABEL_WIDTH$}:  {}", "Theme", theme.name),
             format!(
                 "{:<VALUE_LABEL_WIDTH$}:  {}",
-                "Grid",
-                if checkerboard_enabled { "On" } else { "Off" }
+                "Appearance",
+                appearance.label()
+            ),
+            format!(
+                "{:<VALUE_LABEL_WIDTH$}:  {}",
+                "Background",
+                background.label()
             ),
             format!(
                 "{:<VALUE_LABEL_WIDTH$}:  {}",
                 "Border",
                 if game_border_enabled { "On" } else { "Off" }
             ),
+            format!(
+                "{:<VALUE_LABEL_WIDTH$}:  {}",
+                "Shapes",
+                if shape_coding { "On" } else { "Off" }
+            ),
+            format!("{:<VALUE_LABEL_WIDTH$}:  {keys_label}", "Keys"),
+            format!(
+                "{:<VALUE_LABEL_WIDTH$}:  {}",
+                "Steering",
+                if mouse_steering { "On" } else { "Off" }
+            ),
             "Back".to_string(),
         ]
     } else {
@@ -1046,6 +1508,10 @@ fn start_menu_content_width(
             "Quit".to_string(),
             String::new(),
             String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
         ]
     };
 
@@ -1059,6 +1525,15 @@ fn start_menu_content_width(
     widest.min(u16::MAX as usize) as u16
 }
 
+/// Names the active key layout, or `Custom` once any key was rebound.
+fn keys_label(preset: KeyPreset, bindings: &KeyBindings) -> &'static str {
+    if *bindings == KeyBindings::preset(preset) {
+        preset.label()
+    } else {
+        "Custom"
+    }
+}
+
 fn pause_menu_content_width(theme: &Theme) -> u16 {
     let labels = [
         "Resume".to_string(),
@@ -1101,6 +1576,20 @@ fn menu_popup_height(title_rows: u16, menu_rows: u16) -> u16 {
 }
 
 const MENU_MARGIN_ROWS: u16 = 2;
+/// Entries in the start menu: start, settings and quit.
+pub const START_MENU_ITEM_COUNT: usize = 3;
+pub const START_MENU_START_IDX: usize = 0;
+pub const START_MENU_SETTINGS_IDX: usize = 1;
+pub const START_MENU_QUIT_IDX: usize = 2;
+/// Entries in the pause menu: resume, theme and quit.
+pub const PAUSE_MENU_ITEM_COUNT: usize = 3;
+pub const PAUSE_MENU_RESUME_IDX: usize = 0;
+pub const PAUSE_MENU_THEME_IDX: usize = 1;
+pub const PAUSE_MENU_QUIT_IDX: usize = 2;
+/// Entries in the game-over menu: play again and quit.
+pub const GAME_OVER_MENU_ITEM_COUNT: usize = 2;
+pub const GAME_OVER_MENU_PLAY_AGAIN_IDX: usize = 0;
+pub const GAME_OVER_MENU_QUIT_IDX: usize = 1;
 
 fn centered_rect_with_max_width(area: Rect, max_width: u16) -> Rect {
     if area.width <= max_width {
@@ -1154,40 +1643,39 @@ fn table_row(
     ])
 }
 
-fn render_outer_table_border(frame: &mut Frame<'_>, table_area: Rect, theme: &Theme) {
+fn render_outer_table_border(buf: &mut Buffer, table_area: Rect, theme: &Theme) {
     if table_area.width == 0 || table_area.height == 0 {
         return;
     }
 
-    let screen = frame.area();
+    let screen = buf.area;
     let style = Style::default().fg(theme.ui_bright).bg(theme.ui_bg);
-    let buffer = frame.buffer_mut();
 
     if table_area.y > screen.y {
         let top_y = table_area.y - 1;
         for x in table_area.x..table_area.right() {
-            buffer.set_string(x, top_y, "▁", style);
+            buf.set_string(x, top_y, "▁", style);
         }
     }
 
     if table_area.bottom() < screen.bottom() {
         let bottom_y = table_area.bottom();
         for x in table_area.x..table_area.right() {
-            buffer.set_string(x, bottom_y, "▔", style);
+            buf.set_string(x, bottom_y, "▔", style);
         }
     }
 
     if table_area.x > screen.x {
         let left_x = table_area.x - 1;
         for y in table_area.y..table_area.bottom() {
-            buffer.set_string(left_x, y, "▕", style);
+            buf.set_string(left_x, y, "▕", style);
         }
     }
 
     if table_area.right() < screen.right() {
         let right_x = table_area.right();
         for y in table_area.y..table_area.bottom() {
-            buffer.set_string(right_x, y, "▏", style);
+            buf.set_string(right_x, y, "▏", style);
         }
     }
 }
@@ -1246,11 +1734,10 @@ fn wrap_text_words(text: &str, max_width: usize) -> Vec<String> {
     lines
 }
 
-fn render_menu_panel(frame: &mut Frame<'_>, area: Rect, theme: &Theme) {
-    frame.render_widget(
-        Paragraph::new("").style(Style::default().bg(theme.ui_bg).fg(theme.ui_text)),
-        area,
-    );
+fn render_menu_panel(buf: &mut Buffer, area: Rect, theme: &Theme) {
+    Paragraph::new("")
+        .style(Style::default().bg(theme.ui_bg).fg(theme.ui_text))
+        .render(area, buf);
 
     if area.height < 2 {
         return;
@@ -1260,15 +1747,14 @@ fn render_menu_panel(frame: &mut Frame<'_>, area: Rect, theme: &Theme) {
     let bottom_y = area.bottom().saturating_sub(1);
     let margin_style = Style::default().fg(theme.ui_bg).bg(theme.field_bg);
     let palette = glyphs();
-    let buffer = frame.buffer_mut();
 
     for x in area.x..area.right() {
-        buffer.set_string(x, top_y, palette.half_lower, margin_style);
-        buffer.set_string(x, bottom_y, palette.half_upper, margin_style);
+        buf.set_string(x, top_y, palette.half_lower, margin_style);
+        buf.set_string(x, bottom_y, palette.half_upper, margin_style);
     }
 }
 
-fn render_menu_bottom_margin(frame: &mut Frame<'_>, area: Rect, theme: &Theme) {
+fn render_menu_bottom_margin(buf: &mut Buffer, area: Rect, theme: &Theme) {
     if area.height < 1 {
         return;
     }
@@ -1276,9 +1762,8 @@ fn render_menu_bottom_margin(frame: &mut Frame<'_>, area: Rect, theme: &Theme) {
     let bottom_y = area.bottom().saturating_sub(1);
     let margin_style = Style::default().fg(theme.ui_bg).bg(theme.field_bg);
     let half_upper = glyphs().half_upper;
-    let buffer = frame.buffer_mut();
     for x in area.x..area.right() {
-        buffer.set_string(x, bottom_y, half_upper, margin_style);
+        buf.set_string(x, bottom_y, half_upper, margin_style);
     }
 }
 
@@ -1311,10 +1796,193 @@ fn longest_theme_name_width(themes: &[ThemeItem]) -> usize {
 
 #[cfg(test)]
 mod tests {
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
     use super::{
-        GameOverTitleMode, StartTitleMode, choose_game_over_title_mode, choose_start_title_mode,
+        GameOverTitleMode, MenuHits, MenuTarget, StartTitleMode, ThemeSelectView,
+        choose_game_over_title_mode, choose_start_title_mode, render_key_editor, render_pause_menu,
+        render_theme_editor, render_theme_preview,
     };
     use crate::block_font::text_width;
+    use crate::config::fallback_theme;
+    use crate::input::{Direction, GameInput};
+    use crate::keymap::{KeyBindings, KeyPreset};
+    use crate::theme::ThemeItem;
+    use crate::ui::key_editor::KeyEditor;
+    use crate::ui::theme_editor::ThemeEditor;
+
+    fn render_editor_text(editor: &ThemeEditor) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(60, 32)).expect("test terminal");
+        terminal
+            .draw(|frame| {
+                let area = frame.area();
+                render_theme_editor(frame.buffer_mut(), area, editor, &KeyBindings::default());
+            })
+            .expect("editor should draw");
+        let buffer = terminal.backend().buffer();
+        buffer
+            .content
+            .chunks(usize::from(buffer.area.width))
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>() + "\n")
+            .collect()
+    }
+
+    /// Renders the pause menu and returns its hit map with the screen text.
+    fn render_pause_hits(theme_select: Option<ThemeSelectView<'_>>) -> (MenuHits, Vec<String>) {
+        let theme = fallback_theme();
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).expect("test terminal");
+        let mut hits = MenuHits::default();
+        terminal
+            .draw(|frame| {
+                let area = frame.area();
+                hits = render_pause_menu(frame.buffer_mut(), area, &theme, false, 0, theme_select);
+            })
+            .expect("pause menu should draw");
+        let buffer = terminal.backend().buffer();
+        let rows = buffer
+            .content
+            .chunks(usize::from(buffer.area.width))
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect())
+            .collect();
+        (hits, rows)
+    }
+
+    /// Finds the screen cell where `label` is drawn.
+    fn locate(rows: &[String], label: &str) -> (u16, u16) {
+        rows.iter()
+            .enumerate()
+            .find_map(|(row, text)| {
+                let column = text.find(label)?;
+                let column = text[..column].chars().count();
+                Some((column as u16, row as u16))
+            })
+            .unwrap_or_else(|| panic!("'{label}' should be drawn"))
+    }
+
+    #[test]
+    fn clicks_hit_the_menu_rows_they_land_on() {
+        let (hits, rows) = render_pause_hits(None);
+
+        for (idx, label) in ["Resume", "Theme:", "Quit"].into_iter().enumerate() {
+            let (column, row) = locate(&rows, label);
+            assert_eq!(
+                hits.target_at(column, row),
+                Some(MenuTarget::PauseItem(idx))
+            );
+        }
+        let (column, row) = locate(&rows, "PAUSED");
+        assert_eq!(hits.target_at(column, row), None);
+        assert_eq!(hits.target_at(0, 0), None);
+    }
+
+    #[test]
+    fn theme_list_clicks_report_theme_indices_not_rows() {
+        let themes: Vec<ThemeItem> = ["Alpha", "Bravo", "Charlie", "Delta"]
+            .into_iter()
+            .map(|name| {
+                let mut theme = fallback_theme();
+                theme.name = name.into();
+                ThemeItem {
+                    id: name.to_lowercase(),
+                    theme,
+                }
+            })
+            .collect();
+        let bindings = KeyBindings::default();
+        let (hits, rows) = render_pause_hits(Some(ThemeSelectView {
+            selected_idx: 0,
+            themes: &themes,
+            preview_step: 0,
+            bindings: &bindings,
+        }));
+
+        // The list is centered on the selection, so it starts at Charlie.
+        // Bravo's row is hidden under the popup's bottom margin.
+        for (idx, name) in [(2, "Charlie"), (3, "Delta"), (0, "Alpha")] {
+            let (column, row) = locate(&rows, name);
+            assert_eq!(hits.target_at(column, row), Some(MenuTarget::Theme(idx)));
+        }
+        let (column, row) = locate(&rows, "Alpha");
+        assert_eq!(hits.target_at(column, row + 1), None);
+        // The pause menu underneath is covered by the list and not clickable.
+        assert!(
+            hits.regions
+                .iter()
+                .all(|(_, target)| matches!(target, MenuTarget::Theme(_)))
+        );
+    }
+
+    #[test]
+    fn theme_preview_draws_snake_and_food_in_theme_colors() {
+        let mut theme = fallback_theme();
+        theme.snake_head = ratatui::style::Color::Rgb(1, 2, 3);
+        theme.super_food = ratatui::style::Color::Rgb(4, 5, 6);
+        let mut terminal = Terminal::new(TestBackend::new(30, 14)).expect("test terminal");
+        terminal
+            .draw(|frame| {
+                let area = frame.area();
+                render_theme_preview(frame.buffer_mut(), area, &theme, 3, &KeyBindings::default());
+            })
+            .expect("preview should draw");
+
+        let buffer = terminal.backend().buffer();
+        let uses = |color| {
+            buffer
+                .content
+                .iter()
+                .any(|cell| cell.fg == color || cell.bg == color)
+        };
+        assert!(uses(theme.snake_head));
+        assert!(uses(theme.super_food));
+        assert!(uses(theme.field_bg_alt));
+    }
+
+    #[test]
+    fn theme_editor_lists_fields_and_shows_sliders_in_hsl_mode() {
+        let mut editor = ThemeEditor::new(&fallback_theme());
+        let text = render_editor_text(&editor);
+        assert!(text.contains("Edit theme"));
+        assert!(text.contains("> Head"));
+        assert!(text.contains("Field alt"));
+        assert!(text.contains("Save"));
+        assert!(!text.contains("°"));
+
+        let _ = editor.handle_input(GameInput::Direction(Direction::Right));
+        let text = render_editor_text(&editor);
+        assert!(text.contains("> H "));
+        assert!(text.contains("°"));
+    }
+
+    #[test]
+    fn key_editor_shows_bound_keys_and_capture_prompt() {
+        let mut editor = KeyEditor::new(KeyPreset::Default, &KeyBindings::default());
+        let draw = |editor: &KeyEditor| {
+            let mut terminal = Terminal::new(TestBackend::new(60, 20)).expect("test terminal");
+            terminal
+                .draw(|frame| {
+                    let area = frame.area();
+                    render_key_editor(frame.buffer_mut(), area, editor, &fallback_theme());
+                })
+                .expect("key editor should draw");
+            let buffer = terminal.backend().buffer().clone();
+            buffer
+                .content
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+        };
+
+        let text = draw(&editor);
+        assert!(text.contains("Preset"));
+        assert!(text.contains("> Up          ↑/W"));
+        assert!(text.contains("Enter: change  Esc: close"));
+
+        let _ = editor.handle_input(GameInput::Confirm);
+        let text = draw(&editor);
+        assert!(text.contains("press a key"));
+        assert!(text.contains("Press a key for Up"));
+    }
 
     #[test]
     fn title_mode_uses_full_block_when_wide_enough() {
@@ -1,2 +1,4 @@
 pub mod hud;
+pub mod key_editor;
 pub mod menu;
+pub mod theme_editor;
@@ -0,0 +1,491 @@
+use ratatui::style::Color;
+
+use crate::color::{color_to_rgb, hsl_to_rgb, rgb_to_hsl};
+use crate::config::Theme;
+use crate::input::{Direction, GameInput};
+use crate::theme::{THEME_KEYS, color_value, parse_color_value, theme_color, theme_color_mut};
+
+/// Row holding the theme name; the color rows follow it in [`THEME_KEYS`] order.
+pub const NAME_ROW: usize = 0;
+pub const SAVE_ROW: usize = THEME_KEYS.len() + 1;
+pub const CANCEL_ROW: usize = THEME_KEYS.len() + 2;
+pub const ROW_COUNT: usize = THEME_KEYS.len() + 3;
+
+/// Longest name or color value accepted while typing.
+const MAX_TEXT_LEN: usize = 32;
+const HUE_STEP: u16 = 5;
+const PERCENT_STEP: u8 = 2;
+
+/// Which HSL slider the arrow keys adjust.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum HslChannel {
+    Hue,
+    Saturation,
+    Lightness,
+}
+
+impl HslChannel {
+    fn next(self) -> Self {
+        match self {
+            Self::Hue => Self::Saturation,
+            Self::Saturation => Self::Lightness,
+            Self::Lightness => Self::Hue,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            Self::Hue => Self::Lightness,
+            Self::Saturation => Self::Hue,
+            Self::Lightness => Self::Saturation,
+        }
+    }
+}
+
+/// Hue in degrees plus saturation and lightness in percent.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Hsl {
+    pub hue: u16,
+    pub saturation: u8,
+    pub lightness: u8,
+}
+
+impl Hsl {
+    fn from_color(color: Color) -> Self {
+        let (hue, saturation, lightness) = rgb_to_hsl(color_to_rgb(color).unwrap_or((0, 0, 0)));
+        Self {
+            hue: (hue.round() as u16) % 360,
+            saturation: (saturation * 100.0).round() as u8,
+            lightness: (lightness * 100.0).round() as u8,
+        }
+    }
+
+    /// Returns the RGB color these slider positions describe.
+    #[must_use]
+    pub fn color(self) -> Color {
+        let (r, g, b) = hsl_to_rgb(
+            f32::from(self.hue),
+            f32::from(self.saturation) / 100.0,
+            f32::from(self.lightness) / 100.0,
+        );
+        Color::Rgb(r, g, b)
+    }
+
+    fn adjust(&mut self, channel: HslChannel, increase: bool) {
+        match channel {
+            HslChannel::Hue if increase => self.hue = (self.hue + HUE_STEP) % 360,
+            HslChannel::Hue => self.hue = (self.hue + 360 - HUE_STEP) % 360,
+            HslChannel::Saturation => self.saturation = step_percent(self.saturation, increase),
+            HslChannel::Lightness => self.lightness = step_percent(self.lightness, increase),
+        }
+    }
+}
+
+fn step_percent(value: u8, increase: bool) -> u8 {
+    if increase {
+        value.saturating_add(PERCENT_STEP).min(100)
+    } else {
+        value.saturating_sub(PERCENT_STEP)
+    }
+}
+
+/// What the editor is doing with the selected row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditorMode {
+    /// Moving between rows.
+    Browse,
+    /// Typing the name or a color value. `before` is restored on cancel.
+    Text { buffer: String, before: Color },
+    /// Adjusting the selected color with HSL sliders.
+    Hsl {
+        channel: HslChannel,
+        hsl: Hsl,
+        before: Color,
+    },
+}
+
+/// What the game loop should do after the editor handled an input.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EditorAction {
+    None,
+    /// Write the edited theme to the user theme directory.
+    Save,
+    /// Leave the editor, discarding edits.
+    Close,
+}
+
+/// Working copy of a theme being edited in game.
+///
+/// Every change is applied to [`Self::theme`] right away, so drawing the
+/// game with it previews the edit live.
+#[derive(Debug, Clone)]
+pub struct ThemeEditor {
+    theme: Theme,
+    selected_row: usize,
+    mode: EditorMode,
+    error: Option<String>,
+}
+
+impl ThemeEditor {
+    /// Starts editing a copy of `base` under a new name, so saving does not
+    /// replace the original theme by accident.
+    #[must_use]
+    pub fn new(base: &Theme) -> Self {
+        let mut theme = base.clone();
+        if !theme.name.ends_with(" Custom") {
+            theme.name = format!("{} Custom", theme.name);
+        }
+
+        Self {
+            theme,
+            selected_row: 1,
+            mode: EditorMode::Browse,
+            error: None,
+        }
+    }
+
+    #[must_use]
+    pub fn theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    #[must_use]
+    pub fn selected_row(&self) -> usize {
+        self.selected_row
+    }
+
+    #[must_use]
+    pub fn mode(&self) -> &EditorMode {
+        &self.mode
+    }
+
+    /// Returns the last problem to show the player, if any.
+    #[must_use]
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn set_error(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Whether keys should arrive as typed characters rather than actions.
+    #[must_use]
+    pub fn wants_text_input(&self) -> bool {
+        matches!(self.mode, EditorMode::Text { .. })
+    }
+
+    /// Returns the theme key edited by `row`, or `None` for the name, save
+    /// and cancel rows.
+    #[must_use]
+    pub fn row_key(row: usize) -> Option<&'static str> {
+        row.checked_sub(1)
+            .and_then(|idx| THEME_KEYS.get(idx))
+            .copied()
+    }
+
+    /// Returns the label shown for `row`.
+    #[must_use]
+    pub fn row_label(row: usize) -> &'static str {
+        match row {
+            NAME_ROW => "Name",
+            SAVE_ROW => "Save",
+            CANCEL_ROW => "Cancel",
+            _ => match Self::row_key(row) {
+                Some("snake_head") => "Head",
+                Some("snake_body") => "Body",
+                Some("snake_tail") => "Tail",
+                Some("food") => "Food",
+                Some("super_food") => "Super food",
+                Some("terminal_bg") => "Terminal bg",
+                Some("field_bg") => "Field bg",
+                Some("field_bg_alt") => "Field alt",
+                Some("ui_bg") => "UI bg",
+                Some("ui_text") => "UI text",
+                Some("ui_accent") => "UI accent",
+                Some("ui_muted") => "UI muted",
+                Some("ui_bright") => "UI bright",
+                _ => "",
+            },
+        }
+    }
+
+    /// Returns the color shown on `row`, if it is a color row.
+    #[must_use]
+    pub fn row_color(&self, row: usize) -> Option<Color> {
+        Self::row_key(row).and_then(|key| theme_color(&self.theme, key))
+    }
+
+    pub fn handle_input(&mut self, input: GameInput) -> EditorAction {
+        match self.mode.clone() {
+            EditorMode::Browse => return self.handle_browse_input(input),
+            EditorMode::Text { buffer, before } => self.handle_text_input(input, buffer, before),
+            EditorMode::Hsl {
+                channel,
+                hsl,
+                before,
+            } => self.handle_hsl_input(input, channel, hsl, before),
+        }
+
+        EditorAction::None
+    }
+
+    fn handle_browse_input(&mut self, input: GameInput) -> EditorAction {
+        match input {
+            GameInput::Direction(Direction::Up) => {
+                self.selected_row = (self.selected_row + ROW_COUNT - 1) % ROW_COUNT;
+            }
+            GameInput::Direction(Direction::Down) => {
+                self.selected_row = (self.selected_row + 1) % ROW_COUNT;
+            }
+            GameInput::Confirm => match self.selected_row {
+                SAVE_ROW => return EditorAction::Save,
+                CANCEL_ROW => return EditorAction::Close,
+                NAME_ROW => {
+                    self.error = None;
+                    self.mode = EditorMode::Text {
+                        buffer: self.theme.name.clone(),
+                        before: Color::Reset,
+                    };
+                }
+                row => {
+                    if let Some(color) = self.row_color(row) {
+                        self.error = None;
+                        self.mode = EditorMode::Text {
+                            buffer: color_value(color),
+                            before: color,
+                        };
+                    }
+                }
+            },
+            GameInput::Direction(Direction::Right) => {
+                if let Some(color) = self.row_color(self.selected_row) {
+                    self.error = None;
+                    self.mode = EditorMode::Hsl {
+                        channel: HslChannel::Hue,
+                        hsl: Hsl::from_color(color),
+                        before: color,
+                    };
+                }
+            }
+            GameInput::Pause | GameInput::Direction(Direction::Left) => {
+                return EditorAction::Close;
+            }
+            _ => {}
+        }
+
+        EditorAction::None
+    }
+
+    fn handle_text_input(&mut self, input: GameInput, mut buffer: String, before: Color) {
+        match input {
+            GameInput::Char(ch) if buffer.chars().count() < MAX_TEXT_LEN => buffer.push(ch),
+            GameInput::Backspace => {
+                let _ = buffer.pop();
+            }
+            GameInput::Confirm => {
+                self.commit_text(&buffer);
+                return;
+            }
+            GameInput::Pause => {
+                self.set_selected_color(before);
+                self.error = None;
+                self.mode = EditorMode::Browse;
+                return;
+            }
+            _ => {}
+        }
+
+        // Preview a color as soon as the typed value parses.
+        if let Some(color) = parse_color_value(&buffer) {
+            self.set_selected_color(color);
+        }
+        self.mode = EditorMode::Text { buffer, before };
+    }
+
+    fn commit_text(&mut self, buffer: &str) {
+        let value = buffer.trim();
+        if self.selected_row == NAME_ROW {
+            if value.is_empty() {
+                self.error = Some("Name cannot be empty".to_owned());
+                return;
+            }
+            self.theme.name = value.to_owned();
+        } else {
+            let Some(color) = parse_color_value(value) else {
+                self.error = Some(format!("'{value}' is not a color"));
+                return;
+            };
+            self.set_selected_color(color);
+        }
+
+        self.error = None;
+        self.mode = EditorMode::Browse;
+    }
+
+    fn handle_hsl_input(
+        &mut self,
+        input: GameInput,
+        mut channel: HslChannel,
+        mut hsl: Hsl,
+        before: Color,
+    ) {
+        match input {
+            GameInput::Direction(Direction::Up) => channel = channel.previous(),
+            GameInput::Direction(Direction::Down) => channel = channel.next(),
+            GameInput::Direction(Direction::Left) => {
+                hsl.adjust(channel, false);
+                self.set_selected_color(hsl.color());
+            }
+            GameInput::Direction(Direction::Right) => {
+                hsl.adjust(channel, true);
+                self.set_selected_color(hsl.color());
+            }
+            GameInput::Confirm => {
+                self.mode = EditorMode::Browse;
+                return;
+            }
+            GameInput::Pause => {
+                self.set_selected_color(before);
+                self.mode = EditorMode::Browse;
+                return;
+            }
+            _ => {}
+        }
+
+        self.mode = EditorMode::Hsl {
+            channel,
+            hsl,
+            before,
+        };
+    }
+
+    fn set_selected_color(&mut self, color: Color) {
+        if let Some(slot) =
+            Self::row_key(self.selected_row).and_then(|key| theme_color_mut(&mut self.theme, key))
+        {
+            *slot = color;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Color;
+
+    use super::{CANCEL_ROW, EditorAction, EditorMode, NAME_ROW, SAVE_ROW, ThemeEditor};
+    use crate::config::fallback_theme;
+    use crate::input::{Direction, GameInput};
+
+    fn type_text(editor: &mut ThemeEditor, text: &str) {
+        for ch in text.chars() {
+            let _ = editor.handle_input(GameInput::Char(ch));
+        }
+    }
+
+    fn clear_text(editor: &mut ThemeEditor) {
+        for _ in 0..40 {
+            let _ = editor.handle_input(GameInput::Backspace);
+        }
+    }
+
+    #[test]
+    fn new_editor_copies_theme_under_a_new_name() {
+        let base = fallback_theme();
+        let editor = ThemeEditor::new(&base);
+
+        assert_eq!(editor.theme().name, format!("{} Custom", base.name));
+        assert_eq!(editor.theme().snake_head, base.snake_head);
+        assert_eq!(
+            ThemeEditor::new(editor.theme()).theme().name,
+            editor.theme().name
+        );
+    }
+
+    #[test]
+    fn typed_hex_value_previews_live_and_applies_on_confirm() {
+        let mut editor = ThemeEditor::new(&fallback_theme());
+        assert_eq!(ThemeEditor::row_label(editor.selected_row()), "Head");
+
+        let _ = editor.handle_input(GameInput::Confirm);
+        assert!(editor.wants_text_input());
+        clear_text(&mut editor);
+        type_text(&mut editor, "#12AB");
+        type_text(&mut editor, "ef");
+        assert_eq!(editor.theme().snake_head, Color::Rgb(0x12, 0xAB, 0xEF));
+
+        let _ = editor.handle_input(GameInput::Confirm);
+        assert_eq!(editor.mode(), &EditorMode::Browse);
+        assert_eq!(editor.theme().snake_head, Color::Rgb(0x12, 0xAB, 0xEF));
+    }
+
+    #[test]
+    fn invalid_value_keeps_editing_and_cancel_restores() {
+        let base = fallback_theme();
+        let mut editor = ThemeEditor::new(&base);
+
+        let _ = editor.handle_input(GameInput::Confirm);
+        clear_text(&mut editor);
+        type_text(&mut editor, "#12");
+        let _ = editor.handle_input(GameInput::Confirm);
+        assert!(editor.wants_text_input());
+        assert!(editor.error().is_some());
+
+        type_text(&mut editor, "3456");
+        let _ = editor.handle_input(GameInput::Pause);
+        assert_eq!(editor.mode(), &EditorMode::Browse);
+        assert_eq!(editor.theme().snake_head, base.snake_head);
+    }
+
+    #[test]
+    fn hsl_sliders_adjust_the_selected_color() {
+        let mut base = fallback_theme();
+        base.snake_head = Color::Rgb(255, 0, 0);
+        let mut editor = ThemeEditor::new(&base);
+
+        let _ = editor.handle_input(GameInput::Direction(Direction::Right));
+        for _ in 0..24 {
+            let _ = editor.handle_input(GameInput::Direction(Direction::Right));
+        }
+        assert_eq!(editor.theme().snake_head, Color::Rgb(0, 255, 0));
+
+        let _ = editor.handle_input(GameInput::Direction(Direction::Down));
+        let _ = editor.handle_input(GameInput::Direction(Direction::Down));
+        for _ in 0..25 {
+            let _ = editor.handle_input(GameInput::Direction(Direction::Right));
+        }
+        assert_eq!(editor.theme().snake_head, Color::Rgb(255, 255, 255));
+
+        let _ = editor.handle_input(GameInput::Pause);
+        assert_eq!(editor.theme().snake_head, Color::Rgb(255, 0, 0));
+    }
+
+    #[test]
+    fn name_row_renames_and_rejects_empty_names() {
+        let mut editor = ThemeEditor::new(&fallback_theme());
+        let _ = editor.handle_input(GameInput::Direction(Direction::Up));
+        assert_eq!(editor.selected_row(), NAME_ROW);
+
+        let _ = editor.handle_input(GameInput::Confirm);
+        clear_text(&mut editor);
+        let _ = editor.handle_input(GameInput::Confirm);
+        assert!(editor.error().is_some());
+
+        type_text(&mut editor, "Dusk");
+        let _ = editor.handle_input(GameInput::Confirm);
+        assert_eq!(editor.theme().name, "Dusk");
+    }
+
+    #[test]
+    fn save_and_cancel_rows_report_actions() {
+        let mut editor = ThemeEditor::new(&fallback_theme());
+        while editor.selected_row() != SAVE_ROW {
+            let _ = editor.handle_input(GameInput::Direction(Direction::Down));
+        }
+        assert_eq!(editor.handle_input(GameInput::Confirm), EditorAction::Save);
+
+        let _ = editor.handle_input(GameInput::Direction(Direction::Down));
+        assert_eq!(editor.selected_row(), CANCEL_ROW);
+        assert_eq!(editor.handle_input(GameInput::Confirm), EditorAction::Close);
+    }
+}
@@ -0,0 +1,508 @@
+//! Ratatui widgets for embedding the game in another terminal app.
+//!
+//! [`PlayField`], [`Hud`] and [`Menu`] draw one part of the screen each from
+//! a [`GameState`] and a [`Theme`]. [`GameView`] lays all three out the way
+//! the game itself does and takes a [`GameController`] as its state, so a
+//! host app only has to feed the controller events and draw the view.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::time::Instant;
+//!
+//! use crossterm::event;
+//! use terminal_snake::config::{GridSize, fallback_theme};
+//! use terminal_snake::controller::{ControllerEvent, GameController};
+//! use terminal_snake::widget::GameView;
+//!
+//! # fn main() -> std::io::Result<()> {
+//! let mut terminal = ratatui::init();
+//! let theme = fallback_theme();
+//! // The view resizes the board to fit the terminal on the first draw.
+//! let bounds = GridSize { width: 40, height: 20 };
+//! let mut game = GameController::new(bounds, Instant::now());
+//! loop {
+//!     terminal.draw(|frame| {
+//!         frame.render_stateful_widget(GameView::new(&theme), frame.area(), &mut game);
+//!     })?;
+//!     let timeout = game
+//!         .next_wake(Instant::now())
+//!         .saturating_duration_since(Instant::now());
+//!     if event::poll(timeout)?
+//!         && game.handle_event(event::read()?, Instant::now()) == ControllerEvent::Quit
+//!     {
+//!         break;
+//!     }
+//!     game.update(Instant::now());
+//! }
+//! ratatui::restore();
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::Instant;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::Style;
+use ratatui::widgets::{Block, StatefulWidget, Widget};
+
+use crate::background::{BackgroundChoice, BackgroundPattern};
+use crate::config::{GridSize, HUD_BOTTOM_MARGIN_Y, Theme, glyphs};
+use crate::controller::GameController;
+use crate::game::{GameState, GameStatus};
+use crate::keymap::{KeyBindings, KeyPreset};
+use crate::renderer::{
+    FieldOptions, RenderCache, inset_play_area, render_play_area, render_play_area_border,
+};
+use crate::theme::AppearancePreference;
+use crate::ui::hud::{HudInfo, HudValueFlash, render_hud};
+use crate::ui::menu::{
+    MenuHits, MenuTarget, render_game_over_menu, render_pause_menu, render_start_menu,
+};
+
+/// HUD rows below the play area: two info lines and the bottom margin.
+const HUD_ROWS: u16 = 2 + HUD_BOTTOM_MARGIN_Y;
+
+/// Snake, food and background of a game, drawn to fill the whole area.
+///
+/// Render it as a [`StatefulWidget`] with a [`RenderCache`] kept between
+/// frames to redraw only the cells that changed; as a plain [`Widget`] it
+/// composites every cell each frame.
+#[derive(Debug, Clone, Copy)]
+pub struct PlayField<'a> {
+    state: &'a GameState,
+    theme: &'a Theme,
+    background: &'a BackgroundPattern,
+    shape_coding: bool,
+}
+
+impl<'a> PlayField<'a> {
+    /// Builds a play field drawn with the theme's own background pattern.
+    #[must_use]
+    pub fn new(state: &'a GameState, theme: &'a Theme) -> Self {
+        Self {
+            state,
+            theme,
+            background: &theme.background,
+            shape_coding: false,
+        }
+    }
+
+    /// Replaces the theme's background pattern.
+    #[must_use]
+    pub fn background(mut self, background: &'a BackgroundPattern) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Tells snake and food apart by shape as well as color.
+    #[must_use]
+    pub fn shape_coding(mut self, enabled: bool) -> Self {
+        self.shape_coding = enabled;
+        self
+    }
+
+    /// Returns the grid size that exactly fills `area` in the active glyph
+    /// mode, for sizing the [`GameState`] drawn into it.
+    #[must_use]
+    pub fn grid_size(area: Rect) -> GridSize {
+        glyphs()
+            .mode
+            .grid_size_for_viewport(area.width, area.height)
+    }
+}
+
+impl StatefulWidget for PlayField<'_> {
+    type State = RenderCache;
+
+    fn render(self, area: Rect, buf: &mut Buffer, cache: &mut RenderCache) {
+        Block::default()
+            .style(Style::new().bg(self.theme.field_bg))
+            .render(area, buf);
+        render_play_area(
+            buf,
+            area,
+            self.state,
+            self.theme,
+            FieldOptions {
+                background: self.background,
+                shape_coding: self.shape_coding,
+            },
+            cache,
+        );
+    }
+}
+
+impl Widget for PlayField<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        StatefulWidget::render(self, area, buf, &mut RenderCache::default());
+    }
+}
+
+/// Score and status lines along the bottom of the area.
+///
+/// The rest of the area is left untouched; [`Hud::field_area`] returns the
+/// part of it a [`PlayField`] goes into.
+#[derive(Debug, Clone, Copy)]
+pub struct Hud<'a> {
+    state: &'a GameState,
+    theme: &'a Theme,
+    high_score: u32,
+    /// High score before the current game, to tell a new record apart.
+    reference_high_score: u32,
+}
+
+impl<'a> Hud<'a> {
+    #[must_use]
+    pub fn new(state: &'a GameState, theme: &'a Theme) -> Self {
+        Self {
+            state,
+            theme,
+            high_score: 0,
+            reference_high_score: 0,
+        }
+    }
+
+    /// Sets the high score shown next to the score.
+    #[must_use]
+    pub fn high_score(mut self, high_score: u32) -> Self {
+        self.high_score = high_score;
+        self.reference_high_score = high_score;
+        self
+    }
+
+    /// Returns the gameplay viewport left above the HUD in `area`, inset by
+    /// the play area margins.
+    #[must_use]
+    pub fn field_area(area: Rect) -> Rect {
+        inset_play_area(play_area_above_hud(area))
+    }
+}
+
+impl Widget for Hud<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let _play_area = render_hud(
+            buf,
+            area,
+            self.state,
+            &HudInfo {
+                high_score: self.high_score.max(self.state.score),
+                game_over_reference_high_score: self.reference_high_score,
+                theme: self.theme,
+                debug: false,
+                debug_line: String::new(),
+                now: Instant::now(),
+                value_flash: HudValueFlash::default(),
+                toast: None,
+            },
+        );
+    }
+}
+
+/// Returns the part of `area` above the HUD rows, margins included.
+fn play_area_above_hud(area: Rect) -> Rect {
+    let [play_area, _] =
+        Layout::vertical([Constraint::Min(0), Constraint::Length(HUD_ROWS)]).areas(area);
+    play_area
+}
+
+/// Selection and click targets of the start, pause and game-over menus.
+#[derive(Debug, Clone, Default)]
+pub struct MenuState {
+    /// Highlighted entry of whichever menu is showing.
+    pub selected_idx: usize,
+    /// Entries drawn in the last frame.
+    hits: MenuHits,
+}
+
+impl MenuState {
+    /// Returns the menu entry drawn at a terminal cell in the last frame.
+    #[must_use]
+    pub fn target_at(&self, column: u16, row: u16) -> Option<MenuTarget> {
+        self.hits.target_at(column, row)
+    }
+}
+
+/// Start, pause or game-over popup centered in the area, matching the game
+/// status; draws nothing while the game is playing.
+///
+/// The start menu offers Start, Settings and Quit. Settings and the pause
+/// menu's Theme entry are left to the host, see
+/// [`crate::controller::ControllerEvent`].
+#[derive(Debug, Clone, Copy)]
+pub struct Menu<'a> {
+    state: &'a GameState,
+    theme: &'a Theme,
+    bindings: Option<&'a KeyBindings>,
+    reference_high_score: u32,
+}
+
+impl<'a> Menu<'a> {
+    #[must_use]
+    pub fn new(state: &'a GameState, theme: &'a Theme) -> Self {
+        Self {
+            state,
+            theme,
+            bindings: None,
+            reference_high_score: 0,
+        }
+    }
+
+    /// Sets the key bindings named in menu hints; defaults to the default
+    /// bindings.
+    #[must_use]
+    pub fn bindings(mut self, bindings: &'a KeyBindings) -> Self {
+        self.bindings = Some(bindings);
+        self
+    }
+
+    /// Sets the high score from before this game, which the game-over
+    /// screen compares the final score against.
+    #[must_use]
+    pub fn high_score(mut self, high_score: u32) -> Self {
+        self.reference_high_score = high_score;
+        self
+    }
+}
+
+impl StatefulWidget for Menu<'_> {
+    type State = MenuState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, menu: &mut MenuState) {
+        let default_bindings;
+        let bindings = match self.bindings {
+            Some(bindings) => bindings,
+            None => {
+                default_bindings = KeyBindings::default();
+                &default_bindings
+            }
+        };
+        let state = self.state;
+        menu.hits = match state.status {
+            GameStatus::Paused if state.is_start_screen() => render_start_menu(
+                buf,
+                area,
+                self.reference_high_score,
+                self.theme,
+                false,
+                menu.selected_idx,
+                false,
+                0,
+                state.speed_level,
+                false,
+                AppearancePreference::default(),
+                BackgroundChoice::default(),
+                true,
+                false,
+                KeyPreset::default(),
+                bindings,
+                false,
+                None,
+            ),
+            GameStatus::Paused => {
+                render_pause_menu(buf, area, self.theme, false, menu.selected_idx, None)
+            }
+            GameStatus::GameOver | GameStatus::Victory => render_game_over_menu(
+                buf,
+                area,
+                state.score,
+                self.reference_high_score,
+                state.snake.len(),
+                state.play_area_coverage_percent(),
+                state.death_reason,
+                state.elapsed_duration(),
+                state.sprint_duration(),
+                self.theme,
+                menu.selected_idx,
+                bindings,
+            ),
+            GameStatus::Playing => MenuHits::default(),
+        };
+    }
+}
+
+/// The whole game screen: bordered play field, HUD and menus.
+///
+/// Rendering fits the controller's game to the gameplay viewport and records
+/// where the menus went, so clicks passed to the controller afterwards land
+/// on the entries the player sees.
+#[derive(Debug, Clone, Copy)]
+pub struct GameView<'a> {
+    theme: &'a Theme,
+}
+
+impl<'a> GameView<'a> {
+    #[must_use]
+    pub fn new(theme: &'a Theme) -> Self {
+        Self { theme }
+    }
+}
+
+impl StatefulWidget for GameView<'_> {
+    type State = GameController;
+
+    fn render(self, area: Rect, buf: &mut Buffer, controller: &mut GameController) {
+        let theme = self.theme;
+        Block::default()
+            .style(Style::new().bg(theme.terminal_bg))
+            .render(area, buf);
+
+        let play_area = play_area_above_hud(area);
+        let gameplay_area = inset_play_area(play_area);
+        controller.fit(gameplay_area);
+
+        let view = controller.view();
+        Hud {
+            state: view.state,
+            theme,
+            high_score: view.high_score,
+            reference_high_score: view.reference_high_score,
+        }
+        .render(area, buf);
+        render_play_area_border(buf, play_area, gameplay_area, theme);
+        StatefulWidget::render(
+            PlayField::new(view.state, theme),
+            gameplay_area,
+            buf,
+            view.cache,
+        );
+        Menu {
+            state: view.state,
+            theme,
+            bindings: Some(view.bindings),
+            reference_high_score: view.reference_high_score,
+        }
+        .render(play_area, buf, view.menu);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use ratatui::buffer::Buffer;
+    use ratatui::layout::Rect;
+    use ratatui::widgets::{StatefulWidget, Widget};
+
+    use super::{GameView, Hud, Menu, MenuState, PlayField};
+    use crate::config::fallback_theme;
+    use crate::controller::GameController;
+    use crate::game::{GameState, GameStatus};
+    use crate::renderer::RenderCache;
+    use crate::ui::menu::MenuTarget;
+
+    fn row_text(buf: &Buffer, y: u16) -> String {
+        (buf.area.x..buf.area.right())
+            .map(|x| buf[(x, y)].symbol())
+            .collect()
+    }
+
+    /// Returns the terminal column where `text` starts on row `y`, counting
+    /// cells rather than bytes so wide or multi-byte borders do not shift it.
+    fn column_of(buf: &Buffer, y: u16, text: &str) -> Option<u16> {
+        (buf.area.x..buf.area.right()).find(|&x| {
+            (x..buf.area.right())
+                .map(|x| buf[(x, y)].symbol())
+                .collect::<String>()
+                .starts_with(text)
+        })
+    }
+
+    #[test]
+    fn play_field_draws_the_same_cells_with_or_without_a_cache() {
+        let theme = fallback_theme();
+        let area = Rect::new(0, 0, 40, 20);
+        let state = GameState::new_with_seed(PlayField::grid_size(area), 3);
+
+        let mut cached = Buffer::empty(area);
+        let mut cache = RenderCache::default();
+        StatefulWidget::render(
+            PlayField::new(&state, &theme),
+            area,
+            &mut cached,
+            &mut cache,
+        );
+        let mut uncached = Buffer::empty(area);
+        Widget::render(PlayField::new(&state, &theme), area, &mut uncached);
+
+        assert_eq!(cached, uncached);
+        assert!(
+            cached
+                .content()
+                .iter()
+                .any(|cell| cell.fg == theme.snake_head || cell.bg == theme.snake_head)
+        );
+    }
+
+    #[test]
+    fn hud_draws_along_the_bottom_and_leaves_the_field_area_above() {
+        let theme = fallback_theme();
+        let area = Rect::new(0, 0, 80, 30);
+        let state = GameState::new_with_seed(PlayField::grid_size(area), 3);
+        let mut buf = Buffer::empty(area);
+
+        Hud::new(&state, &theme)
+            .high_score(42)
+            .render(area, &mut buf);
+
+        let field = Hud::field_area(area);
+        assert!(field.bottom() < area.bottom() - 2);
+        assert!(row_text(&buf, area.bottom() - 3).contains("42"));
+        assert!(row_text(&buf, field.y).trim().is_empty());
+    }
+
+    #[test]
+    fn menu_follows_the_game_status_and_records_its_entries() {
+        let theme = fallback_theme();
+        let area = Rect::new(0, 0, 80, 30);
+        let mut state = GameState::new_with_seed(PlayField::grid_size(area), 3);
+        let mut menu = MenuState::default();
+
+        let mut buf = Buffer::empty(area);
+        Menu::new(&state, &theme).render(area, &mut buf, &mut menu);
+        assert_eq!(buf, Buffer::empty(area));
+        assert_eq!(menu.target_at(40, 15), None);
+
+        state.status = GameStatus::Paused;
+        Menu::new(&state, &theme).render(area, &mut buf, &mut menu);
+        let settings = (0..area.height)
+            .find(|&y| row_text(&buf, y).contains("Settings"))
+            .expect("start menu settings entry");
+        let column = column_of(&buf, settings, "Settings").expect("settings column");
+        assert_eq!(
+            menu.target_at(column, settings),
+            Some(MenuTarget::StartItem(1))
+        );
+
+        state.tick_count = 1;
+        let mut buf = Buffer::empty(area);
+        Menu::new(&state, &theme).render(area, &mut buf, &mut menu);
+        let paused = (0..area.height).any(|y| row_text(&buf, y).contains("PAUSED"));
+        assert!(paused);
+        let resume = (0..area.height)
+            .find(|&y| row_text(&buf, y).contains("Resume"))
+            .expect("resume entry");
+        let column = column_of(&buf, resume, "Resume").expect("resume column");
+        assert_eq!(
+            menu.target_at(column, resume),
+            Some(MenuTarget::PauseItem(0))
+        );
+    }
+
+    #[test]
+    fn game_view_fits_the_game_to_the_viewport() {
+        let theme = fallback_theme();
+        let area = Rect::new(0, 0, 90, 40);
+        let mut controller = GameController::new(PlayField::grid_size(area), Instant::now());
+        let mut buf = Buffer::empty(area);
+
+        GameView::new(&theme).render(area, &mut buf, &mut controller);
+
+        assert_eq!(
+            controller.state().bounds(),
+            PlayField::grid_size(Hud::field_area(area))
+        );
+        assert!((0..area.height).any(|y| row_text(&buf, y).contains("Settings")));
+    }
+}
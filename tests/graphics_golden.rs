@@ -0,0 +1,87 @@
+use terminal_snake::graphics::{PixelImage, encode_kitty, encode_sixel, kitty_delete};
+
+/// Renders escape characters as `\e` so golden files stay readable.
+fn visible(sequence: &str) -> String {
+    sequence.replace('\x1b', "\\e")
+}
+
+fn golden(name: &str) -> String {
+    let path = format!("{}/tests/golden/{name}", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read_to_string(&path)
+        .unwrap_or_else(|error| panic!("golden file {path} should be readable: {error}"))
+        .trim_end_matches('\n')
+        .to_owned()
+}
+
+#[test]
+fn kitty_transmission_matches_golden() {
+    let mut image = PixelImage::new(2, 1, [0, 0, 255]);
+    image.fill_rect(0.0, 0.0, 1.0, 1.0, [255, 0, 0]);
+
+    assert_eq!(
+        visible(&encode_kitty(&image, 1, 1)),
+        golden("kitty_two_pixels.golden")
+    );
+}
+
+#[test]
+fn kitty_payload_is_split_into_chunks() {
+    let image = PixelImage::new(33, 32, [10, 20, 30]);
+    let encoded = encode_kitty(&image, 4, 2);
+    let chunks: Vec<&str> = encoded.split("\x1b\\").filter(|c| !c.is_empty()).collect();
+
+    assert_eq!(chunks.len(), 2);
+    assert!(chunks[0].starts_with("\x1b_Ga=T,f=24,s=33,v=32,"));
+    assert!(chunks[0].contains(",m=1;"));
+    assert!(chunks[1].starts_with("\x1b_Gm=0;"));
+}
+
+#[test]
+fn kitty_delete_targets_play_field_image() {
+    assert_eq!(visible(&kitty_delete()), "\\e_Ga=d,d=I,i=31,q=2\\e\\");
+}
+
+#[test]
+fn sixel_bands_match_golden() {
+    let mut image = PixelImage::new(3, 7, [0, 0, 0]);
+    image.fill_rect(1.0, 0.0, 2.0, 7.0, [255, 255, 255]);
+    image.fill_rect(0.0, 6.0, 1.0, 7.0, [255, 0, 0]);
+
+    assert_eq!(
+        visible(&encode_sixel(&image)),
+        golden("sixel_two_bands.golden")
+    );
+}
+
+#[test]
+fn sixel_repeats_use_run_length_encoding() {
+    let image = PixelImage::new(8, 6, [0, 51, 102]);
+
+    assert_eq!(
+        visible(&encode_sixel(&image)),
+        golden("sixel_run_length.golden")
+    );
+}
+
+#[test]
+fn sixel_quantizes_large_palettes() {
+    let mut image = PixelImage::new(300, 1, [0, 0, 0]);
+    for x in 0..300u32 {
+        let shade = (x % 256) as u8;
+        image.fill_rect(
+            x as f32,
+            0.0,
+            x as f32 + 1.0,
+            1.0,
+            [shade, 255 - shade, x as u8],
+        );
+    }
+
+    let encoded = encode_sixel(&image);
+    let declared = encoded
+        .split('#')
+        .skip(1)
+        .filter(|part| part.contains(';'))
+        .count();
+    assert!(declared <= 256, "declared {declared} colors");
+}
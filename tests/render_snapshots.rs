@@ -0,0 +1,35 @@
+use terminal_snake::snapshot::{self, SnapshotStatus};
+
+const REGENERATE: &str = "cargo run --features snapshots --bin snapshots -- update";
+
+#[test]
+fn rendered_screens_match_golden_snapshots() {
+    let snapshots = snapshot::render_all();
+    let failures: Vec<String> = snapshots
+        .iter()
+        .filter_map(|snapshot| match snapshot.compare() {
+            Ok(SnapshotStatus::Matches) => None,
+            Ok(SnapshotStatus::Missing) => Some(format!("{}: no golden file", snapshot.name)),
+            Ok(SnapshotStatus::Differs { line }) => {
+                Some(format!("{}: differs at line {line}", snapshot.name))
+            }
+            Err(error) => Some(format!("{}: {error}", snapshot.name)),
+        })
+        .collect();
+
+    assert!(
+        failures.is_empty(),
+        "render snapshots do not match:\n  {}\nIf the change is intended, run `{REGENERATE}` and review the diff.",
+        failures.join("\n  ")
+    );
+}
+
+#[test]
+fn every_golden_snapshot_is_still_rendered() {
+    let stale = snapshot::stale_files(&snapshot::render_all()).expect("snapshot dir readable");
+
+    assert!(
+        stale.is_empty(),
+        "golden snapshots without a scene: {stale:?}\nRun `{REGENERATE}` to remove them."
+    );
+}
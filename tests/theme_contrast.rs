@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use ratatui::style::Color;
+use terminal_snake::color::color_to_rgb;
+use terminal_snake::config::Theme;
+use terminal_snake::theme::lint::{check_theme, contrast_ratio};
+use terminal_snake::theme::{Appearance, ThemeCatalog};
+
+/// Minimum CIE76 color difference between entities that must not be
+/// confused, measured after simulating the preset's color deficiency.
+const MIN_ENTITY_DELTA_E: f64 = 30.0;
+
+/// Entity contrast against the field required of the color-deficiency presets.
+const PRESET_MIN_CONTRAST: f64 = 4.5;
+
+/// WCAG AAA contrast required of the high-contrast preset.
+const HIGH_CONTRAST_MIN_CONTRAST: f64 = 7.0;
+
+/// Machado et al. (2009) full-severity simulation matrices, in linear RGB.
+const PROTANOPIA: [[f64; 3]; 3] = [
+    [0.152_286, 1.052_583, -0.204_868],
+    [0.114_503, 0.786_281, 0.099_216],
+    [-0.003_882, -0.048_116, 1.051_998],
+];
+const DEUTERANOPIA: [[f64; 3]; 3] = [
+    [0.367_322, 0.860_646, -0.227_968],
+    [0.280_085, 0.672_501, 0.047_413],
+    [-0.011_820, 0.042_940, 0.968_881],
+];
+const TRITANOPIA: [[f64; 3]; 3] = [
+    [1.255_528, -0.076_749, -0.178_779],
+    [-0.078_411, 0.930_809, 0.147_602],
+    [0.004_733, 0.691_367, 0.303_900],
+];
+const NORMAL_VISION: [[f64; 3]; 3] = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+/// WCAG contrast every bundled theme needs for play-field entities against
+/// `field_bg` (graphical objects) and for `ui_text` against `ui_bg` (body
+/// text), in both appearances.
+const ENTITY_MIN_CONTRAST: f64 = 3.0;
+const UI_TEXT_MIN_CONTRAST: f64 = 4.5;
+
+/// Bundled pairs allowed to miss their target, as (theme, pair).
+///
+/// Ayu and TM keep their upstream `ui_text` on `ui_bg` colors, which measure
+/// 4.33:1; neither has light variants, so this covers both appearances. Each
+/// entry must still be needed, so fixing a theme means removing its
+/// exemption here.
+const CONTRAST_EXEMPTIONS: [(&str, &str); 2] = [("ayu", "ui_text"), ("tm", "ui_text")];
+
+fn builtin_theme(id: &str) -> Theme {
+    let catalog = ThemeCatalog::builtin(Appearance::Dark);
+    catalog
+        .items()
+        .iter()
+        .find(|item| item.id == id)
+        .unwrap_or_else(|| panic!("built-in theme '{id}' should exist"))
+        .theme
+        .clone()
+}
+
+fn linear_rgb(color: Color) -> [f64; 3] {
+    let (r, g, b) = color_to_rgb(color).expect("preset colors should be RGB");
+    [r, g, b].map(|channel| {
+        let c = f64::from(channel) / 255.0;
+        if c <= 0.040_45 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    })
+}
+
+fn simulate(rgb: [f64; 3], matrix: &[[f64; 3]; 3]) -> [f64; 3] {
+    matrix.map(|row| (row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]).clamp(0.0, 1.0))
+}
+
+fn lab([r, g, b]: [f64; 3]) -> [f64; 3] {
+    let f = |t: f64| {
+        if t > 0.008_856 {
+            t.cbrt()
+        } else {
+            7.787 * t + 16.0 / 116.0
+        }
+    };
+    let x = f((0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.950_47);
+    let y = f(0.2126 * r + 0.7152 * g + 0.0722 * b);
+    let z = f((0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.088_83);
+    [116.0 * y - 16.0, 500.0 * (x - y), 200.0 * (y - z)]
+}
+
+fn delta_e(a: Color, b: Color, matrix: &[[f64; 3]; 3]) -> f64 {
+    let a = lab(simulate(linear_rgb(a), matrix));
+    let b = lab(simulate(linear_rgb(b), matrix));
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (a - b).powi(2))
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// Pairs a player must tell apart: each food against the other and against
+/// every part of the snake.
+fn entity_pairs(theme: &Theme) -> Vec<(&'static str, Color, &'static str, Color)> {
+    let snake = [
+        ("snake_head", theme.snake_head),
+        ("snake_body", theme.snake_body),
+        ("snake_tail", theme.snake_tail),
+    ];
+    let mut pairs = vec![("food", theme.food, "super_food", theme.super_food)];
+    for (food, food_color) in [("food", theme.food), ("super_food", theme.super_food)] {
+        for (part, part_color) in snake {
+            pairs.push((food, food_color, part, part_color));
+        }
+    }
+    pairs
+}
+
+fn assert_min_contrast(id: &str, theme: &Theme, minimum: f64) {
+    for (name, fg, bg) in [
+        ("snake_head", theme.snake_head, theme.field_bg),
+        ("food", theme.food, theme.field_bg),
+        ("super_food", theme.super_food, theme.field_bg),
+        ("ui_text", theme.ui_text, theme.ui_bg),
+    ] {
+        let ratio = contrast_ratio(fg, bg).expect("preset colors should be RGB");
+        assert!(
+            ratio >= minimum,
+            "{id}: {name} contrast {ratio:.2}:1 is below {minimum}:1"
+        );
+    }
+}
+
+fn assert_distinct(id: &str, theme: &Theme, matrix: &[[f64; 3]; 3]) {
+    for (a, a_color, b, b_color) in entity_pairs(theme) {
+        let delta = delta_e(a_color, b_color, matrix);
+        assert!(
+            delta >= MIN_ENTITY_DELTA_E,
+            "{id}: {a} and {b} differ by only {delta:.1} dE"
+        );
+    }
+}
+
+fn bundled_sources() -> HashMap<String, String> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/themes");
+    let mut sources = HashMap::new();
+    for entry in fs::read_dir(&dir).expect("themes directory should be readable") {
+        let path = entry.expect("theme entry should be readable").path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let id = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .expect("theme file name should be UTF-8")
+                .to_owned();
+            let raw = fs::read_to_string(&path).expect("theme file should be readable");
+            sources.insert(id, raw);
+        }
+    }
+    sources
+}
+
+#[test]
+fn bundled_themes_parse_without_errors() {
+    let sources = bundled_sources();
+    let mut problems = Vec::new();
+    for (id, raw) in &sources {
+        let report = check_theme(id, raw, &sources);
+        problems.extend(report.errors.iter().map(|error| format!("{id}: {error}")));
+    }
+    problems.sort();
+    assert!(problems.is_empty(), "{}", problems.join("\n"));
+}
+
+#[test]
+fn bundled_themes_meet_contrast_targets_in_both_appearances() {
+    let ids: Vec<String> = bundled_sources().into_keys().collect();
+    let mut problems = Vec::new();
+    let mut used_exemptions = Vec::new();
+    for appearance in [Appearance::Dark, Appearance::Light] {
+        let catalog = ThemeCatalog::builtin(appearance);
+        for id in &ids {
+            let theme = &catalog
+                .items()
+                .iter()
+                .find(|item| &item.id == id)
+                .unwrap_or_else(|| panic!("bundled theme '{id}' should be in the catalog"))
+                .theme;
+            for (name, fg, bg, minimum) in [
+                (
+                    "snake_head",
+                    theme.snake_head,
+                    theme.field_bg,
+                    ENTITY_MIN_CONTRAST,
+                ),
+                ("food", theme.food, theme.field_bg, ENTITY_MIN_CONTRAST),
+                (
+                    "super_food",
+                    theme.super_food,
+                    theme.field_bg,
+                    ENTITY_MIN_CONTRAST,
+                ),
+                ("ui_text", theme.ui_text, theme.ui_bg, UI_TEXT_MIN_CONTRAST),
+            ] {
+                // Terminal palette colors have no fixed RGB value to measure.
+                let Some(ratio) = contrast_ratio(fg, bg) else {
+                    continue;
+                };
+                if ratio >= minimum {
+                    continue;
+                }
+                let exemption = (id.as_str(), name);
+                if CONTRAST_EXEMPTIONS.contains(&exemption) {
+                    used_exemptions.push(exemption);
+                } else {
+                    problems.push(format!(
+                        "{id} ({appearance:?}): {name} contrast {ratio:.2}:1 is below {minimum}:1"
+                    ));
+                }
+            }
+        }
+    }
+    for exemption in CONTRAST_EXEMPTIONS {
+        if !used_exemptions.contains(&exemption) {
+            problems.push(format!("{exemption:?} is exempt but meets its target"));
+        }
+    }
+    problems.sort();
+    assert!(problems.is_empty(), "{}", problems.join("\n"));
+}
+
+#[test]
+fn color_deficiency_presets_keep_entities_apart() {
+    for (id, matrix) in [
+        ("deuteranopia", DEUTERANOPIA),
+        ("protanopia", PROTANOPIA),
+        ("tritanopia", TRITANOPIA),
+    ] {
+        let theme = builtin_theme(id);
+        assert_min_contrast(id, &theme, PRESET_MIN_CONTRAST);
+        assert_distinct(id, &theme, &NORMAL_VISION);
+        assert_distinct(id, &theme, &matrix);
+    }
+}
+
+#[test]
+fn high_contrast_preset_meets_aaa_contrast() {
+    let theme = builtin_theme("high-contrast");
+    assert_min_contrast("high-contrast", &theme, HIGH_CONTRAST_MIN_CONTRAST);
+    assert_distinct("high-contrast", &theme, &NORMAL_VISION);
+}